@@ -0,0 +1,119 @@
+//! Bit-band access for Cortex-M3 and Cortex-M4 devices.
+//!
+//! On these cores, two 1MB regions of the memory map have a bit-band alias region, where
+//! each bit of the original region is mapped to a word of the alias region:
+//!
+//! | Region     | Addresses                   | Alias addresses             |
+//! |------------|-----------------------------|-----------------------------|
+//! | SRAM       | `0x2000_0000..0x2010_0000`  | `0x2200_0000..0x2400_0000`  |
+//! | Peripheral | `0x4000_0000..0x4010_0000`  | `0x4200_0000..0x4400_0000`  |
+//!
+//! Writing a 1 or 0 to an alias word sets or clears the corresponding bit in a single bus
+//! transaction, without a read-modify-write, so it cannot tear with an interrupt handler
+//! modifying another bit of the same register. Reading an alias word returns the value of
+//! the bit as 0 or 1.
+//!
+//! Registers outside these regions cannot be bit-banded, and the functions and macros in this
+//! module panic if given one. Not every Cortex-M device implements bit-banding, and Cortex-M0,
+//! Cortex-M0+, and Cortex-M7 cores do not support it at all.
+//!
+//! `bitband_set!` and `bitband_clear!` write to the alias word through a raw pointer, so they
+//! must be called in an `unsafe` block, and only on devices which implement bit-banding.
+
+const SRAM_BASE: usize = 0x2000_0000;
+const SRAM_ALIAS: usize = 0x2200_0000;
+const PERIPHERAL_BASE: usize = 0x4000_0000;
+const PERIPHERAL_ALIAS: usize = 0x4200_0000;
+const REGION_SIZE: usize = 0x10_0000;
+
+/// Returns the address of the bit-band alias word for `bit` of the word at `reg_addr`.
+///
+/// # Panics
+/// Panics if `reg_addr` is not in a bit-band region, or if `bit` is not less than 32.
+#[inline]
+pub fn bit_band_addr(reg_addr: usize, bit: u32) -> *mut u32 {
+    assert!(
+        bit < 32,
+        "bit {} is out of range for a 32-bit register",
+        bit
+    );
+    let alias = if (SRAM_BASE..SRAM_BASE + REGION_SIZE).contains(&reg_addr) {
+        SRAM_ALIAS + (reg_addr - SRAM_BASE) * 32
+    } else if (PERIPHERAL_BASE..PERIPHERAL_BASE + REGION_SIZE).contains(&reg_addr) {
+        PERIPHERAL_ALIAS + (reg_addr - PERIPHERAL_BASE) * 32
+    } else {
+        panic!("address {:#010x} is not in a bit-band region", reg_addr);
+    };
+    (alias + bit as usize * 4) as *mut u32
+}
+
+/// Set a single-bit field through its bit-band alias.
+///
+/// # Usage
+/// The arguments are the same as for `bit_set!`, but only a single field may be given. Rather
+/// than a read-modify-write of the register, the macro writes 1 to the bit-band alias word
+/// for the field, which atomically sets the bit.
+///
+/// The field must be a single bit, and the register must be in a bit-band region; see the
+/// [`bitband`](crate::bitband) module documentation. The macro panics if the register is not in
+/// a bit-band region.
+///
+/// The write to the alias word is a raw pointer write, so the macro must be called in an
+/// `unsafe` block, even with a reference to the register block:
+/// ```compile_fail
+/// # #![allow(non_upper_case_globals, non_snake_case)]
+/// # mod periph {
+/// #     pub struct RegisterBlock {
+/// #         pub MY_REG: ral_registers::RWRegister<u32>,
+/// #     }
+/// #     pub mod MY_REG {
+/// #         pub mod FLAG {
+/// #             pub const offset: u32 = 5;
+/// #             pub const mask: u32 = 1 << offset;
+/// #         }
+/// #     }
+/// # }
+/// fn set(inst: &periph::RegisterBlock) {
+///     ral_registers::bitband_set!(periph, inst, MY_REG, FLAG);
+/// }
+/// # fn main() {}
+/// ```
+///
+/// # Safety
+/// The device must implement bit-banding for the register's region, so that the alias address
+/// is the register's bit-band alias. On a device without bit-banding, such as a Cortex-M0,
+/// Cortex-M0+, or Cortex-M7, the alias address may be ordinary memory, which the macro would
+/// overwrite. The requirements of `modify_reg!` also apply.
+#[macro_export]
+macro_rules! bitband_set {
+    ( $periph:path, $instance:expr, $reg:ident $([$offset:expr])*, $field:ident ) => {
+        $crate::bitband_set!(@write $periph, $instance, $reg $([$offset])*, $field, 1)
+    };
+    ( @write $periph:path, $instance:expr, $reg:ident $([$offset:expr])*, $field:ident, $bit:expr ) => {{
+        #[allow(unused_imports)]
+        use $periph::{*};
+        let offset = $crate::field_offset!($periph, $reg, $field);
+        debug_assert!(
+            $crate::field_mask!($periph, $reg, $field) >> offset == 1,
+            "bit-band fields must be a single bit"
+        );
+        let addr = $crate::register!($periph, $instance, $reg $([$offset])*).as_ptr() as usize;
+        let alias = $crate::bitband::bit_band_addr(addr, offset);
+        ::core::ptr::write_volatile(alias, $bit);
+    }};
+}
+
+/// Clear a single-bit field through its bit-band alias.
+///
+/// # Usage
+/// As for `bitband_set!`, but the macro writes 0 to the bit-band alias word, which atomically
+/// clears the bit. The macro must also be called in an `unsafe` block.
+///
+/// # Safety
+/// As for `bitband_set!`.
+#[macro_export]
+macro_rules! bitband_clear {
+    ( $periph:path, $instance:expr, $reg:ident $([$offset:expr])*, $field:ident ) => {
+        $crate::bitband_set!(@write $periph, $instance, $reg $([$offset])*, $field, 0)
+    };
+}
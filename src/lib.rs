@@ -6,10 +6,168 @@
 //!
 //! See the [README](https://github.com/adamgreig/ral-registers/blob/master/README.md)
 //! for further details.
+//!
+//! # Fields
+//! Each field of a register is described by an item in the register's module, which the macros
+//! find by name. Most code generators emit a module with free constants, along with modules of
+//! named values for reading (`R`), writing (`W`), or both (`RW`):
+//!
+//! ```rust
+//! # #![allow(non_snake_case, non_upper_case_globals)]
+//! pub mod CR {
+//!     pub mod EN {
+//!         pub const offset: u32 = 0;
+//!         pub const mask: u32 = 1 << offset;
+//!         pub mod R {}
+//!         pub mod W {}
+//!         pub mod RW {
+//!             pub const Enabled: u32 = 1;
+//!         }
+//!     }
+//! }
+//! ```
+//!
+//! Instead of `offset` and `mask`, a field module may give the inclusive range of bits the field
+//! occupies, as `lsb` and `msb`, and define `offset` and `mask` from them with
+//! [`field_range!`].
+//!
+//! A field may instead be a type implementing [`FieldMarker`], with associated constants `MASK`
+//! and `OFFSET`:
+//!
+//! ```rust
+//! # #![allow(non_snake_case, non_camel_case_types)]
+//! pub mod CR {
+//!     pub enum EN {}
+//!     impl ral_registers::FieldMarker for EN {
+//!         type Value = u32;
+//!         const MASK: u32 = 1 << Self::OFFSET;
+//!         const OFFSET: u32 = 0;
+//!     }
+//! }
+//! ```
+//!
+//! Bits are numbered from the least significant bit, bit 0. For vendor documentation which
+//! numbers bits from the most significant bit instead, a register module may declare
+//! `pub const BIT_NUMBERING: BitOrder = BitOrder::MsbZero;`, and write each field's `offset`
+//! and `mask` in that numbering; see [`BitOrder`].
+//!
+//! A type has no `R`, `W` or `RW` modules, so a field written this way can only be used by the
+//! macros which do not import named values: the whole-register, single field and tuple forms
+//! of `read_reg!`, and macros such as `extract_field!`, `bit_set!`, `bit_clear!`,
+//! `toggle_reg!`, `clear_flags!` and `with_reg!`. Forms which take field values or compare a
+//! field, such as `write_reg!(.., EN: 1)` and `read_reg!(.., EN == 1)`, require a module.
+//!
+//! # Register arrays
+//! A register array is a `RegisterBlock` field holding an array of registers, such as
+//! `pub CCR: [RWRegister<u32>; 4]`, with a single register module `CCR` describing the fields
+//! of every element. The macros accept any number of indices after the register name, such
+//! as `CCR[i]` or `CCR[i][j]`, and each index may be any expression evaluating to `usize`.
+//!
+//! Some peripherals repeat a register at a stride larger than the register itself, such as a
+//! 4 byte register every 0x20 bytes. A code generator may describe these by padding the
+//! storage array, here with 8 registers per element, and defining an index mapping function
+//! in the register module:
+//!
+//! ```rust
+//! # #![allow(non_snake_case)]
+//! pub mod CCR {
+//!     /// Maps the index of a `CCR` register to its index in `RegisterBlock::CCR`.
+//!     pub const fn index(i: usize) -> usize {
+//!         i * 8
+//!     }
+//! }
+//! ```
+//!
+//! When the register module defines `index`, every macro passes each user-supplied index
+//! through it before indexing the storage, so `read_reg!(periph, inst, CCR[1])` reads
+//! `(*inst).CCR[8]`. The function must be named `index`, accept and return a `usize`, and be
+//! in scope via `use periph::CCR::*`. It should be a `const fn` so that the macros remain
+//! usable in `const` contexts. Bounds checks, including those of the `try_*` macros, apply to
+//! the mapped index. Register modules that do not define `index` use their indices as is.
+//!
+//! When the first index is an integer literal, such as `CCR[5]`, `read_reg!`, `write_reg!`,
+//! `modify_reg!` and `reset_reg!` check it against the length of the array at compile time, so
+//! an out of bounds literal is a compile error rather than a panic. The length is taken from
+//! the type of the `RegisterBlock` field, so a peripheral module accessed with literal indices
+//! must name its register block `RegisterBlock`. Arrays in other register blocks can be indexed
+//! with a constant, such as `CCR[CHANNEL]`, which is not a literal. The check applies to the
+//! mapped index, so with a literal index `index` must be a `const fn`. Other indices are
+//! checked when the register is accessed, and an out of bounds index panics with a message
+//! naming the array and its length, such as `CCR index 40 out of bounds (len 32)`, where the
+//! index is the mapped index. The `try_*` macros skip the compile-time check, and return an
+//! `IndexError` for an out of bounds literal as for any other index.
+//!
+//! # Layout
+//! Every register type is `#[repr(transparent)]` over its value, so a `RWRegister<u32>` has the
+//! same size and alignment as a `u32`. Register types may be embedded in `#[repr(C)]` register
+//! blocks to match the peripheral's memory map, and pointers to a value and to its register
+//! type may be cast to each other, as in [`RWRegister::from_ptr`]. The exception is
+//! [`AtomicRWRegister`], which has the layout of its atomic type, such as `AtomicU64`, and so may
+//! be more strictly aligned than its value.
+//!
+//! # Inlining
+//! The `read` and `write` methods of every register type are `#[inline(always)]`, as are the
+//! other methods which wrap a single access, such as `replace` and `update`. Each is only a
+//! volatile read or write, so a call would cost more than the access itself, and a driver's
+//! polling loop or bit-banged protocol should compile to the same instructions as the raw
+//! `read_volatile` and `write_volatile` it wraps, even without LTO or in debug builds. The
+//! macros expand to calls of these methods, so they inline in the same way. The exception is
+//! the `trace` feature, which calls its hook from every access.
+//!
+//! # Optional features
+//! * `defmt`: implement `defmt::Format` for `RWRegister` and `RORegister`.
+//! * `cortex-m`: enable the [`bitband`] module for Cortex-M bit-band regions.
+//! * `critical-section`: enable `modify_reg_cs!` and `read_pair_cs!`, using the
+//!   `critical-section` crate.
+//! * `mock`: enable the [`mock`] module of host-side mock registers. This requires `std`.
+//! * `trace`: call a hook installed with [`trace::set_hook`] on every register access, and
+//!   count accesses with [`trace::AccessCounter`].
+//! * `serde`: enable the [`snapshot`] module of serializable register snapshots.
+//! * `bytemuck`: implement `bytemuck::Zeroable` for the register types, so register blocks can
+//!   be created zeroed with `Zeroable::zeroed()`. They do not implement `Pod`, because reading
+//!   the bytes of a register through a shared reference would not be volatile.
 
 #![no_std]
 
-use core::cell::UnsafeCell;
+use core::cell::{Cell, UnsafeCell};
+use core::fmt;
+use core::marker::PhantomData;
+use core::ops::{BitAnd, BitOr, BitXor, Not, Shl, Shr};
+use core::sync::atomic::Ordering;
+
+#[cfg(feature = "cortex-m")]
+pub mod bitband;
+
+#[cfg(feature = "mock")]
+pub mod mock;
+
+#[cfg(feature = "trace")]
+pub mod trace;
+
+#[cfg(feature = "serde")]
+pub mod snapshot;
+
+#[cfg(feature = "critical-section")]
+#[doc(hidden)]
+pub use critical_section;
+
+/// Checks at compile time that each register type has the layout of its value.
+macro_rules! assert_layout {
+    ($($ty:ty),+) => {
+        $( assert_layout!(@ty $ty, RWRegister, UnsafeRWRegister, RORegister, UnsafeRORegister,
+            WORegister, UnsafeWORegister, RWRegisterBE, RORegisterBE, WORegisterBE); )+
+    };
+    (@ty $ty:ty, $($register:ident),+) => {
+        $(
+            const _: () = assert!(
+                core::mem::size_of::<$register<$ty>>() == core::mem::size_of::<$ty>()
+                    && core::mem::align_of::<$register<$ty>>() == core::mem::align_of::<$ty>()
+            );
+        )+
+    };
+}
+
+assert_layout!(u8, u16, u32, u64);
 
 /// A read-write register of type T.
 ///
@@ -28,16 +186,238 @@ pub struct RWRegister<T> {
 }
 
 impl<T: Copy> RWRegister<T> {
+    /// Creates a new register containing `value`.
+    ///
+    /// This does not access any hardware; it is intended for constructing register blocks
+    /// in host-side tests or in `static`s.
+    #[inline(always)]
+    pub const fn new(value: T) -> Self {
+        Self {
+            register: UnsafeCell::new(value),
+        }
+    }
+
     /// Reads the value of the register.
+    #[must_use]
     #[inline(always)]
     pub fn read(&self) -> T {
-        unsafe { ::core::ptr::read_volatile(self.register.get()) }
+        let val = unsafe { ::core::ptr::read_volatile(self.register.get()) };
+        #[cfg(feature = "trace")]
+        trace::record(self.register.get(), val, false);
+        val
     }
 
     /// Writes a new value to the register.
     #[inline(always)]
     pub fn write(&self, val: T) {
-        unsafe { ::core::ptr::write_volatile(self.register.get(), val) }
+        unsafe { ::core::ptr::write_volatile(self.register.get(), val) };
+        #[cfg(feature = "trace")]
+        trace::record(self.register.get(), val, true);
+    }
+
+    /// Writes a new value to the register, returning the previous value.
+    ///
+    /// This performs exactly one volatile read of the register followed by one volatile write.
+    /// It is not atomic; see the safety notes on this type.
+    #[inline(always)]
+    pub fn replace(&self, val: T) -> T {
+        let old = self.read();
+        self.write(val);
+        old
+    }
+
+    /// Writes a new value to the register, returning the value read before the write.
+    ///
+    /// This is the same as `replace`, and the method form of `read_then_write_reg!` without
+    /// fields.
+    #[must_use]
+    #[inline(always)]
+    pub fn read_write(&self, new: T) -> T {
+        self.replace(new)
+    }
+
+    /// Reads the register, passes the value to `f`, and writes the result back, returning the
+    /// value that was written.
+    ///
+    /// This performs exactly one read and one write, like the closure form of `modify_reg!`.
+    #[inline(always)]
+    pub fn update<F: FnOnce(T) -> T>(&self, f: F) -> T {
+        let val = f(self.read());
+        self.write(val);
+        val
+    }
+
+    /// Reads the register, passes the value to `f`, and writes the first element of the result
+    /// back, returning the second.
+    ///
+    /// This performs exactly one read and one write, like the closure form of `modify_reg!`
+    /// with a closure which returns a tuple.
+    #[inline(always)]
+    pub fn modify_returning<R, F: FnOnce(T) -> (T, R)>(&self, f: F) -> R {
+        let (val, output) = f(self.read());
+        self.write(val);
+        output
+    }
+
+    /// Returns a raw pointer to the register.
+    ///
+    /// This does not access the register.
+    #[inline(always)]
+    pub const fn as_ptr(&self) -> *const T {
+        self.register.get()
+    }
+
+    /// Returns a mutable raw pointer to the register.
+    ///
+    /// This does not access the register.
+    #[inline(always)]
+    pub const fn as_mut_ptr(&self) -> *mut T {
+        self.register.get()
+    }
+
+    /// Views the register at `ptr` as a `RWRegister`.
+    ///
+    /// This is the reverse of [`as_ptr`](Self::as_ptr), for registers at an address known only
+    /// at runtime, such as one from a linker symbol. This does not access the register.
+    ///
+    /// # Safety
+    /// `ptr` must point to a valid register of type `T`, aligned for `T`, for all of `'a`. The
+    /// register must not be accessed through any other path during `'a`, except through other
+    /// references to it as a register type.
+    #[inline(always)]
+    pub const unsafe fn from_ptr<'a>(ptr: *const T) -> &'a Self {
+        &*(ptr as *const Self)
+    }
+
+    /// Views the register at `ptr` as a `RWRegister`.
+    ///
+    /// This is the reverse of [`as_mut_ptr`](Self::as_mut_ptr), and otherwise the same as
+    /// [`from_ptr`](Self::from_ptr).
+    ///
+    /// # Safety
+    /// Refer to [`from_ptr`](Self::from_ptr)'s Safety section.
+    #[inline(always)]
+    pub const unsafe fn from_mut_ptr<'a>(ptr: *mut T) -> &'a Self {
+        &*(ptr as *const Self)
+    }
+
+    /// Consumes the register, returning its value.
+    ///
+    /// This does not perform a volatile read; owning the register means nothing else can
+    /// access it. It is intended for host-side tests.
+    #[inline(always)]
+    pub fn into_inner(self) -> T {
+        self.register.into_inner()
+    }
+}
+
+impl<T> RWRegister<T>
+where
+    T: Copy + BitOr<Output = T> + BitAnd<Output = T> + BitXor<Output = T> + Not<Output = T>,
+{
+    /// Sets the bits in `mask`, leaving the other bits unchanged.
+    ///
+    /// This is a single read-modify-write of the register.
+    #[inline(always)]
+    pub fn set_bits(&self, mask: T) {
+        self.write(self.read() | mask)
+    }
+
+    /// Clears the bits in `mask`, leaving the other bits unchanged.
+    ///
+    /// This is a single read-modify-write of the register.
+    #[inline(always)]
+    pub fn clear_bits(&self, mask: T) {
+        self.write(self.read() & !mask)
+    }
+
+    /// Toggles the bits in `mask`, leaving the other bits unchanged.
+    ///
+    /// This is a single read-modify-write of the register.
+    #[inline(always)]
+    pub fn toggle_bits(&self, mask: T) {
+        self.write(self.read() ^ mask)
+    }
+}
+
+impl<T> RWRegister<T>
+where
+    T: Copy
+        + BitOr<Output = T>
+        + BitAnd<Output = T>
+        + Not<Output = T>
+        + Shl<u32, Output = T>
+        + From<u8>
+        + PartialEq,
+{
+    /// Reads the register and returns bit `n`.
+    ///
+    /// # Panics
+    /// Panics if `n` is not less than the width of the register in bits.
+    #[inline(always)]
+    pub fn bit(&self, n: u32) -> bool {
+        let mask = bit_mask::<T>(n);
+        self.read() & mask != T::from(0)
+    }
+
+    /// Sets bit `n` to `value`, leaving the other bits unchanged.
+    ///
+    /// This is a single read-modify-write of the register.
+    ///
+    /// # Panics
+    /// Panics if `n` is not less than the width of the register in bits.
+    #[inline(always)]
+    pub fn set_bit(&self, n: u32, value: bool) {
+        let mask = bit_mask::<T>(n);
+        let reg = self.read();
+        self.write(if value { reg | mask } else { reg & !mask })
+    }
+}
+
+/// Returns a `T` with only bit `n` set, panicking if `n` is out of range.
+#[inline(always)]
+fn bit_mask<T: From<u8> + Shl<u32, Output = T>>(n: u32) -> T {
+    let width = 8 * core::mem::size_of::<T>() as u32;
+    assert!(
+        n < width,
+        "bit {} is out of range for a register {} bits wide",
+        n,
+        width
+    );
+    T::from(1) << n
+}
+
+/// Formats the current value of the register in hexadecimal.
+///
+/// This performs a volatile read of the register, which has side effects for some peripheral
+/// registers (for example, clearing a status flag). Avoid formatting such registers.
+impl<T: Copy + fmt::Debug> fmt::Debug for RWRegister<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "RWRegister({:#0width$x?})",
+            self.read(),
+            width = 2 + 2 * core::mem::size_of::<T>()
+        )
+    }
+}
+
+/// Formats the current value of the register with `defmt`.
+///
+/// This performs a volatile read of the register; see the `Debug` implementation.
+#[cfg(feature = "defmt")]
+impl<T: Copy + defmt::Format> defmt::Format for RWRegister<T> {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(f, "RWRegister({})", self.read())
+    }
+}
+
+/// Compares the current value of the register with a value, as in `if inst.SR == 0`.
+///
+/// Each comparison performs a volatile read of the register; see the `Debug` implementation.
+impl<T: Copy + PartialEq> PartialEq<T> for RWRegister<T> {
+    fn eq(&self, other: &T) -> bool {
+        self.read() == *other
     }
 }
 
@@ -58,13 +438,28 @@ pub struct UnsafeRWRegister<T> {
 }
 
 impl<T: Copy> UnsafeRWRegister<T> {
+    /// Creates a new register containing `value`.
+    ///
+    /// This does not access any hardware; it is intended for constructing register blocks
+    /// in host-side tests or in `static`s.
+    #[inline(always)]
+    pub const fn new(value: T) -> Self {
+        Self {
+            register: UnsafeCell::new(value),
+        }
+    }
+
     /// Reads the value of the register.
     ///
     /// # Safety
     /// Refer to [UnsafeRWRegister]'s Safety section.
+    #[must_use]
     #[inline(always)]
     pub unsafe fn read(&self) -> T {
-        ::core::ptr::read_volatile(self.register.get())
+        let val = ::core::ptr::read_volatile(self.register.get());
+        #[cfg(feature = "trace")]
+        trace::record(self.register.get(), val, false);
+        val
     }
 
     /// Writes a new value to the register.
@@ -73,13 +468,118 @@ impl<T: Copy> UnsafeRWRegister<T> {
     /// Refer to [UnsafeRWRegister]'s Safety section.
     #[inline(always)]
     pub unsafe fn write(&self, val: T) {
-        ::core::ptr::write_volatile(self.register.get(), val)
+        ::core::ptr::write_volatile(self.register.get(), val);
+        #[cfg(feature = "trace")]
+        trace::record(self.register.get(), val, true);
+    }
+
+    /// Writes a new value to the register, returning the value read before the write.
+    ///
+    /// This performs exactly one volatile read of the register followed by one volatile write,
+    /// like `RWRegister::read_write`.
+    ///
+    /// # Safety
+    /// Refer to [UnsafeRWRegister]'s Safety section.
+    #[must_use]
+    #[inline(always)]
+    pub unsafe fn read_write(&self, new: T) -> T {
+        let old = self.read();
+        self.write(new);
+        old
+    }
+
+    /// Reads the register, passes the value to `f`, and writes the result back, returning the
+    /// value that was written.
+    ///
+    /// This performs exactly one read and one write, like the closure form of `modify_reg!`.
+    ///
+    /// # Safety
+    /// Refer to [UnsafeRWRegister]'s Safety section.
+    #[inline(always)]
+    pub unsafe fn update<F: FnOnce(T) -> T>(&self, f: F) -> T {
+        let val = f(self.read());
+        self.write(val);
+        val
+    }
+
+    /// Reads the register, passes the value to `f`, and writes the first element of the result
+    /// back, returning the second.
+    ///
+    /// This performs exactly one read and one write, like the closure form of `modify_reg!`
+    /// with a closure which returns a tuple.
+    ///
+    /// # Safety
+    /// Refer to [UnsafeRWRegister]'s Safety section.
+    #[inline(always)]
+    pub unsafe fn modify_returning<R, F: FnOnce(T) -> (T, R)>(&self, f: F) -> R {
+        let (val, output) = f(self.read());
+        self.write(val);
+        output
+    }
+
+    /// Returns a raw pointer to the register.
+    ///
+    /// This does not access the register.
+    #[inline(always)]
+    pub const fn as_ptr(&self) -> *const T {
+        self.register.get()
+    }
+
+    /// Returns a mutable raw pointer to the register.
+    ///
+    /// This does not access the register.
+    #[inline(always)]
+    pub const fn as_mut_ptr(&self) -> *mut T {
+        self.register.get()
+    }
+
+    /// Views the register at `ptr` as a `UnsafeRWRegister`.
+    ///
+    /// This is the reverse of [`as_ptr`](Self::as_ptr), for registers at an address known only
+    /// at runtime, such as one from a linker symbol. This does not access the register.
+    ///
+    /// # Safety
+    /// `ptr` must point to a valid register of type `T`, aligned for `T`, for all of `'a`. The
+    /// register must not be accessed through any other path during `'a`, except through other
+    /// references to it as a register type.
+    #[inline(always)]
+    pub const unsafe fn from_ptr<'a>(ptr: *const T) -> &'a Self {
+        &*(ptr as *const Self)
+    }
+
+    /// Views the register at `ptr` as a `UnsafeRWRegister`.
+    ///
+    /// This is the reverse of [`as_mut_ptr`](Self::as_mut_ptr), and otherwise the same as
+    /// [`from_ptr`](Self::from_ptr).
+    ///
+    /// # Safety
+    /// Refer to [`from_ptr`](Self::from_ptr)'s Safety section.
+    #[inline(always)]
+    pub const unsafe fn from_mut_ptr<'a>(ptr: *mut T) -> &'a Self {
+        &*(ptr as *const Self)
+    }
+
+    /// Consumes the register, returning its value.
+    ///
+    /// This does not perform a volatile read; owning the register means nothing else can
+    /// access it. It is intended for host-side tests.
+    #[inline(always)]
+    pub fn into_inner(self) -> T {
+        self.register.into_inner()
+    }
+}
+
+/// Formats the register without reading it.
+impl<T> fmt::Debug for UnsafeRWRegister<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "UnsafeRWRegister(<unsafe>)")
     }
 }
 
 /// A read-only register of type T.
 ///
-/// Contains one value of type T and provides a volatile read function to it.
+/// Contains one value of type T and provides a volatile read function to it. There is no write
+/// function, so `write_reg!` and `modify_reg!` on a read-only register fail to compile.
 ///
 /// # Safety
 /// This register should be used where reads and writes to this peripheral register do not
@@ -93,10 +593,125 @@ pub struct RORegister<T> {
 }
 
 impl<T: Copy> RORegister<T> {
+    /// Creates a new register containing `value`.
+    ///
+    /// This does not access any hardware; it is intended for constructing register blocks
+    /// in host-side tests or in `static`s.
+    #[inline(always)]
+    pub const fn new(value: T) -> Self {
+        Self {
+            register: UnsafeCell::new(value),
+        }
+    }
+
     /// Reads the value of the register.
+    #[must_use]
     #[inline(always)]
     pub fn read(&self) -> T {
-        unsafe { ::core::ptr::read_volatile(self.register.get()) }
+        let val = unsafe { ::core::ptr::read_volatile(self.register.get()) };
+        #[cfg(feature = "trace")]
+        trace::record(self.register.get(), val, false);
+        val
+    }
+
+    /// Returns a raw pointer to the register.
+    ///
+    /// This does not access the register.
+    #[inline(always)]
+    pub const fn as_ptr(&self) -> *const T {
+        self.register.get()
+    }
+
+    /// Returns a mutable raw pointer to the register.
+    ///
+    /// This does not access the register.
+    #[inline(always)]
+    pub const fn as_mut_ptr(&self) -> *mut T {
+        self.register.get()
+    }
+
+    /// Views the register at `ptr` as a `RORegister`.
+    ///
+    /// This is the reverse of [`as_ptr`](Self::as_ptr), for registers at an address known only
+    /// at runtime, such as one from a linker symbol. This does not access the register.
+    ///
+    /// # Safety
+    /// `ptr` must point to a valid register of type `T`, aligned for `T`, for all of `'a`. The
+    /// register must not be accessed through any other path during `'a`, except through other
+    /// references to it as a register type.
+    #[inline(always)]
+    pub const unsafe fn from_ptr<'a>(ptr: *const T) -> &'a Self {
+        &*(ptr as *const Self)
+    }
+
+    /// Views the register at `ptr` as a `RORegister`.
+    ///
+    /// This is the reverse of [`as_mut_ptr`](Self::as_mut_ptr), and otherwise the same as
+    /// [`from_ptr`](Self::from_ptr).
+    ///
+    /// # Safety
+    /// Refer to [`from_ptr`](Self::from_ptr)'s Safety section.
+    #[inline(always)]
+    pub const unsafe fn from_mut_ptr<'a>(ptr: *mut T) -> &'a Self {
+        &*(ptr as *const Self)
+    }
+
+    /// Consumes the register, returning its value.
+    ///
+    /// This does not perform a volatile read; owning the register means nothing else can
+    /// access it. It is intended for host-side tests.
+    #[inline(always)]
+    pub fn into_inner(self) -> T {
+        self.register.into_inner()
+    }
+}
+
+impl<T> RORegister<T>
+where
+    T: Copy + BitAnd<Output = T> + Shl<u32, Output = T> + From<u8> + PartialEq,
+{
+    /// Reads the register and returns bit `n`.
+    ///
+    /// # Panics
+    /// Panics if `n` is not less than the width of the register in bits.
+    #[inline(always)]
+    pub fn bit(&self, n: u32) -> bool {
+        let mask = bit_mask::<T>(n);
+        self.read() & mask != T::from(0)
+    }
+}
+
+/// Formats the current value of the register in hexadecimal.
+///
+/// This performs a volatile read of the register, which has side effects for some peripheral
+/// registers (for example, clearing a status flag). Avoid formatting such registers.
+impl<T: Copy + fmt::Debug> fmt::Debug for RORegister<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "RORegister({:#0width$x?})",
+            self.read(),
+            width = 2 + 2 * core::mem::size_of::<T>()
+        )
+    }
+}
+
+/// Formats the current value of the register with `defmt`.
+///
+/// This performs a volatile read of the register; see the `Debug` implementation.
+#[cfg(feature = "defmt")]
+impl<T: Copy + defmt::Format> defmt::Format for RORegister<T> {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(f, "RORegister({})", self.read())
+    }
+}
+
+/// Compares the current value of the register with a value, as in `if inst.SR == 0`.
+///
+/// Each comparison performs a volatile read of the register; see the `Debug` implementation.
+impl<T: Copy + PartialEq> PartialEq<T> for RORegister<T> {
+    fn eq(&self, other: &T) -> bool {
+        self.read() == *other
     }
 }
 
@@ -116,19 +731,93 @@ pub struct UnsafeRORegister<T> {
 }
 
 impl<T: Copy> UnsafeRORegister<T> {
+    /// Creates a new register containing `value`.
+    ///
+    /// This does not access any hardware; it is intended for constructing register blocks
+    /// in host-side tests or in `static`s.
+    #[inline(always)]
+    pub const fn new(value: T) -> Self {
+        Self {
+            register: UnsafeCell::new(value),
+        }
+    }
+
     /// Reads the value of the register.
     ///
     /// # Safety
     /// Refer to [UnsafeRWRegister]'s Safety section.
+    #[must_use]
     #[inline(always)]
     pub unsafe fn read(&self) -> T {
-        ::core::ptr::read_volatile(self.register.get())
+        let val = ::core::ptr::read_volatile(self.register.get());
+        #[cfg(feature = "trace")]
+        trace::record(self.register.get(), val, false);
+        val
+    }
+
+    /// Returns a raw pointer to the register.
+    ///
+    /// This does not access the register.
+    #[inline(always)]
+    pub const fn as_ptr(&self) -> *const T {
+        self.register.get()
+    }
+
+    /// Returns a mutable raw pointer to the register.
+    ///
+    /// This does not access the register.
+    #[inline(always)]
+    pub const fn as_mut_ptr(&self) -> *mut T {
+        self.register.get()
+    }
+
+    /// Views the register at `ptr` as a `UnsafeRORegister`.
+    ///
+    /// This is the reverse of [`as_ptr`](Self::as_ptr), for registers at an address known only
+    /// at runtime, such as one from a linker symbol. This does not access the register.
+    ///
+    /// # Safety
+    /// `ptr` must point to a valid register of type `T`, aligned for `T`, for all of `'a`. The
+    /// register must not be accessed through any other path during `'a`, except through other
+    /// references to it as a register type.
+    #[inline(always)]
+    pub const unsafe fn from_ptr<'a>(ptr: *const T) -> &'a Self {
+        &*(ptr as *const Self)
+    }
+
+    /// Views the register at `ptr` as a `UnsafeRORegister`.
+    ///
+    /// This is the reverse of [`as_mut_ptr`](Self::as_mut_ptr), and otherwise the same as
+    /// [`from_ptr`](Self::from_ptr).
+    ///
+    /// # Safety
+    /// Refer to [`from_ptr`](Self::from_ptr)'s Safety section.
+    #[inline(always)]
+    pub const unsafe fn from_mut_ptr<'a>(ptr: *mut T) -> &'a Self {
+        &*(ptr as *const Self)
+    }
+
+    /// Consumes the register, returning its value.
+    ///
+    /// This does not perform a volatile read; owning the register means nothing else can
+    /// access it. It is intended for host-side tests.
+    #[inline(always)]
+    pub fn into_inner(self) -> T {
+        self.register.into_inner()
+    }
+}
+
+/// Formats the register without reading it.
+impl<T> fmt::Debug for UnsafeRORegister<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "UnsafeRORegister(<unsafe>)")
     }
 }
 
 /// A write-only register of type T.
 ///
-/// Contains one value of type T and provides a volatile write function to it.
+/// Contains one value of type T and provides a volatile write function to it. There is no read
+/// function, so `read_reg!` and `modify_reg!` on a write-only register fail to compile.
 ///
 /// # Safety
 /// This register should be used where writes to this peripheral register do not lead to memory
@@ -142,502 +831,4645 @@ pub struct WORegister<T> {
 }
 
 impl<T: Copy> WORegister<T> {
+    /// Creates a new register containing `value`.
+    ///
+    /// This does not access any hardware; it is intended for constructing register blocks
+    /// in host-side tests or in `static`s.
+    #[inline(always)]
+    pub const fn new(value: T) -> Self {
+        Self {
+            register: UnsafeCell::new(value),
+        }
+    }
+
     /// Writes a new value to the register.
     #[inline(always)]
     pub fn write(&self, val: T) {
-        unsafe { ::core::ptr::write_volatile(self.register.get(), val) }
+        unsafe { ::core::ptr::write_volatile(self.register.get(), val) };
+        #[cfg(feature = "trace")]
+        trace::record(self.register.get(), val, true);
     }
-}
-
-/// A write-only register of type T, where write access is unsafe.
-///
-/// Contains one value of type T and provides a volatile write function to it.
-///
-/// # Safety
-/// This register should be used where reads and writes to this peripheral may invoke
-/// undefined behaviour or memory unsafety.
-///
-/// Access to this register must be synchronised; if multiple threads (or the main thread and an
-/// interrupt service routine) are accessing it simultaneously you may encounter data races.
-#[repr(transparent)]
-pub struct UnsafeWORegister<T> {
-    register: UnsafeCell<T>,
-}
 
-impl<T: Copy> UnsafeWORegister<T> {
-    /// Writes a new value to the register.
+    /// Returns a raw pointer to the register.
+    ///
+    /// This does not access the register.
+    #[inline(always)]
+    pub const fn as_ptr(&self) -> *const T {
+        self.register.get()
+    }
+
+    /// Returns a mutable raw pointer to the register.
+    ///
+    /// This does not access the register.
+    #[inline(always)]
+    pub const fn as_mut_ptr(&self) -> *mut T {
+        self.register.get()
+    }
+
+    /// Views the register at `ptr` as a `WORegister`.
+    ///
+    /// This is the reverse of [`as_ptr`](Self::as_ptr), for registers at an address known only
+    /// at runtime, such as one from a linker symbol. This does not access the register.
+    ///
+    /// # Safety
+    /// `ptr` must point to a valid register of type `T`, aligned for `T`, for all of `'a`. The
+    /// register must not be accessed through any other path during `'a`, except through other
+    /// references to it as a register type.
+    #[inline(always)]
+    pub const unsafe fn from_ptr<'a>(ptr: *const T) -> &'a Self {
+        &*(ptr as *const Self)
+    }
+
+    /// Views the register at `ptr` as a `WORegister`.
+    ///
+    /// This is the reverse of [`as_mut_ptr`](Self::as_mut_ptr), and otherwise the same as
+    /// [`from_ptr`](Self::from_ptr).
+    ///
+    /// # Safety
+    /// Refer to [`from_ptr`](Self::from_ptr)'s Safety section.
+    #[inline(always)]
+    pub const unsafe fn from_mut_ptr<'a>(ptr: *mut T) -> &'a Self {
+        &*(ptr as *const Self)
+    }
+
+    /// Consumes the register, returning its value.
+    ///
+    /// This does not perform a volatile read; owning the register means nothing else can
+    /// access it. It is intended for host-side tests.
+    #[inline(always)]
+    pub fn into_inner(self) -> T {
+        self.register.into_inner()
+    }
+}
+
+/// Formats the register without reading it.
+impl<T> fmt::Debug for WORegister<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "WORegister(<write-only>)")
+    }
+}
+
+/// A write-only register of type T, where write access is unsafe.
+///
+/// Contains one value of type T and provides a volatile write function to it.
+///
+/// # Safety
+/// This register should be used where reads and writes to this peripheral may invoke
+/// undefined behaviour or memory unsafety.
+///
+/// Access to this register must be synchronised; if multiple threads (or the main thread and an
+/// interrupt service routine) are accessing it simultaneously you may encounter data races.
+#[repr(transparent)]
+pub struct UnsafeWORegister<T> {
+    register: UnsafeCell<T>,
+}
+
+impl<T: Copy> UnsafeWORegister<T> {
+    /// Creates a new register containing `value`.
+    ///
+    /// This does not access any hardware; it is intended for constructing register blocks
+    /// in host-side tests or in `static`s.
+    #[inline(always)]
+    pub const fn new(value: T) -> Self {
+        Self {
+            register: UnsafeCell::new(value),
+        }
+    }
+
+    /// Writes a new value to the register.
     ///
     /// # Safety
     /// Refer to [UnsafeRWRegister]'s Safety section.
     #[inline(always)]
     pub unsafe fn write(&self, val: T) {
-        ::core::ptr::write_volatile(self.register.get(), val)
+        ::core::ptr::write_volatile(self.register.get(), val);
+        #[cfg(feature = "trace")]
+        trace::record(self.register.get(), val, true);
+    }
+
+    /// Returns a raw pointer to the register.
+    ///
+    /// This does not access the register.
+    #[inline(always)]
+    pub const fn as_ptr(&self) -> *const T {
+        self.register.get()
+    }
+
+    /// Returns a mutable raw pointer to the register.
+    ///
+    /// This does not access the register.
+    #[inline(always)]
+    pub const fn as_mut_ptr(&self) -> *mut T {
+        self.register.get()
+    }
+
+    /// Views the register at `ptr` as a `UnsafeWORegister`.
+    ///
+    /// This is the reverse of [`as_ptr`](Self::as_ptr), for registers at an address known only
+    /// at runtime, such as one from a linker symbol. This does not access the register.
+    ///
+    /// # Safety
+    /// `ptr` must point to a valid register of type `T`, aligned for `T`, for all of `'a`. The
+    /// register must not be accessed through any other path during `'a`, except through other
+    /// references to it as a register type.
+    #[inline(always)]
+    pub const unsafe fn from_ptr<'a>(ptr: *const T) -> &'a Self {
+        &*(ptr as *const Self)
+    }
+
+    /// Views the register at `ptr` as a `UnsafeWORegister`.
+    ///
+    /// This is the reverse of [`as_mut_ptr`](Self::as_mut_ptr), and otherwise the same as
+    /// [`from_ptr`](Self::from_ptr).
+    ///
+    /// # Safety
+    /// Refer to [`from_ptr`](Self::from_ptr)'s Safety section.
+    #[inline(always)]
+    pub const unsafe fn from_mut_ptr<'a>(ptr: *mut T) -> &'a Self {
+        &*(ptr as *const Self)
+    }
+
+    /// Consumes the register, returning its value.
+    ///
+    /// This does not perform a volatile read; owning the register means nothing else can
+    /// access it. It is intended for host-side tests.
+    #[inline(always)]
+    pub fn into_inner(self) -> T {
+        self.register.into_inner()
+    }
+}
+
+/// Formats the register without reading it.
+impl<T> fmt::Debug for UnsafeWORegister<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "UnsafeWORegister(<write-only>)")
+    }
+}
+
+/// An integer which can be converted to and from big-endian byte order.
+///
+/// Used by the big-endian register types, `RWRegisterBE`, `RORegisterBE`, and `WORegisterBE`.
+pub trait BigEndian: Copy {
+    /// Converts a big-endian value to the native byte order.
+    fn from_be(value: Self) -> Self;
+    /// Converts a native value to big-endian byte order.
+    fn to_be(self) -> Self;
+}
+
+macro_rules! impl_big_endian {
+    ( $($ty:ty),+ ) => {
+        $(
+            impl BigEndian for $ty {
+                #[inline(always)]
+                fn from_be(value: Self) -> Self {
+                    <$ty>::from_be(value)
+                }
+                #[inline(always)]
+                fn to_be(self) -> Self {
+                    <$ty>::to_be(self)
+                }
+            }
+        )+
+    };
+}
+
+impl_big_endian!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+/// A read-write register of type T, stored in big-endian byte order.
+///
+/// For peripherals wired big-endian on a little-endian CPU (or the reverse). `read` and `write`
+/// convert between big-endian and native byte order around the volatile access, so the field
+/// macros operate on native values as usual. On a big-endian CPU, this is the same as
+/// `RWRegister`.
+///
+/// # Safety
+/// As for `RWRegister`.
+#[repr(transparent)]
+pub struct RWRegisterBE<T> {
+    register: RWRegister<T>,
+}
+
+impl<T: BigEndian> RWRegisterBE<T> {
+    /// Creates a new register containing `value`, stored in big-endian byte order.
+    ///
+    /// This does not access any hardware; it is intended for constructing register blocks
+    /// in host-side tests.
+    #[inline(always)]
+    pub fn new(value: T) -> Self {
+        Self {
+            register: RWRegister::new(value.to_be()),
+        }
+    }
+
+    /// Reads the value of the register, in native byte order.
+    #[must_use]
+    #[inline(always)]
+    pub fn read(&self) -> T {
+        T::from_be(self.register.read())
+    }
+
+    /// Writes a new value, in native byte order, to the register.
+    #[inline(always)]
+    pub fn write(&self, val: T) {
+        self.register.write(val.to_be())
+    }
+
+    /// Returns a raw pointer to the register.
+    ///
+    /// This does not access the register. Values accessed through the pointer are big-endian.
+    #[inline(always)]
+    pub const fn as_ptr(&self) -> *const T {
+        self.register.as_ptr()
+    }
+
+    /// Returns a mutable raw pointer to the register.
+    ///
+    /// This does not access the register. Values accessed through the pointer are big-endian.
+    #[inline(always)]
+    pub const fn as_mut_ptr(&self) -> *mut T {
+        self.register.as_mut_ptr()
+    }
+
+    /// Consumes the register, returning its value in native byte order.
+    ///
+    /// This does not perform a volatile read; see `RWRegister::into_inner`.
+    #[inline(always)]
+    pub fn into_inner(self) -> T {
+        T::from_be(self.register.into_inner())
+    }
+}
+
+/// Formats the current value of the register, in native byte order, in hexadecimal.
+///
+/// This performs a volatile read of the register; see the `RWRegister` implementation.
+impl<T: BigEndian + fmt::Debug> fmt::Debug for RWRegisterBE<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "RWRegisterBE({:#0width$x?})",
+            self.read(),
+            width = 2 + 2 * core::mem::size_of::<T>()
+        )
+    }
+}
+
+/// A read-only register of type T, stored in big-endian byte order.
+///
+/// See `RWRegisterBE`.
+///
+/// # Safety
+/// As for `RORegister`.
+#[repr(transparent)]
+pub struct RORegisterBE<T> {
+    register: RORegister<T>,
+}
+
+impl<T: BigEndian> RORegisterBE<T> {
+    /// Creates a new register containing `value`, stored in big-endian byte order.
+    ///
+    /// This does not access any hardware; it is intended for constructing register blocks
+    /// in host-side tests.
+    #[inline(always)]
+    pub fn new(value: T) -> Self {
+        Self {
+            register: RORegister::new(value.to_be()),
+        }
     }
+
+    /// Reads the value of the register, in native byte order.
+    #[must_use]
+    #[inline(always)]
+    pub fn read(&self) -> T {
+        T::from_be(self.register.read())
+    }
+
+    /// Returns a raw pointer to the register.
+    ///
+    /// This does not access the register. Values accessed through the pointer are big-endian.
+    #[inline(always)]
+    pub const fn as_ptr(&self) -> *const T {
+        self.register.as_ptr()
+    }
+
+    /// Returns a mutable raw pointer to the register.
+    ///
+    /// This does not access the register. Values accessed through the pointer are big-endian.
+    #[inline(always)]
+    pub const fn as_mut_ptr(&self) -> *mut T {
+        self.register.as_mut_ptr()
+    }
+
+    /// Consumes the register, returning its value in native byte order.
+    ///
+    /// This does not perform a volatile read; see `RWRegister::into_inner`.
+    #[inline(always)]
+    pub fn into_inner(self) -> T {
+        T::from_be(self.register.into_inner())
+    }
+}
+
+/// Formats the current value of the register, in native byte order, in hexadecimal.
+///
+/// This performs a volatile read of the register; see the `RWRegister` implementation.
+impl<T: BigEndian + fmt::Debug> fmt::Debug for RORegisterBE<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "RORegisterBE({:#0width$x?})",
+            self.read(),
+            width = 2 + 2 * core::mem::size_of::<T>()
+        )
+    }
+}
+
+/// A write-only register of type T, stored in big-endian byte order.
+///
+/// See `RWRegisterBE`.
+///
+/// # Safety
+/// As for `WORegister`.
+#[repr(transparent)]
+pub struct WORegisterBE<T> {
+    register: WORegister<T>,
+}
+
+impl<T: BigEndian> WORegisterBE<T> {
+    /// Creates a new register containing `value`, stored in big-endian byte order.
+    ///
+    /// This does not access any hardware; it is intended for constructing register blocks
+    /// in host-side tests.
+    #[inline(always)]
+    pub fn new(value: T) -> Self {
+        Self {
+            register: WORegister::new(value.to_be()),
+        }
+    }
+
+    /// Writes a new value, in native byte order, to the register.
+    #[inline(always)]
+    pub fn write(&self, val: T) {
+        self.register.write(val.to_be())
+    }
+
+    /// Returns a raw pointer to the register.
+    ///
+    /// This does not access the register. Values accessed through the pointer are big-endian.
+    #[inline(always)]
+    pub const fn as_ptr(&self) -> *const T {
+        self.register.as_ptr()
+    }
+
+    /// Returns a mutable raw pointer to the register.
+    ///
+    /// This does not access the register. Values accessed through the pointer are big-endian.
+    #[inline(always)]
+    pub const fn as_mut_ptr(&self) -> *mut T {
+        self.register.as_mut_ptr()
+    }
+
+    /// Consumes the register, returning its value in native byte order.
+    ///
+    /// This does not perform a volatile read; see `RWRegister::into_inner`.
+    #[inline(always)]
+    pub fn into_inner(self) -> T {
+        T::from_be(self.register.into_inner())
+    }
+}
+
+/// Formats the register without reading it.
+impl<T> fmt::Debug for WORegisterBE<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "WORegisterBE(<write-only>)")
+    }
+}
+
+/// An integer type with a matching type in `core::sync::atomic`, for `AtomicRWRegister`.
+///
+/// Implemented for the unsigned integer types where the target supports atomic
+/// compare-and-swap of that width.
+pub trait AtomicValue: Copy {
+    /// The atomic type, such as `AtomicU32` for `u32`.
+    type Atomic;
+
+    /// Creates a new atomic containing `value`.
+    fn new_atomic(value: Self) -> Self::Atomic;
+
+    /// Loads the value of `atomic`.
+    fn load(atomic: &Self::Atomic, ordering: Ordering) -> Self;
+
+    /// Stores `value` to `atomic`.
+    fn store(atomic: &Self::Atomic, value: Self, ordering: Ordering);
+
+    /// Consumes `atomic`, returning its value.
+    fn into_inner(atomic: Self::Atomic) -> Self;
+
+    /// Updates the value of `atomic` with `f`, as with `AtomicU32::fetch_update`.
+    fn fetch_update<F: FnMut(Self) -> Option<Self>>(
+        atomic: &Self::Atomic,
+        set_order: Ordering,
+        fetch_order: Ordering,
+        f: F,
+    ) -> Result<Self, Self>;
+}
+
+macro_rules! impl_atomic_value {
+    ($($ty:ty => $atomic:ident, $width:literal;)+) => {
+        $(
+            #[cfg(target_has_atomic = $width)]
+            impl AtomicValue for $ty {
+                type Atomic = core::sync::atomic::$atomic;
+                #[inline(always)]
+                fn new_atomic(value: Self) -> Self::Atomic {
+                    Self::Atomic::new(value)
+                }
+                #[inline(always)]
+                fn load(atomic: &Self::Atomic, ordering: Ordering) -> Self {
+                    atomic.load(ordering)
+                }
+                #[inline(always)]
+                fn store(atomic: &Self::Atomic, value: Self, ordering: Ordering) {
+                    atomic.store(value, ordering)
+                }
+                #[inline(always)]
+                fn into_inner(atomic: Self::Atomic) -> Self {
+                    atomic.into_inner()
+                }
+                #[inline(always)]
+                fn fetch_update<F: FnMut(Self) -> Option<Self>>(
+                    atomic: &Self::Atomic,
+                    set_order: Ordering,
+                    fetch_order: Ordering,
+                    f: F,
+                ) -> Result<Self, Self> {
+                    atomic.fetch_update(set_order, fetch_order, f)
+                }
+            }
+        )+
+    };
+}
+
+impl_atomic_value! {
+    u8 => AtomicU8, "8";
+    u16 => AtomicU16, "16";
+    u32 => AtomicU32, "32";
+    u64 => AtomicU64, "64";
+    usize => AtomicUsize, "ptr";
+}
+
+/// A read-write register of type T, accessed with atomic instructions.
+///
+/// Contains one value of type T, stored in the matching `core::sync::atomic` type, and provides
+/// atomic load, store, and read-modify-write functions to it. Unlike `RWRegister`, a `modify`
+/// cannot lose a concurrent change to the register, and every access takes an `Ordering`.
+///
+/// The field macros accept an ordering for this register type as an `ordering = ...` argument
+/// after the register, such as `read_reg!(periph, inst, REG, ordering = SeqCst, FIELD)`. With an
+/// ordering, `modify_reg!` performs an atomic `fetch_update` rather than a separate read and
+/// write. Field values are evaluated once, but a closure may be called more than once if the
+/// update is retried.
+///
+/// # Safety
+/// Atomic read-modify-write instructions, such as LDREX/STREX on Cortex-M, are only valid where
+/// the bus and the peripheral support them. Many microcontrollers do not support exclusive
+/// accesses to peripheral memory, so this type is intended for memory such as a mailbox shared
+/// with another core, or a peripheral whose reference manual documents atomic access. Atomic
+/// accesses are also not volatile: the compiler may merge or elide accesses which have no
+/// effect on the program, so this register should not be used where every read or write has
+/// a side effect.
+///
+/// Access to this register must be synchronised with accesses through other register types or
+/// pointers, as for `RWRegister`.
+#[repr(transparent)]
+pub struct AtomicRWRegister<T: AtomicValue> {
+    register: T::Atomic,
+}
+
+impl<T: AtomicValue> AtomicRWRegister<T> {
+    /// Creates a new register containing `value`.
+    ///
+    /// This does not access any hardware; it is intended for constructing register blocks
+    /// in host-side tests.
+    #[inline(always)]
+    pub fn new(value: T) -> Self {
+        Self {
+            register: T::new_atomic(value),
+        }
+    }
+
+    /// Atomically reads the value of the register.
+    ///
+    /// Panics if `ordering` is `Release` or `AcqRel`.
+    #[must_use]
+    #[inline(always)]
+    pub fn read(&self, ordering: Ordering) -> T {
+        T::load(&self.register, ordering)
+    }
+
+    /// Atomically writes a new value to the register.
+    ///
+    /// Panics if `ordering` is `Acquire` or `AcqRel`.
+    #[inline(always)]
+    pub fn write(&self, val: T, ordering: Ordering) {
+        T::store(&self.register, val, ordering)
+    }
+
+    /// Atomically updates the register with `f`, as with `AtomicU32::fetch_update`.
+    ///
+    /// `f` is called with the current value, and may be called again if the register changed
+    /// before the new value could be written. Evaluates to `Ok` of the previous value if `f`
+    /// returned `Some`, or `Err` of the current value if it returned `None`.
+    #[inline(always)]
+    pub fn fetch_update<F: FnMut(T) -> Option<T>>(
+        &self,
+        set_order: Ordering,
+        fetch_order: Ordering,
+        f: F,
+    ) -> Result<T, T> {
+        T::fetch_update(&self.register, set_order, fetch_order, f)
+    }
+
+    /// Atomically updates the register with `f`, and returns the new value.
+    ///
+    /// `ordering` applies to the write of the new value; the read uses the strongest ordering
+    /// valid for a load which is no stronger than `ordering`. `f` may be called more than once,
+    /// as for `fetch_update`.
+    #[inline(always)]
+    pub fn modify<F: FnMut(T) -> T>(&self, ordering: Ordering, mut f: F) -> T {
+        let mut new = None;
+        let _ = self.fetch_update(ordering, load_ordering(ordering), |val| {
+            let val = f(val);
+            new = Some(val);
+            new
+        });
+        // fetch_update always succeeds, since the closure always returns Some.
+        new.unwrap()
+    }
+
+    /// Returns a view of the register which accesses it with `ordering`.
+    ///
+    /// The view has `read()` and `write()` functions without an ordering argument, like
+    /// `RWRegister`, so it can be used with the field macros. The macros use it for the
+    /// `ordering = ...` argument.
+    #[inline(always)]
+    pub fn ordered(&self, ordering: Ordering) -> Ordered<'_, T> {
+        Ordered {
+            register: self,
+            ordering,
+        }
+    }
+
+    /// Consumes the register, returning its value.
+    ///
+    /// This does not perform an atomic read; see `RWRegister::into_inner`.
+    #[inline(always)]
+    pub fn into_inner(self) -> T {
+        T::into_inner(self.register)
+    }
+}
+
+/// Formats the current value of the register in hexadecimal.
+///
+/// This performs a `SeqCst` read of the register.
+impl<T: AtomicValue + fmt::Debug> fmt::Debug for AtomicRWRegister<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "AtomicRWRegister({:#0width$x?})",
+            self.read(Ordering::SeqCst),
+            width = 2 + 2 * core::mem::size_of::<T>()
+        )
+    }
+}
+
+/// An `AtomicRWRegister` accessed with a fixed `Ordering`.
+///
+/// Returned by `AtomicRWRegister::ordered`. Reads use the strongest ordering valid for a load
+/// which is no stronger than the ordering, and writes the strongest valid for a store, so a
+/// view with `AcqRel` reads with `Acquire` and writes with `Release`.
+#[derive(Clone, Copy)]
+pub struct Ordered<'a, T: AtomicValue> {
+    register: &'a AtomicRWRegister<T>,
+    ordering: Ordering,
+}
+
+impl<'a, T: AtomicValue> Ordered<'a, T> {
+    /// Atomically reads the value of the register.
+    #[must_use]
+    #[inline(always)]
+    pub fn read(&self) -> T {
+        self.register.read(load_ordering(self.ordering))
+    }
+
+    /// Atomically writes a new value to the register.
+    #[inline(always)]
+    pub fn write(&self, val: T) {
+        self.register.write(val, store_ordering(self.ordering))
+    }
+
+    /// Atomically updates the register with `f`, and returns the new value.
+    ///
+    /// See `AtomicRWRegister::modify`.
+    #[inline(always)]
+    pub fn modify<F: FnMut(T) -> T>(&self, f: F) -> T {
+        self.register.modify(self.ordering, f)
+    }
+}
+
+/// The strongest ordering valid for a load which is no stronger than `ordering`.
+fn load_ordering(ordering: Ordering) -> Ordering {
+    match ordering {
+        Ordering::Release => Ordering::Relaxed,
+        Ordering::AcqRel => Ordering::Acquire,
+        ordering => ordering,
+    }
+}
+
+/// The strongest ordering valid for a store which is no stronger than `ordering`.
+fn store_ordering(ordering: Ordering) -> Ordering {
+    match ordering {
+        Ordering::Acquire => Ordering::Relaxed,
+        Ordering::AcqRel => Ordering::Release,
+        ordering => ordering,
+    }
+}
+
+/// A register which can be safely read.
+///
+/// Implemented by `RWRegister` and `RORegister` and their big-endian variants, so that code can
+/// be generic over any of them.
+/// The unsafe register types do not implement this trait, since their reads are unsafe.
+pub trait ReadRegister<T> {
+    /// Reads the value of the register.
+    #[must_use]
+    fn read(&self) -> T;
+}
+
+/// A register which can be safely written.
+///
+/// Implemented by `RWRegister` and `WORegister` and their big-endian variants, so that code can
+/// be generic over any of them.
+/// The unsafe register types do not implement this trait, since their writes are unsafe.
+pub trait WriteRegister<T> {
+    /// Writes a new value to the register.
+    fn write(&self, val: T);
+}
+
+impl<T: Copy> ReadRegister<T> for RWRegister<T> {
+    #[inline(always)]
+    fn read(&self) -> T {
+        RWRegister::read(self)
+    }
+}
+
+impl<T: Copy> WriteRegister<T> for RWRegister<T> {
+    #[inline(always)]
+    fn write(&self, val: T) {
+        RWRegister::write(self, val)
+    }
+}
+
+impl<T: Copy> ReadRegister<T> for RORegister<T> {
+    #[inline(always)]
+    fn read(&self) -> T {
+        RORegister::read(self)
+    }
+}
+
+impl<T: Copy> WriteRegister<T> for WORegister<T> {
+    #[inline(always)]
+    fn write(&self, val: T) {
+        WORegister::write(self, val)
+    }
+}
+
+impl<T: BigEndian> ReadRegister<T> for RWRegisterBE<T> {
+    #[inline(always)]
+    fn read(&self) -> T {
+        RWRegisterBE::read(self)
+    }
+}
+
+impl<T: BigEndian> WriteRegister<T> for RWRegisterBE<T> {
+    #[inline(always)]
+    fn write(&self, val: T) {
+        RWRegisterBE::write(self, val)
+    }
+}
+
+impl<T: BigEndian> ReadRegister<T> for RORegisterBE<T> {
+    #[inline(always)]
+    fn read(&self) -> T {
+        RORegisterBE::read(self)
+    }
+}
+
+impl<T: BigEndian> WriteRegister<T> for WORegisterBE<T> {
+    #[inline(always)]
+    fn write(&self, val: T) {
+        WORegisterBE::write(self, val)
+    }
+}
+
+// Safety: each register type is `#[repr(transparent)]` over an `UnsafeCell<T>`, or over another
+// register type, and an all-zero `UnsafeCell<T>` is valid when an all-zero `T` is.
+//
+// There are intentionally no `Pod` impls. `Pod` allows reading the bytes of a shared reference,
+// such as through `bytemuck::bytes_of`, which would be a non-volatile read of a value that may
+// change behind it through `write`.
+#[cfg(feature = "bytemuck")]
+mod zeroable {
+    use super::*;
+    use bytemuck::Zeroable;
+
+    unsafe impl<T: Zeroable> Zeroable for RWRegister<T> {}
+    unsafe impl<T: Zeroable> Zeroable for UnsafeRWRegister<T> {}
+    unsafe impl<T: Zeroable> Zeroable for RORegister<T> {}
+    unsafe impl<T: Zeroable> Zeroable for UnsafeRORegister<T> {}
+    unsafe impl<T: Zeroable> Zeroable for WORegister<T> {}
+    unsafe impl<T: Zeroable> Zeroable for UnsafeWORegister<T> {}
+    unsafe impl<T: Zeroable> Zeroable for RWRegisterBE<T> {}
+    unsafe impl<T: Zeroable> Zeroable for RORegisterBE<T> {}
+    unsafe impl<T: Zeroable> Zeroable for WORegisterBE<T> {}
+}
+
+/// The error returned by the `try_*` macros when a register array index is out of bounds.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct IndexError {
+    /// The index that was supplied.
+    pub index: usize,
+    /// The length of the register array.
+    pub len: usize,
+}
+
+impl IndexError {
+    /// Returns the element of `array` at `index`, or an `IndexError`.
+    #[doc(hidden)]
+    #[inline(always)]
+    pub fn check<T>(array: &[T], index: usize) -> Result<&T, IndexError> {
+        array.get(index).ok_or(IndexError {
+            index,
+            len: array.len(),
+        })
+    }
+}
+
+impl fmt::Display for IndexError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "register index {} out of bounds (len {})",
+            self.index, self.len
+        )
+    }
+}
+
+/// The error returned by `write_reg_checked!` when a field value does not fit in its field.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FieldOverflow {
+    /// The name of the register module.
+    pub register: &'static str,
+    /// The name of the first field whose value did not fit.
+    pub field: &'static str,
+    /// The width of the field, in bits.
+    pub width: u32,
+}
+
+impl fmt::Display for FieldOverflow {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "value does not fit field {}::{} ({} bits)",
+            self.register, self.field, self.width
+        )
+    }
+}
+
+/// The error returned by `wait_reg!` and `poll_reg!` when their condition is not met within
+/// the iteration limit.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Timeout;
+
+impl fmt::Display for Timeout {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "timed out waiting for register")
+    }
+}
+
+/// The decoded fields of a register, returned by `dump_reg!`.
+///
+/// Both the `Display` and `Debug` implementations list each field and its value in
+/// hexadecimal, for example `MY_ARRAY[1] { FIELD_A: 0x7f, FIELD_B: 0x3 }`.
+#[derive(Clone, Copy)]
+pub struct RegisterDump<T, const I: usize, const F: usize> {
+    name: &'static str,
+    indices: [usize; I],
+    fields: [(&'static str, T); F],
+}
+
+impl<T, const I: usize, const F: usize> RegisterDump<T, I, F> {
+    #[doc(hidden)]
+    pub const fn new(
+        name: &'static str,
+        indices: [usize; I],
+        fields: [(&'static str, T); F],
+    ) -> Self {
+        RegisterDump {
+            name,
+            indices,
+            fields,
+        }
+    }
+
+    /// Returns the name of the register.
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Returns the array indices of the register, or an empty slice for scalar registers.
+    pub fn indices(&self) -> &[usize] {
+        &self.indices
+    }
+
+    /// Returns the name and decoded value of each field.
+    pub fn fields(&self) -> &[(&'static str, T)] {
+        &self.fields
+    }
+}
+
+impl<T: fmt::LowerHex, const I: usize, const F: usize> fmt::Display for RegisterDump<T, I, F> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name)?;
+        for index in self.indices.iter() {
+            write!(f, "[{}]", index)?;
+        }
+        write!(f, " {{")?;
+        for (i, (name, value)) in self.fields.iter().enumerate() {
+            let sep = if i == 0 { "" } else { "," };
+            write!(f, "{} {}: {:#x}", sep, name, value)?;
+        }
+        write!(f, " }}")
+    }
+}
+
+impl<T: fmt::LowerHex, const I: usize, const F: usize> fmt::Debug for RegisterDump<T, I, F> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+/// The description of one field of a register, listed by `RegisterFields`.
+///
+/// This struct is `#[non_exhaustive]`, so that more information may be added later; create it
+/// with `FieldInfo::new`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct FieldInfo {
+    /// The name of the field, such as `"FIELD_A"`.
+    pub name: &'static str,
+    /// The offset of the field's lowest bit.
+    pub offset: u32,
+    /// The mask of the field within the register, widened to `u64`.
+    pub mask: u64,
+}
+
+impl FieldInfo {
+    /// Creates the description of a field.
+    pub const fn new(name: &'static str, offset: u32, mask: u64) -> Self {
+        Self { name, offset, mask }
+    }
+
+    /// Extracts the value of this field from a register value, as `extract_field!` does.
+    pub const fn extract(&self, value: u64) -> u64 {
+        (value & self.mask) >> self.offset
+    }
+}
+
+/// A list of the fields of a register, for tools which handle registers generically.
+///
+/// A register module has no type to implement a trait for, so `impl_register_fields!` defines
+/// a `Fields` type in the register module which implements this trait. A debugger or a register
+/// pretty-printer can then be generic over `R: RegisterFields`:
+/// ```rust
+/// use ral_registers::RegisterFields;
+///
+/// fn print_fields<R: RegisterFields>(value: u64) {
+///     for field in R::FIELDS {
+///         println!("{}.{} = {:#x}", R::NAME, field.name, field.extract(value));
+///     }
+/// }
+/// ```
+pub trait RegisterFields {
+    /// The name of the register, such as `"MY_ARRAY"`.
+    const NAME: &'static str;
+
+    /// The fields of the register, in the order they were listed.
+    const FIELDS: &'static [FieldInfo];
+}
+
+/// A register value displayed as a table of its fields, returned by `field_table!`.
+///
+/// The `Display` implementation writes the register's name and value, followed by one line for
+/// each field in `R::FIELDS`, with the field names aligned:
+/// ```text
+/// MY_ARRAY[1] = 0x1000002a
+///   FIELD_A = 42 (0x2a)
+///   FIELD_B = 2 (0x2)
+/// ```
+/// Formatting does not allocate, so the table may be written to any `core::fmt::Write`, such as
+/// a serial console.
+pub struct FieldTable<R, const I: usize> {
+    indices: [usize; I],
+    value: u64,
+    fields: PhantomData<R>,
+}
+
+impl<R: RegisterFields, const I: usize> FieldTable<R, I> {
+    #[doc(hidden)]
+    pub fn new(_fields: R, indices: [usize; I], value: u64) -> Self {
+        FieldTable {
+            indices,
+            value,
+            fields: PhantomData,
+        }
+    }
+
+    /// Returns the array indices of the register, or an empty slice for scalar registers.
+    pub fn indices(&self) -> &[usize] {
+        &self.indices
+    }
+
+    /// Returns the register value, widened to `u64`.
+    pub fn value(&self) -> u64 {
+        self.value
+    }
+}
+
+impl<R: RegisterFields, const I: usize> fmt::Display for FieldTable<R, I> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", R::NAME)?;
+        for index in self.indices.iter() {
+            write!(f, "[{}]", index)?;
+        }
+        write!(f, " = {:#x}", self.value)?;
+        let width = R::FIELDS.iter().map(|field| field.name.len()).max();
+        for field in R::FIELDS {
+            let value = field.extract(self.value);
+            write!(
+                f,
+                "\n  {:width$} = {} ({:#x})",
+                field.name,
+                value,
+                value,
+                width = width.unwrap_or(0)
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Implement `RegisterFields` for a register module.
+///
+/// # Usage
+/// Code generators emit this macro inside each register module, after the field modules. The
+/// arguments are the name of the register module, and a list of its fields in square brackets:
+/// ```rust
+/// # #![allow(non_upper_case_globals, non_snake_case)]
+/// pub mod MY_REG {
+///     pub mod FIELD_A {
+///         pub const offset: u32 = 0;
+///         pub const mask: u32 = 0xF << offset;
+///     }
+///     pub mod FIELD_B {
+///         pub const offset: u32 = 4;
+///         pub const mask: u32 = 1 << offset;
+///     }
+///
+///     ral_registers::impl_register_fields!(MY_REG, [FIELD_A, FIELD_B]);
+/// }
+///
+/// use ral_registers::RegisterFields;
+/// assert_eq!(MY_REG::Fields::FIELDS[1].name, "FIELD_B");
+/// ```
+///
+/// The macro defines a unit struct `Fields` in the register module, which implements
+/// `RegisterFields` with the given name and each field's `offset` and `mask`.
+#[macro_export]
+macro_rules! impl_register_fields {
+    ( @bits $bits:ident, $field:ident ) => {{
+        #[allow(unused_imports)]
+        use $crate::FieldConsts as _;
+        #[allow(unused_imports)]
+        use $crate::BIT_NUMBERING;
+        {
+            #[allow(unused_imports)]
+            use self::*;
+            $crate::field_bits!($bits, $field, BIT_NUMBERING)
+        }
+    }};
+    ( $reg:ident, [ $( $field:ident ),* $(,)? ] ) => {
+        /// The fields of this register, for `RegisterFields`.
+        pub struct Fields;
+
+        impl $crate::RegisterFields for Fields {
+            const NAME: &'static str = stringify!($reg);
+            #[allow(clippy::unnecessary_cast)]
+            const FIELDS: &'static [$crate::FieldInfo] = &[
+                $( $crate::FieldInfo::new(
+                    stringify!($field),
+                    $crate::impl_register_fields!(@bits offset, $field),
+                    $crate::impl_register_fields!(@bits mask, $field) as u64,
+                ) ),*
+            ];
+        }
+    };
+}
+
+/// Define `offset` and `mask` for a field module from its `lsb` and `msb`.
+///
+/// # Usage
+/// A field module may give the inclusive range of bits the field occupies, as `lsb` and `msb`
+/// constants of type `u32`, rather than computing its `mask` by hand. Emit this macro in the
+/// module, with the value type of the register, to define `offset` and `mask` from them:
+/// ```rust
+/// # #![allow(non_upper_case_globals, non_snake_case)]
+/// pub mod FIELD_A {
+///     pub const lsb: u32 = 0;
+///     pub const msb: u32 = 6;
+///     ral_registers::field_range!(u32);
+///     pub mod R {}
+///     pub mod W {}
+///     pub mod RW {}
+/// }
+///
+/// assert_eq!(FIELD_A::offset, 0);
+/// assert_eq!(FIELD_A::mask, 0x7F);
+/// ```
+///
+/// The macros read fields through `offset` and `mask`, so a module written this way works
+/// with all of them. Compilation fails if `msb` is less than `lsb`, or does not fit in the
+/// value type.
+#[macro_export]
+macro_rules! field_range {
+    ( $value:ty ) => {
+        #[allow(non_upper_case_globals, clippy::unnecessary_cast)]
+        pub const offset: $value = lsb as $value;
+        #[allow(non_upper_case_globals)]
+        pub const mask: $value = (<$value>::MAX >> (<$value>::BITS - 1 - (msb - lsb))) << offset;
+
+        const _: () = assert!(
+            lsb <= msb && msb < <$value>::BITS,
+            concat!(
+                "bit range of field ",
+                module_path!(),
+                " does not fit its register"
+            ),
+        );
+    };
+}
+
+/// Declare the module for a register array, with fields given as bit ranges.
+///
+/// # Usage
+/// Code generators may emit this macro in a peripheral module, in place of writing out the
+/// register module by hand. The arguments are the name of the register array, its register
+/// type, value type and length, and its fields, each with the inclusive range of bits it
+/// occupies:
+/// ```rust
+/// # #![allow(non_upper_case_globals, non_snake_case)]
+/// mod periph {
+///     ral_registers::register_array! {
+///         MY_ARRAY: RWRegister<u32>[3],
+///         fields { FIELD_A: 0..=6, FIELD_B: 27..=28 }
+///     }
+///
+///     #[repr(C)]
+///     pub struct RegisterBlock {
+///         pub MY_ARRAY: MY_ARRAY::Array,
+///     }
+/// }
+///
+/// assert_eq!(periph::MY_ARRAY::FIELD_B::offset, 27);
+/// assert_eq!(periph::MY_ARRAY::FIELD_B::mask, 0b11 << 27);
+/// ```
+///
+/// The macro expands to a module named after the register array, containing:
+/// * `Array`, the type of the array, here `[RWRegister<u32>; 3]`. A macro cannot expand to a
+///   field of a struct, so use this type for the array's field of the `RegisterBlock`,
+/// * a module for each field, with `lsb` and `msb` constants, `offset` and `mask` constants of
+///   the value type from `field_range!`, and empty `R`, `W`, and `RW` modules,
+/// * `Fields`, as from `impl_register_fields!`.
+///
+/// The register type is one of the register types of this crate, such as `RWRegister` or
+/// `RORegister`. Compilation fails if a bit range does not fit in the value type, or if two
+/// fields overlap, as for `assert_no_overlap!`.
+#[macro_export]
+macro_rules! register_array {
+    (
+        $reg:ident : $kind:ident < $value:ty > [ $len:expr ],
+        fields { $( $field:ident : $lo:literal ..= $hi:literal ),* $(,)? } $(,)?
+    ) => {
+        #[allow(non_snake_case)]
+        pub mod $reg {
+            /// The type of the register array.
+            pub type Array = [$crate::$kind<$value>; $len];
+
+            $(
+                #[allow(non_snake_case)]
+                pub mod $field {
+                    #[allow(non_upper_case_globals)]
+                    pub const lsb: u32 = $lo;
+                    #[allow(non_upper_case_globals)]
+                    pub const msb: u32 = $hi;
+                    $crate::field_range!($value);
+                    pub mod R {}
+                    pub mod W {}
+                    pub mod RW {}
+                }
+            )*
+
+            $crate::impl_register_fields!($reg, [ $( $field ),* ]);
+            $crate::assert_no_overlap!(super, $reg, [ $( $field ),* ]);
+        }
+    };
+}
+
+/// Check, at compile time, that the masks of a register's fields do not overlap.
+///
+/// # Usage
+/// Code generators may emit this macro once for each register. The arguments are the path to
+/// the peripheral module, the register, and a list of its fields in square brackets:
+/// ```rust
+/// # #![allow(non_upper_case_globals, non_snake_case)]
+/// mod periph {
+///     pub mod MY_REG {
+///         pub mod FIELD_A {
+///             pub const offset: u32 = 0;
+///             pub const mask: u32 = 0xF << offset;
+///         }
+///         pub mod FIELD_B {
+///             pub const offset: u32 = 4;
+///             pub const mask: u32 = 1 << offset;
+///         }
+///     }
+///
+///     ral_registers::assert_no_overlap!(self, MY_REG, [FIELD_A, FIELD_B]);
+/// }
+/// # fn main() {}
+/// ```
+///
+/// The macro expands to a `const` item which compares the mask of each field with the mask of
+/// every later field. If any two masks share a bit, compilation fails with an error naming both
+/// fields. Fields may be modules or `FieldMarker` types.
+#[macro_export]
+#[rustfmt::skip]
+macro_rules! assert_no_overlap {
+    ( @pairs $periph:path, $reg:ident, [ ] ) => {};
+    ( @pairs $periph:path, $reg:ident, [ $first:ident $(, $rest:ident )* ] ) => {
+        $(
+            assert!(
+                $crate::field_mask!($periph, $reg, $first) & $crate::field_mask!($periph, $reg, $rest) == 0,
+                concat!(
+                    "fields ", stringify!($reg), "::", stringify!($first), " and ",
+                    stringify!($reg), "::", stringify!($rest), " overlap",
+                ),
+            );
+        )*
+        $crate::assert_no_overlap!(@pairs $periph, $reg, [ $( $rest ),* ]);
+    };
+    ( $periph:path, $reg:ident, [ $( $field:ident ),* $(,)? ] ) => {
+        const _: () = {
+            $crate::assert_no_overlap!(@pairs $periph, $reg, [ $( $field ),* ]);
+        };
+    };
+}
+
+/// A field of a register written as a type, rather than as a module with `mask` and `offset`.
+///
+/// See "Fields" in the crate documentation.
+pub trait FieldMarker {
+    /// The type of the register, such as `u32`.
+    type Value;
+
+    /// The mask of the field, in place.
+    const MASK: Self::Value;
+
+    /// The offset of the field's lowest bit.
+    const OFFSET: u32;
+}
+
+/// Gives every `FieldMarker` type the `mask` and `offset` of a field module.
+///
+/// With this trait in scope, `FIELD::mask` resolves whether `FIELD` is a module or a type. See
+/// `field_mask!`.
+#[doc(hidden)]
+#[allow(non_upper_case_globals)]
+pub trait FieldConsts {
+    type Value;
+    const mask: Self::Value;
+    const offset: u32;
+}
+
+impl<F: FieldMarker> FieldConsts for F {
+    type Value = F::Value;
+    const mask: F::Value = F::MASK;
+    const offset: u32 = F::OFFSET;
+}
+
+/// A field of a register, as a value, for `CachedRegister`.
+///
+/// `field!` creates one from a field's module, and `with_reg!` defines one for each field it is
+/// given.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Field<T> {
+    /// The mask of the field, in place.
+    pub mask: T,
+    /// The offset of the field's lowest bit.
+    pub offset: u32,
+}
+
+impl<T> Field<T> {
+    /// Create a field from its `mask` and `offset`.
+    pub const fn new(mask: T, offset: u32) -> Self {
+        Field { mask, offset }
+    }
+}
+
+/// A register value read once by `with_reg!`, whose fields may be read and written.
+///
+/// Setting a field only changes this value; `with_reg!` writes it to the register once
+/// afterwards if any field was set.
+///
+/// It also builds register values from fields chosen at runtime, such as a table of
+/// `(Field, value)` pairs, which is then written with one access:
+/// ```rust
+/// # #![allow(non_snake_case, non_upper_case_globals)]
+/// # mod periph {
+/// #     pub mod CR {
+/// #         pub mod EN { pub const offset: u32 = 0; pub const mask: u32 = 1 << offset; }
+/// #         pub mod MODE { pub const offset: u32 = 4; pub const mask: u32 = 0b11 << offset; }
+/// #     }
+/// # }
+/// use ral_registers::{field, CachedRegister, RWRegister};
+///
+/// let table = [(field!(periph, CR, MODE), 0b10), (field!(periph, CR, EN), 1)];
+/// let mut value = CachedRegister::new(0);
+/// value.set_fields(table.iter().copied());
+///
+/// let cr = RWRegister::new(0);
+/// cr.write(value.value());
+/// assert_eq!(cr.read(), 0b10 << 4 | 1);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CachedRegister<T> {
+    value: T,
+    modified: bool,
+}
+
+impl<T> CachedRegister<T>
+where
+    T: Copy + BitAnd<Output = T> + BitOr<Output = T> + Not<Output = T>,
+    T: Shl<u32, Output = T> + Shr<u32, Output = T>,
+{
+    /// Create a cached register holding `value`, which has not been modified.
+    pub const fn new(value: T) -> Self {
+        CachedRegister {
+            value,
+            modified: false,
+        }
+    }
+
+    /// Get the value of `field`, shifted down to start at bit 0.
+    pub fn get(&self, field: Field<T>) -> T {
+        (self.value & field.mask) >> field.offset
+    }
+
+    /// Set `field` to `value`. Bits of `value` which do not fit in the field are discarded.
+    pub fn set(&mut self, field: Field<T>, value: T) {
+        self.value = (self.value & !field.mask) | ((value << field.offset) & field.mask);
+        self.modified = true;
+    }
+
+    /// Set each field to its value, in order, as if by calling `set` for each pair.
+    pub fn set_fields<I: IntoIterator<Item = (Field<T>, T)>>(&mut self, fields: I) {
+        for (field, value) in fields {
+            self.set(field, value);
+        }
+    }
+
+    /// The whole register value, including any fields which have been set.
+    pub fn value(&self) -> T {
+        self.value
+    }
+
+    /// Whether any field has been set.
+    pub fn is_modified(&self) -> bool {
+        self.modified
+    }
+}
+
+/// A write-only register, together with a copy of the last value written to it.
+///
+/// A write-only register cannot be read, so `modify_reg!` cannot be used with it. A `Shadow`
+/// keeps the last value written through it, and `read` returns that value without accessing
+/// the register. This lets `read_reg_ref!` and `modify_reg_ref!` work on the shadow, with each
+/// modify computed against the copy and then written to the register.
+///
+/// The copy is only as accurate as the writes made through the shadow. If the register is
+/// written some other way, or the hardware changes its value, for example by clearing a bit
+/// once an operation completes, the copy no longer matches the register.
+///
+/// The register type defaults to `WORegister<T>`, and may be any type implementing
+/// `WriteRegister<T>`.
+///
+/// # Examples
+/// ```rust
+/// # #![allow(non_snake_case, non_upper_case_globals)]
+/// # mod periph {
+/// #     pub mod CR {
+/// #         pub mod EN { pub const offset: u32 = 0; pub const mask: u32 = 1 << offset;
+/// #             pub mod R {} pub mod W {} pub mod RW {} }
+/// #         pub mod MODE { pub const offset: u32 = 4; pub const mask: u32 = 0b11 << offset;
+/// #             pub mod R {} pub mod W {} pub mod RW {} }
+/// #     }
+/// # }
+/// use ral_registers::{modify_reg_ref, Shadow, WORegister};
+///
+/// let cr = WORegister::new(0);
+/// let shadow = Shadow::new(&cr, 0);
+/// modify_reg_ref!(periph, CR, &shadow, MODE: 0b10);
+/// modify_reg_ref!(periph, CR, &shadow, EN: 1);
+/// assert_eq!(shadow.read(), 0b10 << 4 | 1);
+/// ```
+pub struct Shadow<'a, T, R = WORegister<T>> {
+    register: &'a R,
+    value: Cell<T>,
+}
+
+impl<'a, T: Copy, R: WriteRegister<T>> Shadow<'a, T, R> {
+    /// Create a shadow of `register`, whose current value is `value`, such as its reset value.
+    ///
+    /// The register is not written.
+    pub const fn new(register: &'a R, value: T) -> Self {
+        Shadow {
+            register,
+            value: Cell::new(value),
+        }
+    }
+
+    /// The last value written through the shadow, or the value it was created with.
+    ///
+    /// This does not access the register.
+    #[must_use]
+    #[inline(always)]
+    pub fn read(&self) -> T {
+        self.value.get()
+    }
+
+    /// Write `val` to the register, and keep it as the shadow's value.
+    #[inline(always)]
+    pub fn write(&self, val: T) {
+        self.register.write(val);
+        self.value.set(val);
+    }
+
+    /// The register which this shadows.
+    pub fn register(&self) -> &'a R {
+        self.register
+    }
+}
+
+/// Formats the shadow's value in hexadecimal. The register is not accessed.
+impl<T: Copy + fmt::Debug, R> fmt::Debug for Shadow<'_, T, R> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Shadow({:#0width$x?})",
+            self.value.get(),
+            width = 2 + 2 * core::mem::size_of::<T>()
+        )
+    }
+}
+
+/// Get a reference to a register block, such as a peripheral's `RegisterBlock`, at `base`.
+///
+/// This is the `&*(base as *const T)` cast which maps a `#[repr(C)]` register block over its
+/// memory mapped registers. Debug builds panic if `base` is not aligned for `T`. See also
+/// `peripheral!`, which checks the alignment of a constant address at compile time.
+///
+/// # Safety
+/// `base` must be the address of a `T` which is valid for the `'static` lifetime, such as the
+/// base address of a peripheral whose registers are described by `T`. As for `Instance`, the
+/// caller is responsible for coordinating access to the registers with other references to
+/// them.
+#[inline(always)]
+#[track_caller]
+pub const unsafe fn peripheral<T>(base: usize) -> &'static T {
+    debug_assert!(
+        base & (core::mem::align_of::<T>() - 1) == 0,
+        "peripheral base address is not aligned for its register block"
+    );
+    &*(base as *const T)
+}
+
+/// Visits a register, or each register of a one-dimensional register array, for `reset_all!`.
+///
+/// `(&&Each(&register)).each(f)` resolves to `EachElement` for arrays, and otherwise falls back
+/// to `EachScalar` through one more auto-dereference. `f` is called with the index of each
+/// register, which is zero for a scalar register.
+#[doc(hidden)]
+pub struct Each<'a, R>(pub &'a R);
+
+#[doc(hidden)]
+pub trait EachElement {
+    type Element;
+    fn each<F: FnMut(usize, &Self::Element)>(&self, f: F);
+}
+
+impl<R, const N: usize> EachElement for &Each<'_, [R; N]> {
+    type Element = R;
+    fn each<F: FnMut(usize, &R)>(&self, mut f: F) {
+        self.0
+            .iter()
+            .enumerate()
+            .for_each(|(index, register)| f(index, register))
+    }
+}
+
+#[doc(hidden)]
+pub trait EachScalar {
+    type Element;
+    fn each<F: FnMut(usize, &Self::Element)>(&self, f: F);
+}
+
+impl<R> EachScalar for Each<'_, R> {
+    type Element = R;
+    fn each<F: FnMut(usize, &R)>(&self, mut f: F) {
+        f(0, self.0)
+    }
+}
+
+/// A reset value, which may be one value for every element of a register array, or an array
+/// of values with one for each element.
+///
+/// `(&&ResetValue(&value)).at(index)` resolves to `ResetElement` for an array of values, and
+/// selects the value at `index`. Otherwise it falls back to `ResetScalar` through one more
+/// auto-dereference, and the value is the same at every index.
+#[doc(hidden)]
+pub struct ResetValue<'a, T>(pub &'a T);
+
+impl<T: Copy> ResetValue<'_, T> {
+    #[inline(always)]
+    pub fn value(&self) -> T {
+        *self.0
+    }
+}
+
+#[doc(hidden)]
+pub trait ResetElement<'a> {
+    type Element;
+    fn at(&self, index: usize) -> ResetValue<'a, Self::Element>;
+}
+
+impl<'a, T, const N: usize> ResetElement<'a> for &ResetValue<'a, [T; N]> {
+    type Element = T;
+    #[inline(always)]
+    #[track_caller]
+    fn at(&self, index: usize) -> ResetValue<'a, T> {
+        ResetValue(&self.0[index])
+    }
+}
+
+#[doc(hidden)]
+pub trait ResetScalar<'a> {
+    type Element;
+    fn at(&self, index: usize) -> ResetValue<'a, Self::Element>;
+}
+
+impl<'a, T> ResetScalar<'a> for ResetValue<'a, T> {
+    type Element = T;
+    #[inline(always)]
+    fn at(&self, _: usize) -> ResetValue<'a, T> {
+        ResetValue(self.0)
+    }
+}
+
+/// The barrier issued by `write_reg_barrier!` and `modify_reg_barrier!`.
+#[doc(hidden)]
+#[inline(always)]
+pub fn barrier() {
+    #[cfg(all(feature = "cortex-m", target_arch = "arm"))]
+    // Safety: DMB only orders memory accesses.
+    unsafe {
+        core::arch::asm!("dmb", options(nostack, preserves_flags))
+    };
+    core::sync::atomic::compiler_fence(core::sync::atomic::Ordering::SeqCst);
+}
+
+/// Returns `value` unchanged, so that the macros which read a register warn, like the `read`
+/// methods, if the value they read is unused.
+#[doc(hidden)]
+#[inline(always)]
+#[must_use = "the value read from the register is unused; assign it to `_` to discard it"]
+pub fn must_use<T>(value: T) -> T {
+    value
+}
+
+/// Indexes a register array, panicking with the array's name if `index` is out of bounds.
+///
+/// The message reads like `MY_ARRAY index 42 out of bounds (len 3)`. The index is the index
+/// into the array's storage, after any `index` mapping.
+#[doc(hidden)]
+#[inline(always)]
+#[track_caller]
+pub fn index_register<'a, R>(array: &'a [R], index: usize, name: &'static str) -> &'a R {
+    match array.get(index) {
+        Some(register) => register,
+        None => panic!(
+            "{} index {} out of bounds (len {})",
+            name,
+            index,
+            array.len()
+        ),
+    }
+}
+
+/// Displays a register's name with the values of its indices, such as `MY_ARRAY[2]`, for
+/// `assert_reset!`.
+#[doc(hidden)]
+pub struct RegisterName<'a>(pub &'static str, pub &'a [usize]);
+
+impl fmt::Display for RegisterName<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.0)?;
+        for index in self.1 {
+            write!(f, "[{}]", index)?;
+        }
+        Ok(())
+    }
+}
+
+/// How a register module numbers the bits of its fields.
+///
+/// A register module declares its numbering with a `BIT_NUMBERING` constant. Without one, the
+/// fields of the register are numbered `LsbZero`. With `MsbZero`, each field's `offset` is the
+/// number of its most significant bit counted from the register's most significant bit, and its
+/// `mask` is the field's bits shifted left by that `offset`, just as the field would be written
+/// for `LsbZero` numbering. The macros translate both to the least significant bit numbering of
+/// the register before using them:
+///
+/// ```rust
+/// # #![allow(non_snake_case, non_upper_case_globals)]
+/// pub mod CR {
+///     use ral_registers::BitOrder;
+///     pub const BIT_NUMBERING: BitOrder = BitOrder::MsbZero;
+///
+///     /// Bits 0 and 1, the two most significant bits of the register.
+///     pub mod MODE {
+///         pub const offset: u32 = 0;
+///         pub const mask: u32 = 0b11 << offset;
+///         pub mod R {}
+///         pub mod W {}
+///         pub mod RW {}
+///     }
+/// }
+/// ```
+///
+/// Here `MODE` is read and written as bits 31 and 30 of the register. Modules which name their
+/// fields' bits with `lsb` and `msb` for `field_range!` always use `LsbZero` numbering.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BitOrder {
+    /// Bit 0 is the least significant bit of the register.
+    LsbZero,
+    /// Bit 0 is the most significant bit of the register.
+    MsbZero,
+}
+
+/// The default `BitOrder` of a register module which does not declare one.
+///
+/// See "Fields" in the crate documentation.
+#[doc(hidden)]
+pub const BIT_NUMBERING: BitOrder = BitOrder::LsbZero;
+
+/// Translates the `offset` of a `BitOrder::MsbZero` field of `bits` bits, in a register `width`
+/// bits wide, to the offset of its least significant bit.
+#[doc(hidden)]
+#[inline(always)]
+#[track_caller]
+pub const fn msb_zero_offset(width: u32, offset: u32, bits: u32) -> u32 {
+    assert!(
+        offset < width && bits <= width - offset,
+        "field does not fit the register"
+    );
+    width - offset - bits
+}
+
+/// The default mapping from a register array index to an index into its storage.
+///
+/// See "Register arrays" in the crate documentation.
+#[doc(hidden)]
+#[inline(always)]
+pub const fn identity_index(index: usize) -> usize {
+    index
+}
+
+/// Checks, at compile time, that a field's `mask` and `offset` describe a field of the register.
+///
+/// `width` is the bit width of the mask's type, which is also the register's type. The field
+/// must start inside the register, and the mask must be non-empty with no bits below `offset`.
+#[doc(hidden)]
+pub const fn check_field(mask: u64, offset: u32, width: u32) {
+    assert!(offset < width, "field offset is outside the register");
+    assert!(mask != 0, "field mask is empty");
+    assert!(
+        mask.trailing_zeros() >= offset,
+        "field mask has bits below the field offset"
+    );
+}
+
+/// Returns `value`, constraining its type to the type of `_like`.
+///
+/// Used for field values whose type would otherwise not be inferred, such as the results of the
+/// `modify_reg!` field closure.
+#[doc(hidden)]
+#[inline(always)]
+pub fn same_type<T>(_like: T, value: T) -> T {
+    value
+}
+
+/// The result of a `modify_reg!` closure: either the new value of the register, or a tuple of
+/// the new value and a value for the macro to evaluate to.
+#[doc(hidden)]
+pub trait ModifyOutput<T> {
+    type Output;
+    fn split(self) -> (T, Self::Output);
+}
+
+impl<T: Copy> ModifyOutput<T> for T {
+    type Output = T;
+    #[inline(always)]
+    fn split(self) -> (T, T) {
+        (self, self)
+    }
+}
+
+impl<T, R> ModifyOutput<T> for (T, R) {
+    type Output = R;
+    #[inline(always)]
+    fn split(self) -> (T, R) {
+        self
+    }
+}
+
+/// Splits the result of a `modify_reg!` closure into the value to write and the value to
+/// return, using the value read from the register, `_old`, to fix the register's type.
+#[doc(hidden)]
+#[inline(always)]
+pub fn split_modify<T, M: ModifyOutput<T>>(_old: &T, result: M) -> (T, M::Output) {
+    result.split()
+}
+
+/// Calls `f` with `value`.
+///
+/// Gives the `with_reg!` closure its parameter type, so that methods may be called on the
+/// parameter without a type annotation.
+#[doc(hidden)]
+#[inline(always)]
+pub fn call_mut<T, R, F: FnOnce(&mut T) -> R>(f: F, value: &mut T) -> R {
+    f(value)
+}
+
+/// Sign-extends a field value, for `read_signed_field!`.
+#[doc(hidden)]
+pub trait SignExtend: Copy {
+    /// The signed integer type of the same width.
+    type Signed;
+
+    /// Interprets the low `width` bits of `self` as a two's-complement value.
+    fn sign_extend(self, width: u32) -> Self::Signed;
+}
+
+macro_rules! impl_sign_extend {
+    ($($ty:ty => $signed:ty;)+) => {
+        $(
+            impl SignExtend for $ty {
+                type Signed = $signed;
+
+                #[inline(always)]
+                fn sign_extend(self, width: u32) -> $signed {
+                    let shift = <$ty>::BITS - width;
+                    ((self << shift) as $signed) >> shift
+                }
+            }
+        )+
+    };
+}
+
+impl_sign_extend! {
+    u8 => i8;
+    u16 => i16;
+    u32 => i32;
+    u64 => i64;
+    usize => isize;
+}
+
+/// The bit width of `T`, for `check_field`.
+#[doc(hidden)]
+pub const fn bit_width<T>(_: &T) -> u32 {
+    (core::mem::size_of::<T>() * 8) as u32
+}
+
+/// Write to a RWRegister or UnsafeRWRegister.
+///
+/// # Examples
+/// ```rust,no_run
+/// # use stm32ral::{read_reg, write_reg, modify_reg, reset_reg}; fn main() {
+/// // Safely acquire the peripheral instance (will panic if already acquired)
+/// let gpioa = stm32ral::gpio::GPIOA::take().unwrap();
+///
+/// // Write some value to the register.
+/// write_reg!(stm32ral::gpio, gpioa, ODR, 1<<3);
+///
+/// // Write values to specific fields. Unspecified fields are written to 0.
+/// write_reg!(stm32ral::gpio, gpioa, MODER, MODER3: Output, MODER4: Analog);
+///
+/// // Unsafe access without requiring you to first `take()` the instance
+/// unsafe { write_reg!(stm32ral::gpio, GPIOA, MODER, MODER3: Output, MODER4: Analog) };
+/// # }
+/// ```
+///
+/// # Usage
+/// Like `modify_reg!`, this macro can be used in two ways, either with a single value to write to
+/// the whole register, or with multiple fields each with their own value.
+///
+/// In both cases, the first arguments are:
+/// * the path to the peripheral module: `stm32ral::gpio`,
+/// * a reference to the instance of that peripheral: 'gpioa' (anything which dereferences to
+///   `RegisterBlock`, such as `Instance`, `&Instance`, `&RegisterBlock`, or
+///   `*const RegisterBlock`),
+/// * the register you wish you access: `MODER` (a field on the `RegisterBlock`). For register
+///   arrays, follow the register with one index per dimension: `ARRAY[i]` or `MATRIX[i][j]`.
+///
+/// In the single-value usage, the final argument is just the value to write:
+/// ```rust,no_run
+/// # use stm32ral::{read_reg, write_reg, modify_reg, reset_reg}; fn main() {
+/// # let gpioa = stm32ral::gpio::GPIOA::take().unwrap();
+/// // Turn on PA3 (and turn everything else off).
+/// write_reg!(stm32ral::gpio, gpioa, ODR, 1<<3);
+/// # }
+/// ```
+///
+/// Otherwise, the remaining arguments are each `Field: Value` pairs:
+/// ```rust,no_run
+/// # use stm32ral::{read_reg, write_reg, modify_reg, reset_reg}; fn main() {
+/// // Set PA3 to Output, PA4 to Analog, and everything else to 0 (which is Input).
+/// # let gpioa = stm32ral::gpio::GPIOA::take().unwrap();
+/// write_reg!(stm32ral::gpio, gpioa, MODER, MODER3: 0b01, MODER4: 0b11);
+/// # }
+/// ```
+/// For fields with annotated values, you can also specify a named value:
+/// ```rust,no_run
+/// # use stm32ral::{read_reg, write_reg, modify_reg, reset_reg}; fn main() {
+/// // As above, but with named values.
+/// # let gpioa = stm32ral::gpio::GPIOA::take().unwrap();
+/// write_reg!(stm32ral::gpio, gpioa, MODER, MODER3: Output, MODER4: Analog);
+/// # }
+/// ```
+///
+/// The fields may be preceded by `base = value`, to start from that value rather than zero.
+/// The bits of each named field are cleared in the base, and the field values are written
+/// over them. This is still a single write, and the register is not read:
+/// ```rust,no_run
+/// # use ral_registers::write_reg; fn main() {
+/// # let gpioa = stm32ral::gpio::GPIOA::take().unwrap();
+/// // Set every pin to Analog, except PA3, which is an Output.
+/// write_reg!(stm32ral::gpio, gpioa, MODER, base = 0xFFFF_FFFF, MODER3: 0b01);
+/// # }
+/// ```
+///
+/// This macro expands to calling `(*$instance).$register.write(value)`,
+/// where in the second usage, the value is computed as the bitwise OR of
+/// each field value, which are masked and shifted appropriately for the given field.
+/// The named values are brought into scope by
+/// `use $peripheral::$register::$field::{W::*, RW::*}` for each field, so any constant a
+/// generator emits in a field's `W` or `RW` module may be used as a value.
+/// In debug builds, the macro panics if a field value does not fit in its field before
+/// shifting; in release builds the excess bits are masked off.
+/// The same constants could just be specified manually:
+/// ```rust,no_run
+/// # use stm32ral::{read_reg, write_reg, modify_reg, reset_reg}; fn main() {
+/// // As above, but being explicit about named values.
+/// # let gpioa = stm32ral::gpio::GPIOA::take().unwrap();
+/// write_reg!(stm32ral::gpio, gpioa, MODER, MODER3: stm32ral::gpio::MODER::MODER3::RW::Output,
+///                                          MODER4: stm32ral::gpio::MODER::MODER4::RW::Analog);
+/// # }
+/// ```
+///
+/// The fully expanded form is equivalent to:
+/// ```rust,no_run
+/// # use stm32ral::{read_reg, write_reg, modify_reg, reset_reg}; fn main() {
+/// // As above, but expanded.
+/// # let gpioa = stm32ral::gpio::GPIOA::take().unwrap();
+/// (*gpioa).MODER.write(
+///     ((stm32ral::gpio::MODER::MODER3::RW::Output << stm32ral::gpio::MODER::MODER3::offset)
+///      & stm32ral::gpio::MODER::MODER3::mask)
+///     |
+///     ((stm32ral::gpio::MODER::MODER4::RW::Analog << stm32ral::gpio::MODER::MODER4::offset)
+///      & stm32ral::gpio::MODER::MODER4::mask)
+/// );
+/// # }
+/// ```
+///
+/// Either way, the register is written exactly once, with one `core::ptr::write_volatile` of
+/// the whole value, and the macro issues no fences or barrier instructions. Volatile accesses
+/// are not reordered with respect to each other by the compiler, so consecutive `write_reg!`s
+/// to different registers are emitted in program order. Ordinary memory accesses may still be
+/// moved across them, and the hardware may buffer or reorder accesses on the bus. Where that
+/// matters, use `write_reg_fenced!` or `write_reg_barrier!` instead.
+///
+/// For an `AtomicRWRegister`, an ordering is given after the register, as in
+/// `write_reg!(periph, inst, REG, ordering = SeqCst, FIELD: 1)`, and the write is an atomic store.
+///
+/// # Safety
+/// This macro will require an unsafe function or block when used with an UnsafeRWRegister,
+/// but not if used with RWRegister.
+///
+/// When run in an unsafe context, peripheral instances are directly accessible without requiring
+/// having called `take()` beforehand:
+/// ```rust,no_run
+/// # use stm32ral::{read_reg, write_reg, modify_reg, reset_reg}; fn main() {
+/// unsafe { write_reg!(stm32ral::gpio, GPIOA, MODER, MODER3: Output, MODER4: Analog) };
+/// # }
+/// ```
+/// This works because `GPIOA` is a `*const RegisterBlock` in the `stm32ral::gpio` module;
+/// and the macro brings such constants into scope and then dereferences the provided reference.
+#[macro_export]
+macro_rules! write_reg {
+    ( @place $periph:path, $reg:ident, ($($place:tt)*), base = $base:expr, $( $field:ident : $value:expr ),+ ) => {{
+        #[allow(unused_imports)]
+        use $periph::{*};
+        let base = $base;
+        let mask = $($crate::field_mask!($periph, $reg, $field)) | *;
+        ($($place)*).write((base & !mask) | $crate::build_value!(@checked $periph, $reg, $( $field : $value ),+));
+    }};
+    ( @place $periph:path, $reg:ident, ($($place:tt)*), $( $field:ident : $value:expr ),+ ) => {{
+        #[allow(unused_imports)]
+        use $periph::{*};
+        ($($place)*).write($crate::build_value!(@checked $periph, $reg, $( $field : $value ),+));
+    }};
+    ( @place $periph:path, $reg:ident, ($($place:tt)*), $value:expr ) => {{
+        #[allow(unused_imports)]
+        use $periph::{*};
+        ($($place)*).write($value);
+    }};
+    ( $periph:path, $instance:expr, $reg:ident [$index:literal] $($rest:tt)* ) => {{
+        $crate::check_literal_index!($periph, $reg, $index);
+        $crate::write_reg!($periph, $instance, $reg [($index)] $($rest)*)
+    }};
+    ( $periph:path, $instance:expr, $reg:ident $([$offset:expr])*, ordering = $ordering:expr, $($rest:tt)+ ) => {
+        $crate::write_reg!(@place $periph, $reg, ($crate::register!($periph, $instance, $reg $([$offset])*).ordered($ordering)), $($rest)+)
+    };
+    ( $periph:path, $instance:expr, $reg:ident $([$offset:expr])*, $($rest:tt)+ ) => {
+        $crate::write_reg!(@place $periph, $reg, ($crate::register!($periph, $instance, $reg $([$offset])*)), $($rest)+)
+    };
+}
+
+/// Write fields of a RWRegister or UnsafeRWRegister, clamping each value to its field.
+///
+/// # Examples
+/// ```rust,no_run
+/// # use ral_registers::write_reg_saturating; fn main() {
+/// # let gpioa = stm32ral::gpio::GPIOA::take().unwrap();
+/// # let speed = 7;
+/// // Values above 0b11 are written as 0b11, the highest speed.
+/// write_reg_saturating!(stm32ral::gpio, gpioa, OSPEEDR, OSPEEDR3: speed);
+/// # }
+/// ```
+///
+/// # Usage
+/// The arguments are the same as the field form of `write_reg!`. Each field value which is
+/// larger than the field's maximum, `mask >> offset`, is replaced by that maximum, so a value
+/// computed at runtime never spills into other fields or panics. The macro evaluates to the
+/// value written.
+///
+/// # Safety
+/// As for `write_reg!`.
+#[macro_export]
+macro_rules! write_reg_saturating {
+    ( $periph:path, $instance:expr, $reg:ident $([$offset:expr])*, $( $field:ident : $value:expr ),+ ) => {{
+        #[allow(unused_imports)]
+        use $periph::{*};
+        let value = $crate::build_value!(@saturating $periph, $reg, $( $field : $value ),+);
+        $crate::register!($periph, $instance, $reg $([$offset])*).write(value);
+        value
+    }};
+}
+
+/// Write fields of a RWRegister or UnsafeRWRegister, if every value fits in its field.
+///
+/// # Examples
+/// ```rust,no_run
+/// # use ral_registers::write_reg_checked; fn main() {
+/// # let gpioa = stm32ral::gpio::GPIOA::take().unwrap();
+/// # let speed = 7;
+/// match write_reg_checked!(stm32ral::gpio, gpioa, OSPEEDR, OSPEEDR3: speed) {
+///     Ok(_) => { /* written */ }
+///     Err(err) => { /* `speed` is too large, and nothing was written */ }
+/// }
+/// # }
+/// ```
+///
+/// # Usage
+/// The arguments are the same as the field form of `write_reg!`. Every field value is checked
+/// against the field's width before the write. If they all fit, the register is written and the
+/// macro evaluates to `Ok` of the value written; otherwise the register is not written, and it
+/// evaluates to `Err(FieldOverflow)` naming the first field which did not fit. Every value
+/// expression is evaluated either way.
+///
+/// # Safety
+/// As for `write_reg!`.
+#[macro_export]
+macro_rules! write_reg_checked {
+    ( $periph:path, $instance:expr, $reg:ident $([$offset:expr])*, $( $field:ident : $value:expr ),+ ) => {{
+        #[allow(unused_imports)]
+        use $periph::{*};
+        let mut overflow = None;
+        let value = $({
+            #[allow(unused_imports)]
+            use $periph::{$reg::$field::{W::*, RW::*}};
+            $crate::check_field!($periph, $reg, $field);
+            let offset = $crate::field_offset!($periph, $reg, $field);
+            let mask = $crate::field_mask!($periph, $reg, $field);
+            let value = $crate::same_type(mask, $value);
+            if overflow.is_none() && value & !(mask >> offset) != 0 {
+                overflow = Some($crate::FieldOverflow {
+                    register: stringify!($reg),
+                    field: stringify!($field),
+                    width: (mask >> offset).count_ones(),
+                });
+            }
+            (value << offset) & mask
+        }) | *;
+        match overflow {
+            None => {
+                $crate::register!($periph, $instance, $reg $([$offset])*).write(value);
+                Ok(value)
+            }
+            Some(overflow) => Err(overflow),
+        }
+    }};
+}
+
+/// Write to a RWRegister or UnsafeRWRegister, keeping the bits which must be preserved.
+///
+/// # Examples
+/// ```rust,ignore
+/// pub mod CR {
+///     /// Bits 8 to 15 are reserved, and must be written back as read.
+///     pub const preserve_mask: u32 = 0xFF << 8;
+///     // ... field modules ...
+/// }
+///
+/// // Write EN and MODE, zero every other field, and keep bits 8 to 15.
+/// write_reg_preserve!(periph, inst, CR, EN: 1, MODE: 0b10);
+/// ```
+///
+/// # Usage
+/// Some registers have bits, often reserved bits, which must be written with the value they
+/// are read as. For these, the register module declares a `preserve_mask` constant of the
+/// register's type, with a 1 for each bit to preserve. This macro requires `preserve_mask`;
+/// register modules which do not need it omit it.
+///
+/// The arguments are the same as for `write_reg!`. The macro reads the register, keeps the
+/// bits under `preserve_mask`, and writes them combined with the value `write_reg!` would
+/// write, whose bits under `preserve_mask` are ignored. It evaluates to the value written.
+/// Unlike `modify_reg!`, fields which are not named are written as zero.
+///
+/// # Safety
+/// As for `modify_reg!`.
+#[macro_export]
+macro_rules! write_reg_preserve {
+    ( @place $periph:path, $reg:ident, ($($place:tt)*), $( $field:ident : $value:expr ),+ ) => {
+        $crate::write_reg_preserve!(@place $periph, $reg, ($($place)*),
+            $crate::build_value!(@checked $periph, $reg, $( $field : $value ),+))
+    };
+    ( @place $periph:path, $reg:ident, ($($place:tt)*), $value:expr ) => {{
+        #[allow(unused_imports)]
+        use $periph::{*};
+        let preserve = { use $periph::{$reg::preserve_mask}; preserve_mask };
+        let value = (($($place)*).read() & preserve) | ($value & !preserve);
+        ($($place)*).write(value);
+        value
+    }};
+    ( $periph:path, $instance:expr, $reg:ident $([$offset:expr])*, $($rest:tt)+ ) => {
+        $crate::write_reg_preserve!(@place $periph, $reg, ($crate::register!($periph, $instance, $reg $([$offset])*)), $($rest)+)
+    };
+}
+
+/// Modify a RWRegister or UnsafeRWRegister.
+///
+/// # Examples
+/// ```rust,no_run
+/// # use stm32ral::{read_reg, write_reg, modify_reg, reset_reg}; fn main() {
+/// // Safely acquire the peripheral instance (will panic if already acquired)
+/// let gpioa = stm32ral::gpio::GPIOA::take().unwrap();
+///
+/// // Update the register to ensure bit 3 is set.
+/// modify_reg!(stm32ral::gpio, gpioa, ODR, |reg| reg | (1<<3));
+///
+/// // Write values to specific fields. Unspecified fields are left unchanged.
+/// modify_reg!(stm32ral::gpio, gpioa, MODER, MODER3: Output, MODER4: Analog);
+///
+/// // Unsafe access without requiring you to first `take()` the instance
+/// unsafe { modify_reg!(stm32ral::gpio, GPIOA, MODER, MODER3: Output, MODER4: Analog) };
+/// # }
+/// ```
+///
+/// # Usage
+/// Like `write_reg!`, this macro can be used in two ways, either with a modification of the entire
+/// register, or by specifying which fields to change and what value to change them to.
+///
+/// In both cases, the first arguments are:
+/// * the path to the peripheral module: `stm32ral::gpio`,
+/// * a reference to the instance of that peripheral: 'gpioa' (anything which dereferences to
+///   `RegisterBlock`, such as `Instance`, `&Instance`, `&RegisterBlock`, or
+///   `*const RegisterBlock`),
+/// * the register you wish you access: `MODER` (a field on the `RegisterBlock`). For register
+///   arrays, follow the register with one index per dimension: `ARRAY[i]` or `MATRIX[i][j]`.
+///
+/// In the whole-register usage, the final argument is a closure that accepts the current value
+/// of the register and returns the new value to write:
+/// ```rust,no_run
+/// # use stm32ral::{read_reg, write_reg, modify_reg, reset_reg}; fn main() {
+/// # let gpioa = stm32ral::gpio::GPIOA::take().unwrap();
+/// // Turn on PA3 without affecting anything else.
+/// modify_reg!(stm32ral::gpio, gpioa, ODR, |reg| reg | (1<<3));
+/// # }
+/// ```
+///
+/// Otherwise, the remaining arguments are `Field: Value` pairs:
+/// ```rust,no_run
+/// # use stm32ral::{read_reg, write_reg, modify_reg, reset_reg}; fn main() {
+/// # let gpioa = stm32ral::gpio::GPIOA::take().unwrap();
+/// // Set PA3 to Output, PA4 to Analog, and leave everything else unchanged.
+/// modify_reg!(stm32ral::gpio, gpioa, MODER, MODER3: 0b01, MODER4: 0b11);
+/// # }
+/// ```
+///
+/// For fields with annotated values, you can also specify a named value:
+/// ```rust,no_run
+/// # use stm32ral::{read_reg, write_reg, modify_reg, reset_reg}; fn main() {
+/// # let gpioa = stm32ral::gpio::GPIOA::take().unwrap();
+/// // As above, but with named values.
+/// modify_reg!(stm32ral::gpio, gpioa, MODER, MODER3: Output, MODER4: Analog);
+/// # }
+/// ```
+///
+/// Finally, the remaining argument can be a list of fields in square brackets, then `=>` and
+/// a closure. The closure takes the current value of each field, in the order listed, and
+/// returns the new value of each field: a single value for one field, or a tuple for several.
+/// Bits outside the listed fields are unchanged. Named values are not brought into scope
+/// inside the closure:
+/// ```rust,no_run
+/// # use ral_registers::modify_reg; fn main() {
+/// # let gpioa = stm32ral::gpio::GPIOA::take().unwrap();
+/// // Swap the modes of PA3 and PA4.
+/// modify_reg!(stm32ral::gpio, gpioa, MODER, [MODER3, MODER4] => |pa3, pa4| (pa4, pa3));
+///
+/// // Step up the speed of PA3.
+/// modify_reg!(stm32ral::gpio, gpioa, OSPEEDR, [OSPEEDR3] => |speed| if speed < 0b11 { speed + 1 } else { speed });
+///
+/// // Calling methods on the field values needs a type annotation.
+/// modify_reg!(stm32ral::gpio, gpioa, OSPEEDR, [OSPEEDR4] => |speed: u32| speed.saturating_sub(1));
+/// # }
+/// ```
+///
+/// The new value of a field can also be a closure, which takes the current value of that field
+/// and returns its new value. If one field is given a closure, every field must be. This is the
+/// same as the previous form, so the register is read and written once however many fields are
+/// listed:
+/// ```rust,no_run
+/// # use ral_registers::modify_reg; fn main() {
+/// # let gpioa = stm32ral::gpio::GPIOA::take().unwrap();
+/// // Make PA3 an input, and invert the mode bits of PA4.
+/// modify_reg!(stm32ral::gpio, gpioa, MODER, MODER3: |_| 0, MODER4: |pa4: u32| pa4 ^ 0b11);
+/// # }
+/// ```
+///
+/// The closure may instead return a tuple of the new value and any other value, which the
+/// macro evaluates to in place of the new value. This returns something learned from the
+/// value read, without reading the register again:
+/// ```rust,no_run
+/// # use ral_registers::modify_reg; fn main() {
+/// # let gpioa = stm32ral::gpio::GPIOA::take().unwrap();
+/// // Turn on PA3, and find out whether it was already on.
+/// let was_on = modify_reg!(stm32ral::gpio, gpioa, ODR, |reg| (reg | (1<<3), reg & (1<<3) != 0));
+/// # }
+/// ```
+///
+/// A closure given with an `ordering`, for an `AtomicRWRegister`, must return the new value.
+///
+/// In every other usage, the macro evaluates to the new value written to the register:
+/// ```rust,no_run
+/// # use stm32ral::{read_reg, write_reg, modify_reg, reset_reg}; fn main() {
+/// # let gpioa = stm32ral::gpio::GPIOA::take().unwrap();
+/// // Turn on PA3, and keep the new value of ODR.
+/// let odr = modify_reg!(stm32ral::gpio, gpioa, ODR, |reg| reg | (1<<3));
+/// # }
+/// ```
+///
+/// This macro expands to calling `(*instance).register.write(value)`, and evaluates to the
+/// `value` that was written. When called with a closure, `(*instance).register.read()` is
+/// called, the result passed in to the closure, and the return value of the closure is used
+/// for `value`.
+/// When called with `Field: Value` arguments, the current value is read and then masked
+/// according to the specified fields, and then ORd with the OR of each field value,
+/// each masked and shifted appropriately for the field. The field closure form is the same,
+/// with the field values returned by the closure. As for `write_reg!`, debug builds panic
+/// if a field value does not fit in its field. The named values are brought into scope
+/// by `use peripheral::register::field::{W::*, RW::*}` for each field. The same constants could
+/// just be specified manually:
+/// ```rust,no_run
+/// # use stm32ral::{read_reg, write_reg, modify_reg, reset_reg}; fn main() {
+/// # let gpioa = stm32ral::gpio::GPIOA::take().unwrap();
+/// // As above, but being explicit about named values.
+/// modify_reg!(stm32ral::gpio, gpioa, MODER, MODER3: stm32ral::gpio::MODER::MODER3::RW::Output,
+///                                           MODER4: stm32ral::gpio::MODER::MODER4::RW::Analog);
+/// # }
+/// ```
+///
+/// The fully expanded form is equivalent to:
+/// ```rust,no_run
+/// # use stm32ral::{read_reg, write_reg, modify_reg, reset_reg}; fn main() {
+/// # let gpioa = stm32ral::gpio::GPIOA::take().unwrap();
+/// // As above, but expanded.
+/// (*gpioa).MODER.write(
+///     (
+///         // First read the current value...
+///         (*gpioa).MODER.read()
+///         // Then AND it with an appropriate mask...
+///         &
+///         !( stm32ral::gpio::MODER::MODER3::mask | stm32ral::gpio::MODER::MODER4::mask )
+///     )
+///     // Then OR with each field value.
+///     |
+///         ((stm32ral::gpio::MODER::MODER3::RW::Output << stm32ral::gpio::MODER::MODER3::offset)
+///          & stm32ral::gpio::MODER::MODER3::mask)
+///     |
+///         ((stm32ral::gpio::MODER::MODER4::RW::Analog << stm32ral::gpio::MODER::MODER3::offset)
+///          & stm32ral::gpio::MODER::MODER3::mask)
+/// );
+/// # }
+/// ```
+///
+/// To combine new bits with the current value of a field, rather than replace it, write the
+/// field followed by `|=`, `&=`, or `^=` and a value, as in a compound assignment. The current
+/// value of each field is ORed, ANDed, or XORed with its value, within the single
+/// read-modify-write, and bits outside the listed fields are unchanged. Every field in the
+/// list takes one of these operators, and named values are in scope as for `Field: Value`:
+/// ```rust,no_run
+/// # use ral_registers::modify_reg; fn main() {
+/// # let gpioa = stm32ral::gpio::GPIOA::take().unwrap();
+/// // Make PA3 analog from any mode, and clear the high mode bit of PA4.
+/// modify_reg!(stm32ral::gpio, gpioa, MODER, MODER3 |= Analog, MODER4 &= 0b01);
+/// # }
+/// ```
+///
+/// Some registers need other fields cleared in the same read-modify-write that sets a field.
+/// List the fields to clear after `clear:`, separated by `|`, and the fields to set after
+/// `set:`, as for the `Field: Value` form. The macro reads the register once, clears every
+/// listed field, ORs in the values of the `set:` fields, and writes the result once:
+/// ```rust,no_run
+/// # use ral_registers::modify_reg; fn main() {
+/// # let gpioa = stm32ral::gpio::GPIOA::take().unwrap();
+/// // Clear MODER3 and MODER4, and set MODER5 to Output.
+/// modify_reg!(stm32ral::gpio, gpioa, MODER, clear: MODER3 | MODER4, set: MODER5: Output);
+/// # }
+/// ```
+///
+/// For an `AtomicRWRegister`, an ordering is given after the register, as in
+/// `modify_reg!(periph, inst, REG, ordering = SeqCst, FIELD: 1)`, and the modify is a single
+/// atomic `fetch_update`; see `AtomicRWRegister`.
+///
+/// # Safety
+/// This macro will require an unsafe function or block when used with an UnsafeRWRegister,
+/// but not if used with RWRegister.
+///
+/// When run in an unsafe context, peripheral instances are directly accessible without requiring
+/// having called `take()` beforehand:
+/// ```rust,no_run
+/// # use stm32ral::{read_reg, write_reg, modify_reg, reset_reg}; fn main() {
+/// unsafe { modify_reg!(stm32ral::gpio, GPIOA, MODER, MODER3: Output, MODER4: Analog) };
+/// # }
+/// ```
+/// This works because `GPIOA` is a `*const RegisterBlock` in the `stm32ral::gpio` module;
+/// and the macro brings such constants into scope and then dereferences the provided reference.
+#[macro_export]
+macro_rules! modify_reg {
+    ( @op $periph:path, $reg:ident, $old:ident, $field:ident |= $value:expr ) => {
+        ($crate::extract_field!($periph, $reg, $field, $old) | ($value))
+    };
+    ( @op $periph:path, $reg:ident, $old:ident, $field:ident &= $value:expr ) => {
+        ($crate::extract_field!($periph, $reg, $field, $old) & ($value))
+    };
+    ( @op $periph:path, $reg:ident, $old:ident, $field:ident ^= $value:expr ) => {
+        ($crate::extract_field!($periph, $reg, $field, $old) ^ ($value))
+    };
+    ( @compound place $periph:path, $reg:ident, ($($place:tt)*), $( $field:ident $op:tt $value:expr ),+ ) => {{
+        #[allow(unused_imports)]
+        use $periph::{*};
+        let reg = ($($place)*).read();
+        let value = (reg & !( $($crate::field_mask!($periph, $reg, $field)) | * ))
+            | $crate::build_value!(@checked $periph, $reg, $( $field : $crate::modify_reg!(@op $periph, $reg, reg, $field $op $value) ),+);
+        ($($place)*).write(value);
+        value
+    }};
+    ( @compound ordered $periph:path, $reg:ident, ($($place:tt)*), $( $field:ident $op:tt $value:expr ),+ ) => {{
+        #[allow(unused_imports)]
+        use $periph::{*};
+        ($($place)*).modify(|reg| {
+            (reg & !( $($crate::field_mask!($periph, $reg, $field)) | * ))
+                | $crate::build_value!(@checked $periph, $reg, $( $field : $crate::modify_reg!(@op $periph, $reg, reg, $field $op $value) ),+)
+        })
+    }};
+    ( @$mode:ident $periph:path, $reg:ident, ($($place:tt)*), $field:ident |= $($rest:tt)+ ) => {
+        $crate::modify_reg!(@compound $mode $periph, $reg, ($($place)*), $field |= $($rest)+)
+    };
+    ( @$mode:ident $periph:path, $reg:ident, ($($place:tt)*), $field:ident &= $($rest:tt)+ ) => {
+        $crate::modify_reg!(@compound $mode $periph, $reg, ($($place)*), $field &= $($rest)+)
+    };
+    ( @$mode:ident $periph:path, $reg:ident, ($($place:tt)*), $field:ident ^= $($rest:tt)+ ) => {
+        $crate::modify_reg!(@compound $mode $periph, $reg, ($($place)*), $field ^= $($rest)+)
+    };
+    ( @place $periph:path, $reg:ident, ($($place:tt)*), clear: $( $clear:ident )|+, set: $( $field:ident : $value:expr ),+ ) => {{
+        #[allow(unused_imports)]
+        use $periph::{*};
+        let mask = $($crate::field_mask!($periph, $reg, $clear)) | * | $($crate::field_mask!($periph, $reg, $field)) | *;
+        let value = (($($place)*).read() & !mask)
+            | $crate::build_value!(@checked $periph, $reg, $( $field : $value ),+);
+        ($($place)*).write(value);
+        value
+    }};
+    ( @place $periph:path, $reg:ident, ($($place:tt)*), $( $field:ident : |$old:tt $(: $ty:ty)?| $new:expr ),+ ) => {
+        $crate::modify_reg!(@place $periph, $reg, ($($place)*), [ $( $field ),+ ] => |$( $old $(: $ty)? ),+| ( $( $new ),+ ))
+    };
+    ( @place $periph:path, $reg:ident, ($($place:tt)*), $( $field:ident : $value:expr ),+ ) => {{
+        #[allow(unused_imports)]
+        use $periph::{*};
+        let value = (($($place)*).read() & !( $($crate::field_mask!($periph, $reg, $field)) | * ))
+            | $crate::build_value!(@checked $periph, $reg, $( $field : $value ),+);
+        ($($place)*).write(value);
+        value
+    }};
+    ( @place $periph:path, $reg:ident, ($($place:tt)*), [ $( $field:ident ),+ ] => $fn:expr ) => {{
+        #[allow(unused_imports)]
+        use $periph::{*};
+        let reg = ($($place)*).read();
+        #[allow(non_snake_case, unused_parens)]
+        let ( $( $field ),+ ) = $fn( $( $crate::extract_field!($periph, $reg, $field, reg) ),+ );
+        let value = (reg & !( $($crate::field_mask!($periph, $reg, $field)) | * ))
+            | $crate::build_value!(@checked $periph, $reg, $( $field : $field ),+);
+        ($($place)*).write(value);
+        value
+    }};
+    ( @place $periph:path, $reg:ident, ($($place:tt)*), $fn:expr ) => {{
+        #[allow(unused_imports)]
+        use $periph::{*};
+        let old = ($($place)*).read();
+        let (value, output) = $crate::split_modify(&old, $fn(old));
+        ($($place)*).write(value);
+        output
+    }};
+    ( @ordered $periph:path, $reg:ident, ($($place:tt)*), clear: $( $clear:ident )|+, set: $( $field:ident : $value:expr ),+ ) => {{
+        #[allow(unused_imports)]
+        use $periph::{*};
+        let mask = $($crate::field_mask!($periph, $reg, $clear)) | * | $($crate::field_mask!($periph, $reg, $field)) | *;
+        let value = $crate::build_value!(@checked $periph, $reg, $( $field : $value ),+);
+        ($($place)*).modify(|reg| (reg & !mask) | value)
+    }};
+    ( @ordered $periph:path, $reg:ident, ($($place:tt)*), $( $field:ident : |$old:tt $(: $ty:ty)?| $new:expr ),+ ) => {
+        $crate::modify_reg!(@ordered $periph, $reg, ($($place)*), [ $( $field ),+ ] => |$( $old $(: $ty)? ),+| ( $( $new ),+ ))
+    };
+    ( @ordered $periph:path, $reg:ident, ($($place:tt)*), $( $field:ident : $value:expr ),+ ) => {{
+        #[allow(unused_imports)]
+        use $periph::{*};
+        let mask = $($crate::field_mask!($periph, $reg, $field)) | *;
+        let value = $crate::build_value!(@checked $periph, $reg, $( $field : $value ),+);
+        ($($place)*).modify(|reg| (reg & !mask) | value)
+    }};
+    ( @ordered $periph:path, $reg:ident, ($($place:tt)*), [ $( $field:ident ),+ ] => $fn:expr ) => {{
+        #[allow(unused_imports)]
+        use $periph::{*};
+        ($($place)*).modify(|reg| {
+            #[allow(non_snake_case, unused_parens)]
+            let ( $( $field ),+ ) = $fn( $( $crate::extract_field!($periph, $reg, $field, reg) ),+ );
+            (reg & !( $($crate::field_mask!($periph, $reg, $field)) | * ))
+                | $crate::build_value!(@checked $periph, $reg, $( $field : $field ),+)
+        })
+    }};
+    ( @ordered $periph:path, $reg:ident, ($($place:tt)*), $fn:expr ) => {{
+        #[allow(unused_imports)]
+        use $periph::{*};
+        ($($place)*).modify($fn)
+    }};
+    ( $periph:path, $instance:expr, $reg:ident [$index:literal] $($rest:tt)* ) => {{
+        $crate::check_literal_index!($periph, $reg, $index);
+        $crate::modify_reg!($periph, $instance, $reg [($index)] $($rest)*)
+    }};
+    ( $periph:path, $instance:expr, $reg:ident $([$offset:expr])*, ordering = $ordering:expr, $($rest:tt)+ ) => {
+        $crate::modify_reg!(@ordered $periph, $reg, ($crate::register!($periph, $instance, $reg $([$offset])*).ordered($ordering)), $($rest)+)
+    };
+    ( $periph:path, $instance:expr, $reg:ident $([$offset:expr])*, $($rest:tt)+ ) => {
+        $crate::modify_reg!(@place $periph, $reg, ($crate::register!($periph, $instance, $reg $([$offset])*)), $($rest)+)
+    };
+}
+
+/// Read the value from a RORegister, RWRegister, UnsafeRORegister, or UnsafeRWRegister.
+///
+/// # Examples
+/// ```rust,no_run
+/// # use stm32ral::{read_reg, write_reg, modify_reg, reset_reg}; fn main() {
+/// // Safely acquire the peripheral instance (will panic if already acquired)
+/// let gpioa = stm32ral::gpio::GPIOA::take().unwrap();
+///
+/// // Read the whole register.
+/// let val = read_reg!(stm32ral::gpio, gpioa, IDR);
+///
+/// // Read one field from the register.
+/// let val = read_reg!(stm32ral::gpio, gpioa, IDR, IDR2);
+///
+/// // Read multiple fields from the register.
+/// let (val1, val2, val3) = read_reg!(stm32ral::gpio, gpioa, IDR, IDR0, IDR1, IDR2);
+///
+/// // Check if one field is equal to a specific value, with the field's named values in scope.
+/// while read_reg!(stm32ral::gpio, gpioa, IDR, IDR2 == High) {}
+///
+/// // Unsafe access without requiring you to first `take()` the instance
+/// let val = unsafe { read_reg!(stm32ral::gpio, GPIOA, IDR) };
+/// # }
+/// ```
+///
+/// # Usage
+/// Like `write_reg!`, this macro can be used multiple ways, either reading the entire register or
+/// reading a one or more fields from it and potentially performing a comparison with one field.
+///
+/// In all cases, the first arguments are:
+/// * the path to the peripheral module: `stm32ral::gpio`,
+/// * a reference to the instance of that peripheral: 'gpioa' (anything which dereferences to
+///   `RegisterBlock`, such as `Instance`, `&Instance`, `&RegisterBlock`, or
+///   `*const RegisterBlock`),
+/// * the register you wish to access: `IDR` (a field on the `RegisterBlock`). For register
+///   arrays, follow the register with one index per dimension: `ARRAY[i]` or `MATRIX[i][j]`.
+///
+/// In the whole-register usage, the macro simply returns the register's value:
+/// ```rust,no_run
+/// # use stm32ral::{read_reg, write_reg, modify_reg, reset_reg}; fn main() {
+/// # let gpioa = stm32ral::gpio::GPIOA::take().unwrap();
+/// // Read the entire value of GPIOA.IDR into `val`.
+/// let val = read_reg!(stm32ral::gpio, gpioa, IDR);
+/// # }
+/// ```
+///
+/// For reading individual fields, the macro masks and shifts appropriately:
+/// ```rust,no_run
+/// # use stm32ral::{read_reg, write_reg, modify_reg, reset_reg}; fn main() {
+/// # let gpioa = stm32ral::gpio::GPIOA::take().unwrap();
+/// // Read just the value of the field GPIOA.IDR2 into `val`.
+/// let val = read_reg!(stm32ral::gpio, gpioa, IDR, IDR2);
+///
+/// // As above, but expanded for exposition:
+/// let val = ((*gpioa).IDR.read() & stm32ral::gpio::IDR::IDR2::mask)
+///           >> stm32ral::gpio::IDR::IDR2::offset;
+///
+/// // Read multiple fields
+/// let (val1, val2) = read_reg!(stm32ral::gpio, gpioa, IDR, IDR2, IDR3);
+///
+/// // As above, but expanded for exposition:
+/// let (val1, val2) = { let val = (*gpioa).IDR.read();
+///     ((val & stm32ral::gpio::IDR::IDR2::mask) >> stm32ral::gpio::IDR::IDR2::offset,
+///      (val & stm32ral::gpio::IDR::IDR3::mask) >> stm32ral::gpio::IDR::IDR3::offset,
+///     )};
+/// # }
+/// ```
+///
+/// For comparing a single field, the macro masks and shifts and then performs the comparison.
+/// Any comparison operator may be used, such as `==`, `!=`, `<`, or `>=`, against any
+/// expression. The named values in the field's `R` and `RW` modules are brought into scope, so
+/// they may be used directly in the comparison, or named by their full path:
+/// ```rust,no_run
+/// # use stm32ral::{read_reg, write_reg, modify_reg, reset_reg}; fn main() {
+/// # let gpioa = stm32ral::gpio::GPIOA::take().unwrap();
+/// # let rcc = stm32ral::rcc::RCC::take().unwrap();
+/// // Loop while PA2 is High.
+/// while read_reg!(stm32ral::gpio, gpioa, IDR, IDR2 == High) {}
+///
+/// // Only proceed if the clock is not the HSI.
+/// if read_reg!(stm32ral::rcc, rcc, CFGR, SWS != HSI) { }
+///
+/// // As above, naming the value by its path, and comparing outside the macro.
+/// if read_reg!(stm32ral::rcc, rcc, CFGR, SWS) != stm32ral::rcc::CFGR::SWS::R::HSI { }
+///
+/// // Check whether the AHB prescaler divides the clock.
+/// if read_reg!(stm32ral::rcc, rcc, CFGR, HPRE >= Div2) { }
+///
+/// // Equivalent expansion:
+/// if (((*rcc).CFGR.read() & stm32ral::rcc::CFGR::SWS::mask)
+///     >> stm32ral::rcc::CFGR::SWS::offset) != stm32ral::rcc::CFGR::SWS::R::HSI { }
+/// # }
+/// ```
+///
+/// To check several flags at once, wrap them in `any(...)` or `all(...)`. The register is read
+/// once. `any` is true if any of the fields is nonzero, and `all` is true only if every field is
+/// nonzero:
+/// ```rust,no_run
+/// # use ral_registers::read_reg; fn main() {
+/// # let gpioa = stm32ral::gpio::GPIOA::take().unwrap();
+/// // Proceed if either PA2 or PA3 is high.
+/// if read_reg!(stm32ral::gpio, gpioa, IDR, any(IDR2, IDR3)) { }
+///
+/// // Proceed only if both are high.
+/// if read_reg!(stm32ral::gpio, gpioa, IDR, all(IDR2, IDR3)) { }
+/// # }
+/// ```
+///
+/// To get the whole register value as well as some of its fields from one read, write
+/// `raw + (...)` with the fields in parentheses. The macro evaluates to a tuple of the raw
+/// value and the fields, as they would be returned by the multiple field form:
+/// ```rust,no_run
+/// # use ral_registers::read_reg; fn main() {
+/// # let gpioa = stm32ral::gpio::GPIOA::take().unwrap();
+/// // Log the whole of IDR, and branch on PA2 and PA3.
+/// let (idr, (pa2, pa3)) = read_reg!(stm32ral::gpio, gpioa, IDR, raw + (IDR2, IDR3));
+/// # }
+/// ```
+///
+/// For an `AtomicRWRegister`, an ordering is given after the register, as in
+/// `read_reg!(periph, inst, REG, ordering = SeqCst, FIELD)`, and the read is an atomic load.
+///
+/// Like the `read` methods of the register types, the macro's value is `#[must_use]`, so the
+/// compiler warns about a read such as `read_reg!(periph, inst, SR, BUSY == 0);` whose value
+/// is discarded. To read a register only for the side effects of the read, assign the value
+/// to `_`: `let _ = read_reg!(periph, inst, DR);`.
+///
+/// # Safety
+/// This macro will require an unsafe function or block when used with an UnsafeRWRegister or
+/// UnsafeRORegister, but not if used with RWRegister, or RORegister.
+///
+/// When run in an unsafe context, peripheral instances are directly accessible without requiring
+/// having called `take()` beforehand:
+/// ```rust,no_run
+/// # use stm32ral::{read_reg, write_reg, modify_reg, reset_reg}; fn main() {
+/// let val = unsafe { read_reg!(stm32ral::gpio, GPIOA, MODER) };
+/// # }
+/// ```
+/// This works because `GPIOA` is a `*const RegisterBlock` in the `stm32ral::gpio` module;
+/// and the macro brings such constants into scope and then dereferences the provided reference.
+#[macro_export]
+macro_rules! read_reg {
+    ( @place $periph:path, $reg:ident, ($($place:tt)*), any( $( $field:ident ),+ $(,)? ) ) => {{
+        #[allow(unused_imports)]
+        use $periph::{*};
+        let val = (($($place)*).read());
+        $( $crate::check_field!($periph, $reg, $field); )+
+        (val & ( $($crate::field_mask!($periph, $reg, $field)) | * )) != 0
+    }};
+    ( @place $periph:path, $reg:ident, ($($place:tt)*), all( $( $field:ident ),+ $(,)? ) ) => {{
+        #[allow(unused_imports)]
+        use $periph::{*};
+        let val = (($($place)*).read());
+        $( $crate::check_field!($periph, $reg, $field); )+
+        $( (val & $crate::field_mask!($periph, $reg, $field)) != 0 )&&*
+    }};
+    ( @place $periph:path, $reg:ident, ($($place:tt)*), raw + ( $( $field:ident ),+ $(,)? ) ) => {{
+        #[allow(unused_imports)]
+        use $periph::{*};
+        let val = (($($place)*).read());
+        (val, $crate::extract_field!($periph, $reg, [ $( $field ),+ ], val))
+    }};
+    ( @place $periph:path, $reg:ident, ($($place:tt)*), $( $field:ident ),+ ) => {{
+        #[allow(unused_imports)]
+        use $periph::{*};
+        let val = (($($place)*).read());
+        ( $({
+            $crate::check_field!($periph, $reg, $field);
+            (val & $crate::field_mask!($periph, $reg, $field))
+                >> $crate::field_offset!($periph, $reg, $field)
+        }) , *)
+    }};
+    ( @place $periph:path, $reg:ident, ($($place:tt)*), $field:ident $($cmp:tt)* ) => {{
+        #[allow(unused_imports)]
+        use $periph::{*};
+        #[allow(unused_imports)]
+        use $periph::{$reg::$field::{R::*, RW::*}};
+        let val = (($($place)*).read());
+        let val = {
+            $crate::check_field!($periph, $reg, $field);
+            (val & $crate::field_mask!($periph, $reg, $field))
+                >> $crate::field_offset!($periph, $reg, $field)
+        };
+        val $($cmp)*
+    }};
+    ( @place $periph:path, $reg:ident, ($($place:tt)*) ) => {{
+        #[allow(unused_imports)]
+        use $periph::{*};
+        (($($place)*).read())
+    }};
+    ( $periph:path, $instance:expr, $reg:ident [$index:literal] $($rest:tt)* ) => {{
+        $crate::check_literal_index!($periph, $reg, $index);
+        $crate::read_reg!($periph, $instance, $reg [($index)] $($rest)*)
+    }};
+    ( $periph:path, $instance:expr, $reg:ident $([$offset:expr])*, ordering = $ordering:expr $(, $($rest:tt)+)? ) => {
+        $crate::must_use($crate::read_reg!(@place $periph, $reg, ($crate::register!($periph, $instance, $reg $([$offset])*).ordered($ordering)) $(, $($rest)+)?))
+    };
+    ( $periph:path, $instance:expr, $reg:ident $([$offset:expr])* $(, $($rest:tt)+)? ) => {
+        $crate::must_use($crate::read_reg!(@place $periph, $reg, ($crate::register!($periph, $instance, $reg $([$offset])*)) $(, $($rest)+)?))
+    };
+}
+
+/// Reset a RWRegister, UnsafeRWRegister, WORegister, or UnsafeWORegister to its reset value.
+///
+/// # Examples
+/// ```rust,no_run
+/// # use stm32ral::{read_reg, write_reg, modify_reg, reset_reg}; fn main() {
+/// // Safely acquire the peripheral instance (will panic if already acquired)
+/// let gpioa = stm32ral::gpio::GPIOA::take().unwrap();
+///
+/// // Reset PA14 and PA15 to their reset state
+/// reset_reg!(stm32ral::gpio, gpioa, GPIOA, MODER, MODER14, MODER15);
+///
+/// // Reset the entire GPIOA.MODER to its reset state
+/// reset_reg!(stm32ral::gpio, gpioa, GPIOA, MODER);
+/// # }
+/// ```
+///
+/// # Usage
+/// Like `write_reg!`, this macro can be used in two ways, either resetting the entire register
+/// or just resetting specific fields within in. The register or fields are written with their
+/// reset values.
+///
+/// In both cases, the first arguments are:
+/// * the path to the peripheral module: `stm32ral::gpio`,
+/// * a reference to the instance of that peripheral: 'gpioa' (anything which dereferences to
+///   `RegisterBlock`, such as `Instance`, `&Instance`, `&RegisterBlock`, or
+///   `*const RegisterBlock`),
+/// * the module for the instance of that peripheral: `GPIOA`,
+/// * the register you wish to access: `MODER` (a field on the `RegisterBlock`). For register
+///   arrays, follow the register with one index per dimension: `ARRAY[i]` or `MATRIX[i][j]`.
+///
+/// In the whole-register usage, that's it:
+/// ```rust,no_run
+/// # use stm32ral::{read_reg, write_reg, modify_reg, reset_reg}; fn main() {
+/// # let gpioa = stm32ral::gpio::GPIOA::take().unwrap();
+/// // Reset the entire GPIOA.MODER
+/// reset_reg!(stm32ral::gpio, gpioa, GPIOA, MODER);
+/// # }
+/// ```
+///
+/// Otherwise, the remaining arguments are each field names:
+/// ```rust,no_run
+/// # use stm32ral::{read_reg, write_reg, modify_reg, reset_reg}; fn main() {
+/// # let gpioa = stm32ral::gpio::GPIOA::take().unwrap();
+/// // Reset the JTAG pins
+/// reset_reg!(stm32ral::gpio, gpioa, GPIOA, MODER, MODER13, MODER14, MODER15);
+/// reset_reg!(stm32ral::gpio, gpioa, GPIOB, MODER, MODER3, MODER4);
+/// # }
+/// ```
+///
+/// The second form is only available to RWRegister and UnsafeRWRegister, since `.read()` is
+/// not available for WORegister and UnsafeWORegister. Only the bits of the listed fields are
+/// taken from the reset value, and every other bit keeps its current value. As for the other
+/// field macros, each field's `mask` and `offset` are checked at compile time, so a field that
+/// starts outside the register, or whose mask is empty or has bits below its offset, is a
+/// compile error rather than a reset of the wrong bits.
+///
+/// The reset value of a register array is usually one value that applies to every register in
+/// the array. It may instead be an array itself, such as `MY_ARRAY: [u32; 3]` in
+/// `ResetValues`, in which case each register is reset to the value at its own index:
+/// `reset_reg!(periph, inst, INST, MY_ARRAY[1])` writes `INST::reset.MY_ARRAY[1]`. An index
+/// that is out of bounds of the register array panics before the reset value is indexed.
+///
+/// To reset every register in a register array, write `[..]` in place of the index. Each
+/// register is written with the array's reset value, in index order:
+/// ```rust,ignore
+/// // Reset every channel's configuration register.
+/// reset_reg!(periph, dma, DMA1, CCR[..]);
+///
+/// // Reset only the EN field of every channel's configuration register.
+/// reset_reg!(periph, dma, DMA1, CCR[..], EN);
+/// ```
+///
+/// This writes every element of the array's storage, which includes any padding registers of
+/// a strided array (see "Register arrays" in the crate documentation), and only applies to
+/// the outermost dimension of an array.
+///
+/// This macro expands to calling `(*$instance).$register.write(value)`, where
+/// `value` is either the register's reset value, or the current read value of the register
+/// masked appropriately and combined with the reset value for each field. The macro evaluates
+/// to the `value` that was written. With `[..]`, the whole-register form evaluates to the reset
+/// value, which is an array if the reset value is an array, and the field form evaluates to
+/// `()`, since each register may be written with a different value.
+///
+/// # Safety
+/// This macro will require an unsafe function or block when used with an UnsafeRWRegister or
+/// UnsafeRORegister, but not if used with RWRegister or RORegister.
+///
+/// When run in an unsafe context, peripheral instances are directly accessible without requiring
+/// having called `take()` beforehand:
+/// ```rust,no_run
+/// # use stm32ral::{read_reg, write_reg, modify_reg, reset_reg}; fn main() {
+/// unsafe { reset_reg!(stm32ral::gpio, GPIOA, GPIOA, MODER) };
+/// # }
+/// ```
+/// This works because `GPIOA` is a `*const RegisterBlock` in the `stm32ral::gpio` module;
+/// and the macro brings such constants into scope and then dereferences the provided reference.
+///
+/// Note that the second argument is a `*const` and the third is a path; despite both being written
+/// `GPIOA` they are not the same thing.
+#[macro_export]
+macro_rules! reset_reg {
+    ( @fields $periph:path, $instance:expr, $instancemod:path, ($($field:ident),+), $reg:ident $([$index:ident])* ) => {{
+        #[allow(unused_imports)]
+        use $periph::{*};
+        use $periph::{$instancemod::{reset}};
+        let resetmask = $({ $crate::check_field!($periph, $reg, $field); $crate::field_mask!($periph, $reg, $field) }) | *;
+        let register = &$crate::register!($periph, $instance, $reg $([$index])*);
+        let resetvalue = $crate::reset_value!($periph, reset, $reg $([$index])*).value();
+        let value = (register.read() & !resetmask) | (resetvalue & resetmask);
+        register.write(value);
+        value
+    }};
+    ( @register $periph:path, $instance:expr, $instancemod:path, $reg:ident $([$index:ident])* ) => {{
+        #[allow(unused_imports)]
+        use $periph::{*};
+        use $periph::{$instancemod::{reset}};
+        let register = &$crate::register!($periph, $instance, $reg $([$index])*);
+        let value = $crate::reset_value!($periph, reset, $reg $([$index])*).value();
+        register.write(value);
+        value
+    }};
+    ( $periph:path, $instance:expr, $instancemod:path, $reg:ident [$index:literal] $($rest:tt)* ) => {{
+        $crate::check_literal_index!($periph, $reg, $index);
+        $crate::reset_reg!($periph, $instance, $instancemod, $reg [($index)] $($rest)*)
+    }};
+    ( $periph:path, $instance:expr, $instancemod:path, $reg:ident [..], $( $field:ident ),+ ) => {{
+        #[allow(unused_imports)]
+        use $periph::{*};
+        use $periph::{$instancemod::{reset}};
+        #[allow(unused_imports)]
+        use $crate::{ResetElement as _, ResetScalar as _};
+        let resetmask = $({ $crate::check_field!($periph, $reg, $field); $crate::field_mask!($periph, $reg, $field) }) | *;
+        let resetvalue = $crate::reset_value!($periph, reset, $reg);
+        for (index, register) in (*$instance).$reg.iter().enumerate() {
+            let value = (&&resetvalue).at(index).value();
+            register.write((register.read() & !resetmask) | (value & resetmask));
+        }
+    }};
+    ( $periph:path, $instance:expr, $instancemod:path, $reg:ident [..] ) => {{
+        #[allow(unused_imports)]
+        use $periph::{*};
+        use $periph::{$instancemod::{reset}};
+        #[allow(unused_imports)]
+        use $crate::{ResetElement as _, ResetScalar as _};
+        let resetvalue = $crate::reset_value!($periph, reset, $reg);
+        for (index, register) in (*$instance).$reg.iter().enumerate() {
+            register.write((&&resetvalue).at(index).value());
+        }
+        *resetvalue.0
+    }};
+    ( $periph:path, $instance:expr, $instancemod:path, $reg:ident $([$offset:expr])*, $( $field:ident ),+ ) => {
+        $crate::with_indices!([] $([$offset])* => reset_reg!(@fields $periph, $instance, $instancemod, ($($field),+), $reg))
+    };
+    ( $periph:path, $instance:expr, $instancemod:path, $reg:ident $([$offset:expr])*) => {
+        $crate::with_indices!([] $([$offset])* => reset_reg!(@register $periph, $instance, $instancemod, $reg))
+    };
+}
+
+/// Reset several registers to their reset values.
+///
+/// # Examples
+/// ```rust,no_run
+/// # use ral_registers::reset_all; fn main() {
+/// # let gpioa = stm32ral::gpio::GPIOA::take().unwrap();
+/// // Reset the configuration registers of GPIOA.
+/// reset_all!(stm32ral::gpio, gpioa, GPIOA, [MODER, OTYPER, OSPEEDR, PUPDR]);
+/// # }
+/// ```
+///
+/// # Usage
+/// The first arguments are the same as for `reset_reg!`, followed by a list of registers in
+/// square brackets. Each register is written with its reset value, in the order listed, as if
+/// by the whole-register form of `reset_reg!`.
+///
+/// A register array in the list, without an index, resets every register in the array. For
+/// multi-dimensional arrays, list each row: `[MATRIX[0], MATRIX[1]]`. Every element of the
+/// array's storage is written, so list the registers of a strided array (see "Register
+/// arrays" in the crate documentation) individually.
+///
+/// # Safety
+/// As for `reset_reg!`.
+#[macro_export]
+macro_rules! reset_all {
+    ( @register $periph:path, $instance:expr, $instancemod:path, $reg:ident $([$index:ident])* ) => {{
+        #[allow(unused_imports)]
+        use $periph::{*};
+        use $periph::{$instancemod::{reset}};
+        #[allow(unused_imports)]
+        use $crate::{EachElement as _, EachScalar as _, ResetElement as _, ResetScalar as _};
+        let resetvalue = $crate::reset_value!($periph, reset, $reg $([$index])*);
+        (&&$crate::Each(&$crate::register!($periph, $instance, $reg $([$index])*)))
+            .each(|index, register| register.write((&&resetvalue).at(index).value()));
+    }};
+    ( $periph:path, $instance:expr, $instancemod:path, [ $( $reg:ident $([$offset:expr])* ),+ $(,)? ] ) => {{
+        $(
+            $crate::with_indices!([] $([$offset])* => reset_all!(@register $periph, $instance, $instancemod, $reg));
+        )+
+    }};
+}
+
+/// Assert that registers hold their reset values.
+///
+/// # Examples
+/// ```rust,no_run
+/// # use ral_registers::assert_reset; fn main() {
+/// # let gpioa = stm32ral::gpio::GPIOA::take().unwrap();
+/// // Check that GPIOA came out of reset before configuring it.
+/// assert_reset!(stm32ral::gpio, gpioa, GPIOA, [MODER, OTYPER, OSPEEDR, PUPDR]);
+/// # }
+/// ```
+///
+/// # Usage
+/// The arguments are the same as for `reset_all!`, except that a register array must be
+/// listed with an index for each element to check: `[CR1, CR2, MY_ARRAY[0]]`. Each register is
+/// read once, in the order listed, and compared with its reset value.
+///
+/// # Panics
+/// Panics if a register does not hold its reset value. The message names the register, with
+/// the values of its indices, and both values in hexadecimal:
+/// `MY_ARRAY[0] is 0x7, but its reset value is 0x2a`. Each index is evaluated once.
+///
+/// # Safety
+/// As for `read_reg!`.
+#[macro_export]
+macro_rules! assert_reset {
+    ( @register $periph:path, $instance:expr, $instancemod:path, $reg:ident $([$index:ident])* ) => {{
+        #[allow(unused_imports)]
+        use $periph::{*};
+        use $periph::{$instancemod::{reset}};
+        let value = $crate::register!($periph, $instance, $reg $([$index])*).read();
+        let resetvalue = $crate::reset_value!($periph, reset, $reg $([$index])*).value();
+        if value != resetvalue {
+            panic!(
+                "{} is {:#x}, but its reset value is {:#x}",
+                $crate::RegisterName(stringify!($reg), &[$($index),*]),
+                value,
+                resetvalue,
+            );
+        }
+    }};
+    ( $periph:path, $instance:expr, $instancemod:path, [ $( $reg:ident $([$offset:expr])* ),+ $(,)? ] ) => {{
+        $(
+            $crate::with_indices!([] $([$offset])* => assert_reset!(@register $periph, $instance, $instancemod, $reg));
+        )+
+    }};
+}
+
+/// Write to a RWRegister or UnsafeRWRegister only if its value would change.
+///
+/// # Examples
+/// ```rust,no_run
+/// # use ral_registers::write_reg_if_changed; fn main() {
+/// # let gpioa = stm32ral::gpio::GPIOA::take().unwrap();
+/// // Only writes MODER if PA3 or PA4 is not already in the requested mode, or if any other
+/// // pin is not in input mode.
+/// let written = write_reg_if_changed!(stm32ral::gpio, gpioa, MODER, MODER3: Output, MODER4: Analog);
+/// # }
+/// ```
+///
+/// # Usage
+/// The arguments are the same as for `write_reg!`, in either form. The register is read, and
+/// only written if the value `write_reg!` would write differs from the value read. The macro
+/// evaluates to `true` if the register was written, and `false` otherwise.
+///
+/// The register must be readable, so a WORegister or UnsafeWORegister is a compile error:
+/// ```rust,compile_fail
+/// # use ral_registers::write_reg_if_changed; fn main() {
+/// # let gpioa = stm32ral::gpio::GPIOA::take().unwrap();
+/// // BSRR is write-only.
+/// write_reg_if_changed!(stm32ral::gpio, gpioa, BSRR, BS3: 1);
+/// # }
+/// ```
+///
+/// # Safety
+/// As for `write_reg!`.
+#[macro_export]
+macro_rules! write_reg_if_changed {
+    ( $periph:path, $instance:expr, $reg:ident $([$offset:expr])*, $( $field:ident : $value:expr ),+ ) => {{
+        #[allow(unused_imports)]
+        use $periph::{*};
+        let value = $crate::build_value!(@checked $periph, $reg, $( $field : $value ),+);
+        $crate::write_reg_if_changed!(@write ($crate::register!($periph, $instance, $reg $([$offset])*)), value)
+    }};
+    ( @write ($($place:tt)*), $value:expr ) => {{
+        let register = &$($place)*;
+        let value = $value;
+        let changed = register.read() != value;
+        if changed {
+            register.write(value);
+        }
+        changed
+    }};
+    ( $periph:path, $instance:expr, $reg:ident $([$offset:expr])*, $value:expr ) => {{
+        #[allow(unused_imports)]
+        use $periph::{*};
+        $crate::write_reg_if_changed!(@write ($crate::register!($periph, $instance, $reg $([$offset])*)), $value)
+    }};
+}
+
+/// Clear write-one-to-clear flags in a register.
+///
+/// # Examples
+/// ```rust,no_run
+/// # use ral_registers::clear_flags; fn main() {
+/// # let usart1 = stm32ral::usart::USART1::take().unwrap();
+/// // Clear the overrun and framing error flags, leaving any other pending flags set.
+/// clear_flags!(stm32ral::usart, usart1, ICR, ORECF, FECF);
+/// # }
+/// ```
+///
+/// # Usage
+/// Many status registers clear a flag when a 1 is written to it, and ignore bits written as 0.
+/// The first arguments are the same as for `write_reg!`, followed by one or more field names.
+/// The macro writes a value with every bit of each named field set, and every other bit zero,
+/// and evaluates to that value.
+///
+/// This is a direct write, not a read-modify-write: the register is never read. Using
+/// `modify_reg!` or `bit_set!` on such a register would write back every flag that was read
+/// as set, clearing pending flags other than the ones named.
+///
+/// This works with WORegister and UnsafeWORegister, as well as RWRegister and
+/// UnsafeRWRegister.
+///
+/// # Safety
+/// As for `write_reg!`.
+#[macro_export]
+macro_rules! clear_flags {
+    ( $periph:path, $instance:expr, $reg:ident $([$offset:expr])*, $( $field:ident ),+ ) => {{
+        #[allow(unused_imports)]
+        use $periph::{*};
+        let value = $({ $crate::check_field!($periph, $reg, $field); $crate::field_mask!($periph, $reg, $field) }) | *;
+        $crate::register!($periph, $instance, $reg $([$offset])*).write(value);
+        value
+    }};
+}
+
+/// Read a register, then clear every write-one-to-clear flag that was read as set.
+///
+/// # Examples
+/// ```rust,ignore
+/// // Take every pending flag, then handle each of them.
+/// let pending = read_clear_reg!(periph, inst, ISR);
+/// ```
+///
+/// # Usage
+/// The arguments are the path to the peripheral module, the instance, and the register, as
+/// for the whole-register form of `read_reg!`. The macro performs two volatile accesses: it
+/// reads the register, then writes the value it read back to the register. For a register
+/// whose flags are cleared by writing a 1, this clears exactly the flags which were set when
+/// it was read, so a flag which is set between the read and the write stays set. The macro
+/// evaluates to the value read.
+///
+/// Only use this where every set bit of the register may be written back as a 1; see
+/// `clear_flags!` to clear only some flags.
+///
+/// # Safety
+/// As for `modify_reg!`.
+#[macro_export]
+macro_rules! read_clear_reg {
+    ( $periph:path, $instance:expr, $reg:ident $([$offset:expr])* ) => {{
+        #[allow(unused_imports)]
+        use $periph::{*};
+        let value = $crate::register!($periph, $instance, $reg $([$offset])*).read();
+        $crate::register!($periph, $instance, $reg $([$offset])*).write(value);
+        value
+    }};
+}
+
+/// Read a register, then write a new value to it, evaluating to the value read.
+///
+/// # Examples
+/// ```rust,ignore
+/// // Latch a new reload value, and find out what the old one was.
+/// let old = read_then_write_reg!(periph, timer, RELOAD, 1000);
+///
+/// // Take the COUNT and OVF fields, and restart the counter.
+/// let (count, overflowed) = read_then_write_reg!(periph, timer, CNT, [COUNT, OVF], EN: 1);
+/// ```
+///
+/// # Usage
+/// The first arguments are the path to the peripheral module, the instance, and the register,
+/// as for `write_reg!`. These may be followed by a list of fields in square brackets, and then
+/// by the same value arguments as `write_reg!`: a single value for the whole register, or
+/// `Field: Value` pairs, optionally starting with `base = value`.
+///
+/// The macro reads the register once, then writes it once. Without a list of fields, it
+/// evaluates to the value read. With the fields, it evaluates to the value of each field
+/// before the write, as a tuple if there is more than one field, like the field form of
+/// `read_reg!`. This suits registers where a write latches or clears the value which was
+/// read. For a whole-register swap, the `read_write` method of the read-write register types
+/// is equivalent.
+///
+/// # Safety
+/// As for `modify_reg!`.
+#[macro_export]
+macro_rules! read_then_write_reg {
+    ( $periph:path, $instance:expr, $reg:ident $([$offset:expr])*, [ $( $field:ident ),+ $(,)? ], $($rest:tt)+ ) => {{
+        #[allow(unused_imports)]
+        use $periph::{*};
+        let register = &$crate::register!($periph, $instance, $reg $([$offset])*);
+        let old = register.read();
+        $crate::write_reg!(@place $periph, $reg, (*register), $($rest)+);
+        $crate::must_use($crate::extract_field!($periph, $reg, [ $( $field ),+ ], old))
+    }};
+    ( $periph:path, $instance:expr, $reg:ident $([$offset:expr])*, $($rest:tt)+ ) => {{
+        #[allow(unused_imports)]
+        use $periph::{*};
+        let register = &$crate::register!($periph, $instance, $reg $([$offset])*);
+        let old = register.read();
+        $crate::write_reg!(@place $periph, $reg, (*register), $($rest)+);
+        $crate::must_use(old)
+    }};
+}
+
+/// Get a field of a register as a `Field` value.
+///
+/// # Usage
+/// The arguments are the path to the peripheral module, the register module, and the field:
+/// `field!(periph, CR, MODE)`. The macro evaluates to a `Field` holding the field's `mask` and
+/// `offset`, which may be stored in tables and passed to `CachedRegister`. The expansion is a
+/// constant expression.
+#[macro_export]
+macro_rules! field {
+    ( $periph:path, $reg:ident, $field:ident ) => {{
+        $crate::check_field!($periph, $reg, $field);
+        #[allow(clippy::unnecessary_cast)]
+        $crate::Field::new(
+            $crate::field_mask!($periph, $reg, $field),
+            $crate::field_offset!($periph, $reg, $field) as u32,
+        )
+    }};
+}
+
+/// Read a register once, then read and write several of its fields with one volatile write.
+///
+/// # Examples
+/// ```rust,no_run
+/// # use ral_registers::with_reg; fn main() {
+/// # let gpioa = stm32ral::gpio::GPIOA::take().unwrap();
+/// // Make PA3 an output, if PA2 is an input.
+/// with_reg!(stm32ral::gpio, gpioa, MODER, [MODER2, MODER3], |r| {
+///     if r.get(MODER2) == 0b00 {
+///         r.set(MODER3, 0b01);
+///     }
+/// });
+/// # }
+/// ```
+///
+/// # Usage
+/// The first arguments are the path to the peripheral module, the instance, and the register,
+/// as for `modify_reg!`. They are followed by the fields to use, in square brackets, and then a
+/// closure which is passed a `&mut CachedRegister` holding the value read.
+///
+/// Within the closure, each field is a local `Field` variable of the same name, which is
+/// passed to `CachedRegister::get` and `CachedRegister::set`. Field values are numbers; the
+/// named values in a field's `R`, `W` and `RW` modules are not imported, but may be written by
+/// their full path. Setting a field only changes the cached value.
+///
+/// The macro reads the register once before calling the closure. Afterwards, if any field was
+/// set, it writes the cached value to the register once. It evaluates to the closure's result.
+///
+/// # Safety
+/// As for `modify_reg!`.
+#[macro_export]
+macro_rules! with_reg {
+    ( $periph:path, $instance:expr, $reg:ident $([$offset:expr])*, [ $( $field:ident ),+ $(,)? ], $fn:expr ) => {{
+        #[allow(unused_imports)]
+        use $periph::{*};
+        $(
+            #[allow(non_snake_case)]
+            let $field = $crate::field!($periph, $reg, $field);
+        )+
+        let place = &$crate::register!($periph, $instance, $reg $([$offset])*);
+        let mut cached = $crate::CachedRegister::new(place.read());
+        let result = $crate::call_mut($fn, &mut cached);
+        if cached.is_modified() {
+            place.write(cached.value());
+        }
+        result
+    }};
+}
+
+/// Set every bit of one or more fields in a RWRegister or UnsafeRWRegister.
+///
+/// # Examples
+/// ```rust,no_run
+/// # use ral_registers::bit_set; fn main() {
+/// # let rcc = stm32ral::rcc::RCC::take().unwrap();
+/// // Enable the clocks for GPIOA and GPIOB.
+/// bit_set!(stm32ral::rcc, rcc, AHBENR, IOPAEN, IOPBEN);
+/// # }
+/// ```
+///
+/// # Usage
+/// The first arguments are the same as for `modify_reg!`, followed by one or more field names.
+/// The register is read, every bit in each field's mask is set, and the result is written
+/// back, so this is equivalent to `modify_reg!` with each field set to all ones. The macro
+/// evaluates to the value written to the register.
+///
+/// # Safety
+/// As for `modify_reg!`.
+#[macro_export]
+macro_rules! bit_set {
+    ( $periph:path, $instance:expr, $reg:ident $([$offset:expr])*, $( $field:ident ),+ ) => {{
+        #[allow(unused_imports)]
+        use $periph::{*};
+        let value = $crate::register!($periph, $instance, $reg $([$offset])*).read() | ( $($crate::field_mask!($periph, $reg, $field)) | * );
+        $crate::register!($periph, $instance, $reg $([$offset])*).write(value);
+        value
+    }};
+}
+
+/// Clear every bit of one or more fields in a RWRegister or UnsafeRWRegister.
+///
+/// # Examples
+/// ```rust,no_run
+/// # use ral_registers::bit_clear; fn main() {
+/// # let rcc = stm32ral::rcc::RCC::take().unwrap();
+/// // Disable the clocks for GPIOA and GPIOB.
+/// bit_clear!(stm32ral::rcc, rcc, AHBENR, IOPAEN, IOPBEN);
+/// # }
+/// ```
+///
+/// # Usage
+/// The first arguments are the same as for `modify_reg!`, followed by one or more field names.
+/// The register is read, every bit in each field's mask is cleared, and the result is written
+/// back, so this is equivalent to `modify_reg!` with each field set to zero. The macro
+/// evaluates to the value written to the register.
+///
+/// # Safety
+/// As for `modify_reg!`.
+#[macro_export]
+macro_rules! bit_clear {
+    ( $periph:path, $instance:expr, $reg:ident $([$offset:expr])*, $( $field:ident ),+ ) => {{
+        #[allow(unused_imports)]
+        use $periph::{*};
+        let value = $crate::register!($periph, $instance, $reg $([$offset])*).read() & !( $($crate::field_mask!($periph, $reg, $field)) | * );
+        $crate::register!($periph, $instance, $reg $([$offset])*).write(value);
+        value
+    }};
+}
+
+/// Toggle every bit of one or more fields in a RWRegister or UnsafeRWRegister.
+///
+/// # Examples
+/// ```rust,no_run
+/// # use ral_registers::toggle_reg; fn main() {
+/// # let gpioa = stm32ral::gpio::GPIOA::take().unwrap();
+/// // Flip the output state of PA3 and PA4.
+/// toggle_reg!(stm32ral::gpio, gpioa, ODR, ODR3, ODR4);
+/// # }
+/// ```
+///
+/// # Usage
+/// The first arguments are the same as for `modify_reg!`, followed by one or more field names.
+/// The register is read, every bit in each field's mask is inverted, and the result is
+/// written back. The macro evaluates to the value written to the register.
+///
+/// This is a read-modify-write, not an atomic operation: if the register is changed between
+/// the read and the write (for example, by an interrupt handler), that change is lost.
+///
+/// # Safety
+/// As for `modify_reg!`.
+#[macro_export]
+macro_rules! toggle_reg {
+    ( $periph:path, $instance:expr, $reg:ident $([$offset:expr])*, $( $field:ident ),+ ) => {{
+        #[allow(unused_imports)]
+        use $periph::{*};
+        let value = $crate::register!($periph, $instance, $reg $([$offset])*).read() ^ ( $($crate::field_mask!($periph, $reg, $field)) | * );
+        $crate::register!($periph, $instance, $reg $([$offset])*).write(value);
+        value
+    }};
+}
+
+/// Increment a field in a RWRegister or UnsafeRWRegister, wrapping to zero after its largest
+/// value.
+///
+/// # Examples
+/// ```rust,ignore
+/// // Advance the sequence number of the next frame.
+/// increment_field!(periph, inst, CR, SEQ);
+/// ```
+///
+/// # Usage
+/// The first arguments are the same as for `modify_reg!`, followed by one field name. The
+/// register is read, the field is incremented, and the result is written back in one
+/// read-modify-write, as by the field closure form of `modify_reg!`. A field that holds its
+/// largest value, `mask >> offset`, wraps to zero rather than carrying into the bits above it,
+/// and every bit outside the field is unchanged. The macro evaluates to the value written to
+/// the register.
+///
+/// For an `AtomicRWRegister`, an ordering is given after the register, as for `modify_reg!`,
+/// and the increment is a single atomic `fetch_update`. Otherwise, if the register is changed
+/// between the read and the write, that change is lost.
+///
+/// # Safety
+/// As for `modify_reg!`.
+#[macro_export]
+macro_rules! increment_field {
+    ( @wrap $periph:path, $reg:ident, $field:ident, $value:ident ) => {{
+        let max = $crate::field_mask!($periph, $reg, $field) >> $crate::field_offset!($periph, $reg, $field);
+        if $value == max { 0 } else { $value + 1 }
+    }};
+    ( $periph:path, $instance:expr, $reg:ident $([$offset:expr])*, ordering = $ordering:expr, $field:ident ) => {
+        $crate::modify_reg!($periph, $instance, $reg $([$offset])*, ordering = $ordering,
+            $field: |value| $crate::increment_field!(@wrap $periph, $reg, $field, value))
+    };
+    ( $periph:path, $instance:expr, $reg:ident $([$offset:expr])*, $field:ident ) => {
+        $crate::modify_reg!($periph, $instance, $reg $([$offset])*,
+            $field: |value| $crate::increment_field!(@wrap $periph, $reg, $field, value))
+    };
+}
+
+/// Find the lowest set bit of a field in a register.
+///
+/// # Examples
+/// ```rust,ignore
+/// // Dispatch the lowest-numbered pending interrupt.
+/// if let Some(irq) = first_set_field_bit!(periph, inst, ISR, PENDING) {
+///     HANDLERS[irq as usize]();
+/// }
+/// ```
+///
+/// # Usage
+/// The arguments are the same as the single field form of `read_reg!`. The register is read
+/// once, and the macro evaluates to `Some(n)` where `n` is the index of the lowest set bit of
+/// the field's value, counting from the field's lowest bit, or `None` if no bit of the field is
+/// set. `n` is a `u32`.
+///
+/// # Safety
+/// As for `read_reg!`.
+#[macro_export]
+macro_rules! first_set_field_bit {
+    ( $periph:path, $instance:expr, $reg:ident $([$offset:expr])*, $field:ident ) => {{
+        let value = $crate::read_reg!($periph, $instance, $reg $([$offset])*, $field);
+        if value == 0 {
+            None
+        } else {
+            Some(value.trailing_zeros())
+        }
+    }};
+}
+
+/// Exchange the values of two registers.
+///
+/// # Examples
+/// ```rust,ignore
+/// // Swap the active and shadow configuration.
+/// swap_reg!(periph, inst, CFG_A, CFG_B);
+///
+/// // Swap two registers of an array.
+/// swap_reg!(periph, inst, CFG[0], CFG[1]);
+/// ```
+///
+/// # Usage
+/// The arguments are the path to the peripheral module, the instance, and two registers, each
+/// with one index per dimension if it is a register array. The macro performs two reads
+/// followed by two writes, in this order: read the first register, read the second, write the
+/// second register's value to the first, then write the first register's value to the second.
+/// Index expressions are evaluated twice.
+///
+/// This is not atomic: if either register is changed between the reads and the writes, that
+/// change is lost.
+///
+/// # Safety
+/// As for `modify_reg!`, for both registers.
+#[macro_export]
+macro_rules! swap_reg {
+    ( $periph:path, $instance:expr, $a:ident $([$a_offset:expr])*, $b:ident $([$b_offset:expr])* ) => {{
+        #[allow(unused_imports)]
+        use $periph::{*};
+        let a = $crate::register!($periph, $instance, $a $([$a_offset])*).read();
+        let b = $crate::register!($periph, $instance, $b $([$b_offset])*).read();
+        $crate::register!($periph, $instance, $a $([$a_offset])*).write(b);
+        $crate::register!($periph, $instance, $b $([$b_offset])*).write(a);
+    }};
+}
+
+/// Wait until a field in a register meets a condition.
+///
+/// # Examples
+/// ```rust,no_run
+/// # use ral_registers::wait_reg; fn main() {
+/// # let rcc = stm32ral::rcc::RCC::take().unwrap();
+/// // Spin until the HSE is ready.
+/// wait_reg!(stm32ral::rcc, rcc, CR, HSERDY == Ready);
+///
+/// // As above, but give up after 10,000 reads.
+/// match wait_reg!(stm32ral::rcc, rcc, CR, HSERDY == Ready, max_iters = 10_000) {
+///     Ok(()) => { /* the HSE is ready */ }
+///     Err(ral_registers::Timeout) => { /* the HSE did not start */ }
+/// }
+/// # }
+/// ```
+///
+/// # Usage
+/// The arguments are the same as the comparison form of `read_reg!`: a peripheral, an instance,
+/// a register, and a field followed by a comparison such as `BUSY == 0`. The register is read
+/// with `read_reg!` until the comparison is true. Every iteration performs a new volatile read
+/// of the register.
+///
+/// Without a limit, the macro spins forever until the comparison is true, and evaluates to
+/// `()`. With a trailing `max_iters = N` argument, the macro reads the register at most `N`
+/// times, and evaluates to `Ok(())` if the comparison became true, or `Err(Timeout)` if not.
+///
+/// To avoid spinning on a slow peripheral, a trailing `backoff = f` argument, after
+/// `max_iters` if both are given, calls the closure `f` between reads, each time the
+/// comparison is false and the register will be read again. The closure may sleep or yield,
+/// such as `backoff = || cortex_m::asm::wfe()`, and is not called once the comparison is true
+/// or the read limit is reached:
+/// ```rust,no_run
+/// # use ral_registers::wait_reg; fn main() {
+/// # let rcc = stm32ral::rcc::RCC::take().unwrap();
+/// // Spin more politely, and give up after 100 reads.
+/// let ready = wait_reg!(stm32ral::rcc, rcc, CR, HSERDY == Ready, max_iters = 100,
+///                       backoff = || core::hint::spin_loop());
+/// # }
+/// ```
+///
+/// # Safety
+/// As for `read_reg!`.
+#[macro_export]
+macro_rules! wait_reg {
+    ( @munch ($($args:tt)*) [$($cond:tt)+] , backoff = $backoff:expr ) => {{
+        let mut backoff = $backoff;
+        while !$crate::read_reg!($($args)*, $($cond)+) {
+            backoff();
+        }
+    }};
+    ( @munch ($($args:tt)*) [$($cond:tt)+] , max_iters = $max:expr , backoff = $backoff:expr ) => {{
+        let mut backoff = $backoff;
+        let mut result = Err($crate::Timeout);
+        for i in 0..$max {
+            if i != 0 {
+                backoff();
+            }
+            if $crate::read_reg!($($args)*, $($cond)+) {
+                result = Ok(());
+                break;
+            }
+        }
+        result
+    }};
+    ( @munch ($($args:tt)*) [$($cond:tt)+] , max_iters = $max:expr ) => {{
+        let mut result = Err($crate::Timeout);
+        for _ in 0..$max {
+            if $crate::read_reg!($($args)*, $($cond)+) {
+                result = Ok(());
+                break;
+            }
+        }
+        result
+    }};
+    ( @munch ($($args:tt)*) [$($cond:tt)+] $next:tt $($rest:tt)* ) => {
+        $crate::wait_reg!(@munch ($($args)*) [$($cond)+ $next] $($rest)*)
+    };
+    ( @munch ($($args:tt)*) [$($cond:tt)+] ) => {
+        while !$crate::read_reg!($($args)*, $($cond)+) {}
+    };
+    ( $periph:path, $instance:expr, $reg:ident $([$offset:expr])*, $field:ident $($rest:tt)+ ) => {
+        $crate::wait_reg!(@munch ($periph, $instance, $reg $([$offset])*) [$field] $($rest)+)
+    };
+}
+
+/// Wait until a field in a register meets a condition, and count the reads it took.
+///
+/// # Examples
+/// ```rust,no_run
+/// # use ral_registers::poll_reg; fn main() {
+/// # let rcc = stm32ral::rcc::RCC::take().unwrap();
+/// // Spin until the HSE is ready, and find out how long it took.
+/// let reads = poll_reg!(stm32ral::rcc, rcc, CR, HSERDY == Ready);
+///
+/// // As above, but give up after 10,000 reads.
+/// match poll_reg!(stm32ral::rcc, rcc, CR, HSERDY == Ready, max_iters = 10_000) {
+///     Ok(reads) => { /* the HSE is ready after `reads` reads */ }
+///     Err(ral_registers::Timeout) => { /* the HSE did not start */ }
+/// }
+/// # }
+/// ```
+///
+/// # Usage
+/// The arguments are the same as for `wait_reg!`, and the register is read in the same way.
+/// Without a limit, the macro evaluates to the number of reads performed as a `u32`, including
+/// the read which met the condition. The count saturates at `u32::MAX`. With a trailing
+/// `max_iters = N` argument, where `N` is a `u32`, the macro evaluates to `Ok(reads)` if the
+/// comparison became true, or `Err(Timeout)` after `N` reads if not.
+///
+/// # Safety
+/// As for `read_reg!`.
+#[macro_export]
+macro_rules! poll_reg {
+    ( @munch ($($args:tt)*) [$($cond:tt)+] , max_iters = $max:expr ) => {{
+        let max: u32 = $max;
+        let mut result = Err($crate::Timeout);
+        let mut reads: u32 = 0;
+        while reads < max {
+            reads += 1;
+            if $crate::read_reg!($($args)*, $($cond)+) {
+                result = Ok(reads);
+                break;
+            }
+        }
+        result
+    }};
+    ( @munch ($($args:tt)*) [$($cond:tt)+] $next:tt $($rest:tt)* ) => {
+        $crate::poll_reg!(@munch ($($args)*) [$($cond)+ $next] $($rest)*)
+    };
+    ( @munch ($($args:tt)*) [$($cond:tt)+] ) => {{
+        let mut reads: u32 = 1;
+        while !$crate::read_reg!($($args)*, $($cond)+) {
+            reads = reads.saturating_add(1);
+        }
+        reads
+    }};
+    ( $periph:path, $instance:expr, $reg:ident $([$offset:expr])*, $field:ident $($rest:tt)+ ) => {
+        $crate::poll_reg!(@munch ($periph, $instance, $reg $([$offset])*) [$field] $($rest)+)
+    };
+}
+
+/// Read a register and decode the named fields for display.
+///
+/// # Examples
+/// ```rust,no_run
+/// # use ral_registers::dump_reg; fn main() {
+/// # let gpioa = stm32ral::gpio::GPIOA::take().unwrap();
+/// // Displays as something like `MODER { MODER3: 0x1, MODER4: 0x3 }`.
+/// let dump = dump_reg!(stm32ral::gpio, gpioa, MODER, MODER3, MODER4);
+/// # }
+/// ```
+///
+/// # Usage
+/// The arguments are the same as the multiple field form of `read_reg!`. The register is read
+/// once, and the macro evaluates to a `RegisterDump`, which implements `Display` and `Debug`
+/// by listing each field with its value in hexadecimal. For register arrays, the name
+/// includes the index of the register that was read, such as `MY_ARRAY[1]`.
+///
+/// Array index expressions are evaluated twice: once to read the register, and once more to
+/// record the index.
+///
+/// # Safety
+/// As for `read_reg!`.
+#[macro_export]
+macro_rules! dump_reg {
+    ( $periph:path, $instance:expr, $reg:ident $([$offset:expr])*, $( $field:ident ),+ ) => {{
+        #[allow(unused_imports)]
+        use $periph::{*};
+        let val = $crate::register!($periph, $instance, $reg $([$offset])*).read();
+        $crate::RegisterDump::new(
+            stringify!($reg),
+            [$($offset),*],
+            [$((
+                stringify!($field),
+                (val & $crate::field_mask!($periph, $reg, $field))
+                    >> $crate::field_offset!($periph, $reg, $field),
+            )),+],
+        )
+    }};
+}
+
+/// Read a register and display it as a table of every field.
+///
+/// # Examples
+/// ```rust,ignore
+/// // Log GPIOA's mode register during bring-up.
+/// writeln!(serial, "{}", field_table!(periph, gpioa, MODER)).ok();
+/// ```
+///
+/// # Usage
+/// The arguments are the same as the whole-register form of `read_reg!`. The register module
+/// must implement `RegisterFields` with `impl_register_fields!`, which lists the fields to
+/// display. The register is read once, when the macro is evaluated, and not when the table is
+/// formatted; format the result promptly to log the register's current value. The macro
+/// evaluates to a `FieldTable`, which implements `Display`.
+///
+/// Array index expressions are evaluated twice: once to read the register, and once more to
+/// record the index.
+///
+/// # Safety
+/// As for `read_reg!`.
+#[macro_export]
+macro_rules! field_table {
+    ( $periph:path, $instance:expr, $reg:ident $([$offset:expr])* ) => {{
+        #[allow(unused_imports)]
+        use $periph::{*};
+        let val = $crate::register!($periph, $instance, $reg $([$offset])*).read();
+        let indices = [$($offset),*];
+        #[allow(clippy::unnecessary_cast)]
+        let val = val as u64;
+        {
+            use $periph::{$reg::{Fields}};
+            $crate::FieldTable::new(Fields, indices, val)
+        }
+    }};
+}
+
+/// Read a field holding a two's-complement value, sign-extending it.
+///
+/// # Examples
+/// ```rust,ignore
+/// // TRIM is a signed 12-bit calibration value.
+/// let trim: i32 = read_signed_field!(periph, adc, CAL, TRIM);
+/// ```
+///
+/// # Usage
+/// The arguments are the same as the single field form of `read_reg!`. The field is read as
+/// `read_reg!` would, and its highest bit, given by the width of its mask, is taken as the sign
+/// bit. The macro evaluates to the signed integer type of the register's width, such as `i32`
+/// for a `u32` register, so a 4-bit field holding `0b1111` reads as `-1`.
+///
+/// # Safety
+/// As for `read_reg!`.
+#[macro_export]
+macro_rules! read_signed_field {
+    ( $periph:path, $instance:expr, $reg:ident $([$offset:expr])*, $field:ident ) => {{
+        #[allow(unused_imports)]
+        use $periph::{*};
+        let val = $crate::register!($periph, $instance, $reg $([$offset])*).read();
+        $crate::must_use($crate::SignExtend::sign_extend(
+            $crate::extract_field!($periph, $reg, $field, val),
+            $crate::field_mask!($periph, $reg, $field).count_ones(),
+        ))
+    }};
+}
+
+/// Read a field, and convert it to the enum of values the generator provides for it.
+///
+/// # Examples
+/// ```rust,ignore
+/// match read_enum!(periph, uart, SR, STATE) {
+///     Ok(periph::SR::STATE::R::State::Idle) => { /* ... */ }
+///     Ok(periph::SR::STATE::R::State::Busy) => { /* ... */ }
+///     Err(bits) => { /* a reserved value */ }
+/// }
+/// ```
+///
+/// # Usage
+/// The arguments are the same as the single field form of `read_reg!`. The field's `R` module
+/// must provide a function converting the field value to an enum:
+/// ```rust
+/// # #![allow(non_snake_case)]
+/// pub mod STATE {
+///     pub const offset: u32 = 0;
+///     pub const mask: u32 = 0b11 << offset;
+///     pub mod R {
+///         #[derive(Debug, PartialEq, Eq)]
+///         pub enum State {
+///             Idle = 0,
+///             Busy = 1,
+///         }
+///
+///         /// Converts a value of the field, returning it unchanged if it has no variant.
+///         pub fn from_bits(bits: u32) -> Result<State, u32> {
+///             match bits {
+///                 0 => Ok(State::Idle),
+///                 1 => Ok(State::Busy),
+///                 _ => Err(bits),
+///             }
+///         }
+///     }
+/// }
+/// ```
+/// The enum may have any name, and `from_bits` may be written with a `TryFrom` implementation.
+/// The macro reads the field as `read_reg!` would and evaluates to the result of `from_bits`:
+/// `Ok` of the variant, or `Err` of the field value if it is not one of the enum's values.
+///
+/// # Safety
+/// As for `read_reg!`.
+#[macro_export]
+macro_rules! read_enum {
+    ( $periph:path, $instance:expr, $reg:ident $([$offset:expr])*, $field:ident ) => {{
+        let bits = $crate::read_reg!($periph, $instance, $reg $([$offset])*, $field);
+        {
+            use $periph::{$reg::$field::R::{from_bits}};
+            from_bits(bits)
+        }
+    }};
+}
+
+/// Read a register and decode the named fields into a struct.
+///
+/// # Examples
+/// ```rust,no_run
+/// # use ral_registers::read_reg_struct; fn main() {
+/// # let gpioa = stm32ral::gpio::GPIOA::take().unwrap();
+/// let moder = read_reg_struct!(stm32ral::gpio, gpioa, MODER, { MODER3, MODER4 });
+/// if moder.MODER3 == moder.MODER4 {
+///     /* PA3 and PA4 have the same mode */
+/// }
+/// # }
+/// ```
+///
+/// # Usage
+/// The arguments are the same as the multiple field form of `read_reg!`, except that the
+/// fields are in braces. The register is read once, and the macro evaluates to a struct with
+/// one public field for each named field, holding that field's value. The struct is defined by
+/// the macro expansion, so it has no name that can be written in a type. It implements `Clone`,
+/// `Copy`, `Debug`, `PartialEq` and `Eq`.
+///
+/// # Safety
+/// As for `read_reg!`.
+#[macro_export]
+macro_rules! read_reg_struct {
+    ( $periph:path, $instance:expr, $reg:ident $([$offset:expr])*, { $( $field:ident ),+ $(,)? } ) => {{
+        #[allow(non_snake_case, dead_code)]
+        #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+        struct Fields<T> {
+            $( $field: T ),+
+        }
+        let val = $crate::read_reg!($periph, $instance, $reg $([$offset])*);
+        $crate::must_use(Fields {
+            $( $field: $crate::extract_field!($periph, $reg, $field, val) ),+
+        })
+    }};
+}
+
+/// Read a range of registers from a register array.
+///
+/// # Usage
+/// The first arguments are the path to the peripheral module, the instance, and the register
+/// array, followed by a range with constant bounds: `read_many!(periph, inst, FIFO, 0..4)`.
+/// Indices for outer dimensions of a multi-dimensional array may be given before the range:
+/// `read_many!(periph, inst, MATRIX[1], 0..4)`.
+///
+/// The macro evaluates to an array containing the value of each register in the range. Each
+/// register is read with its own volatile read, in ascending index order, which matters for
+/// registers where reads have side effects such as hardware FIFOs. The macro panics if the
+/// range is out of bounds.
+///
+/// # Safety
+/// As for `read_reg!`.
+#[macro_export]
+macro_rules! read_many {
+    ( $periph:path, $instance:expr, $reg:ident $([$offset:expr])*, $start:tt .. $end:tt ) => {{
+        #[allow(unused_imports)]
+        use $periph::{*};
+        let start: usize = $start;
+        let mut values = [Default::default(); $end - $start];
+        for (i, value) in values.iter_mut().enumerate() {
+            *value = $crate::register!($periph, $instance, $reg $([$offset])* [start + i]).read();
+        }
+        $crate::must_use(values)
+    }};
+}
+
+/// Modify fields of a register through its SET and CLR alias registers.
+///
+/// # Usage
+/// Many peripherals pair a register with SET and CLR registers, where writing a 1 to a bit of
+/// the SET register sets that bit of the register, writing a 1 to a bit of the CLR register
+/// clears it, and writing a 0 to either has no effect. The arguments are:
+/// * the path to the peripheral module: `periph`,
+/// * the instance of that peripheral: `gpio`,
+/// * the register which describes the fields: `DIR`,
+/// * the SET register: `DIR_SET`,
+/// * the CLR register: `DIR_CLR`,
+/// * one or more `Field: Value` pairs, as for `modify_reg!`.
+///
+/// ```rust,ignore
+/// // Set PIN3, clear PIN4, and leave every other bit of DIR untouched.
+/// modify_atomic!(periph, gpio, DIR, DIR_SET, DIR_CLR, PIN3: 1, PIN4: 0);
+/// ```
+///
+/// The macro computes the field values as `write_reg!` would, then writes the bits that should
+/// be 1 to the SET register, and the bits of each field's mask that should be 0 to the CLR
+/// register. The SET register is written first, and a register is not written at all if it
+/// would be written with zero. The register itself is never read or written, so unlike
+/// `modify_reg!` this cannot tear with another bus master or interrupt handler modifying
+/// other bits.
+///
+/// # Safety
+/// As for `write_reg!`, for the SET and CLR registers.
+#[macro_export]
+macro_rules! modify_atomic {
+    ( $periph:path, $instance:expr, $reg:ident,
+      $set:ident $([$set_offset:expr])*, $clr:ident $([$clr_offset:expr])*,
+      $( $field:ident : $value:expr ),+ ) => {{
+        #[allow(unused_imports)]
+        use $periph::{*};
+        let fields_mask = $($crate::field_mask!($periph, $reg, $field)) | *;
+        let set_bits = $crate::build_value!(@checked $periph, $reg, $( $field : $value ),+);
+        let clr_bits = fields_mask & !set_bits;
+        if set_bits != 0 {
+            $crate::register!($periph, $instance, $set $([$set_offset])*).write(set_bits);
+        }
+        if clr_bits != 0 {
+            $crate::register!($periph, $instance, $clr $([$clr_offset])*).write(clr_bits);
+        }
+    }};
+}
+
+/// Modify a RWRegister or UnsafeRWRegister inside a critical section.
+///
+/// # Usage
+/// Accepts the same arguments as `modify_reg!`, and evaluates to the value that was written.
+/// The read-modify-write runs inside `critical_section::with`, so no interrupt handler can
+/// modify the register between the read and the write.
+///
+/// Requires the `critical-section` feature, and a `critical-section` implementation for the
+/// target.
+///
+/// # Safety
+/// As for `modify_reg!`.
+#[cfg(feature = "critical-section")]
+#[macro_export]
+macro_rules! modify_reg_cs {
+    ( $($args:tt)+ ) => {
+        $crate::critical_section::with(|_| $crate::modify_reg!($($args)+))
+    };
+}
+
+/// Read a pair of registers holding the high and low halves of a counter, as one `u64`.
+///
+/// # Examples
+/// ```rust,ignore
+/// let ticks: u64 = read_pair!(periph, timer, CNT_HI, CNT_LO);
+/// ```
+///
+/// # Usage
+/// The arguments are the path to the peripheral module, the instance, the register holding
+/// the high half, and the register holding the low half. Each register may be followed by its
+/// indices if it is a register array. The macro evaluates to `(hi << bits) | lo`, where `bits`
+/// is the width of the low register, so the registers may be at most 32 bits wide.
+///
+/// A counter which keeps running may roll over from the low half into the high half between
+/// the two reads, which would combine a new high half with an old low half. To avoid this,
+/// the macro reads the high half, then the low half, then the high half again. If the high
+/// half is unchanged, no rollover happened between the reads, and the first two reads are
+/// combined. Otherwise, the macro reads the low half again and repeats the check with the
+/// latest high half, until the high half is stable. This performs three reads, or two more
+/// for each rollover. See `read_pair_cs!` to also keep interrupts from delaying the reads.
+///
+/// # Safety
+/// As for `read_reg!`.
+#[macro_export]
+macro_rules! read_pair {
+    ( $periph:path, $instance:expr, $hi:ident $([$hi_offset:expr])*, $lo:ident $([$lo_offset:expr])* ) => {{
+        #[allow(unused_imports)]
+        use $periph::{*};
+        let mut hi = $crate::register!($periph, $instance, $hi $([$hi_offset])*).read();
+        $crate::must_use(loop {
+            let lo = $crate::register!($periph, $instance, $lo $([$lo_offset])*).read();
+            let next = $crate::register!($periph, $instance, $hi $([$hi_offset])*).read();
+            if next == hi {
+                #[allow(clippy::unnecessary_cast)]
+                break ((hi as u64) << $crate::bit_width(&lo)) | lo as u64;
+            }
+            hi = next;
+        })
+    }};
+}
+
+/// Read a pair of registers like `read_pair!`, inside a critical section.
+///
+/// # Usage
+/// Accepts the same arguments as `read_pair!`, and evaluates to the combined value. The reads
+/// run inside `critical_section::with`, so an interrupt handler cannot delay them, and the
+/// value is as close as possible to the counter's value when the macro was called.
+///
+/// Requires the `critical-section` feature, and a `critical-section` implementation for the
+/// target.
+///
+/// # Safety
+/// As for `read_pair!`.
+#[cfg(feature = "critical-section")]
+#[macro_export]
+macro_rules! read_pair_cs {
+    ( $($args:tt)+ ) => {
+        $crate::must_use($crate::critical_section::with(|_| $crate::read_pair!($($args)+)))
+    };
 }
 
-/// Write to a RWRegister or UnsafeRWRegister.
+/// The order in which `write_reg64!` and `read_reg64!` access the halves of a 64-bit value.
+///
+/// Hardware which splits a 64-bit value across two registers often latches it when one of the
+/// halves is accessed. For example, writing the high half may commit the previously written low
+/// half, or reading the low half may capture the high half for the next read. The order must
+/// then match the hardware's expectations.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WordOrder {
+    /// Access the low half, then the high half.
+    LoFirst,
+    /// Access the high half, then the low half.
+    HiFirst,
+}
+
+/// Write a `u64` to a pair of 32-bit registers holding its low and high halves.
 ///
 /// # Examples
-/// ```rust,no_run
-/// # use stm32ral::{read_reg, write_reg, modify_reg, reset_reg}; fn main() {
-/// // Safely acquire the peripheral instance (will panic if already acquired)
-/// let gpioa = stm32ral::gpio::GPIOA::take().unwrap();
+/// ```rust,ignore
+/// use ral_registers::WordOrder;
+/// write_reg64!(periph, timer, CMP_LO, CMP_HI, 0x1_0000_0002);
+/// write_reg64!(periph, timer, CMP_LO, CMP_HI, 0x1_0000_0002, order = WordOrder::HiFirst);
+/// ```
 ///
-/// // Write some value to the register.
-/// write_reg!(stm32ral::gpio, gpioa, ODR, 1<<3);
+/// # Usage
+/// The arguments are the path to the peripheral module, the instance, the register holding
+/// the low half, the register holding the high half, and the `u64` value. Each register may be
+/// followed by its indices if it is a register array. Unlike `read_pair!`, the low register
+/// comes first. The low register receives bits 0 to 31 of the value, and the high register
+/// receives bits 32 to 63. Both registers must hold `u32`s.
 ///
-/// // Write values to specific fields. Unspecified fields are written to 0.
-/// write_reg!(stm32ral::gpio, gpioa, MODER, MODER3: Output, MODER4: Analog);
+/// By default, the macro writes the low half, then the high half. Pass `order =
+/// WordOrder::HiFirst` to write the high half first. The two writes are separate volatile
+/// writes, so an interrupt handler may run between them. Call the macro inside a critical
+/// section if a handler must not observe a half written value.
 ///
-/// // Unsafe access without requiring you to first `take()` the instance
-/// unsafe { write_reg!(stm32ral::gpio, GPIOA, MODER, MODER3: Output, MODER4: Analog) };
-/// # }
+/// # Safety
+/// As for `write_reg!`.
+#[macro_export]
+macro_rules! write_reg64 {
+    ( $periph:path, $instance:expr, $lo:ident $([$lo_offset:expr])*, $hi:ident $([$hi_offset:expr])*, $value:expr, order = $order:expr ) => {{
+        #[allow(unused_imports)]
+        use $periph::{*};
+        let value: u64 = $value;
+        let lo = value as u32;
+        let hi = (value >> 32) as u32;
+        match $order {
+            $crate::WordOrder::LoFirst => {
+                $crate::register!($periph, $instance, $lo $([$lo_offset])*).write(lo);
+                $crate::register!($periph, $instance, $hi $([$hi_offset])*).write(hi);
+            }
+            $crate::WordOrder::HiFirst => {
+                $crate::register!($periph, $instance, $hi $([$hi_offset])*).write(hi);
+                $crate::register!($periph, $instance, $lo $([$lo_offset])*).write(lo);
+            }
+        }
+    }};
+    ( $periph:path, $instance:expr, $lo:ident $([$lo_offset:expr])*, $hi:ident $([$hi_offset:expr])*, $value:expr ) => {
+        $crate::write_reg64!($periph, $instance, $lo $([$lo_offset])*, $hi $([$hi_offset])*, $value,
+            order = $crate::WordOrder::LoFirst)
+    };
+}
+
+/// Read a `u64` from a pair of 32-bit registers holding its low and high halves.
+///
+/// # Examples
+/// ```rust,ignore
+/// use ral_registers::WordOrder;
+/// let cmp: u64 = read_reg64!(periph, timer, CMP_LO, CMP_HI);
+/// let cmp: u64 = read_reg64!(periph, timer, CMP_LO, CMP_HI, order = WordOrder::HiFirst);
 /// ```
 ///
 /// # Usage
-/// Like `modify_reg!`, this macro can be used in two ways, either with a single value to write to
-/// the whole register, or with multiple fields each with their own value.
+/// Accepts the same arguments as `write_reg64!`, without the value, and evaluates to
+/// `(hi << 32) | lo`. By default, the macro reads the low half, then the high half. Pass
+/// `order = WordOrder::HiFirst` to read the high half first.
 ///
-/// In both cases, the first arguments are:
-/// * the path to the peripheral module: `stm32ral::gpio`,
-/// * a reference to the instance of that peripheral: 'gpioa' (anything which dereferences to
-///   `RegisterBlock`, such as `Instance`, `&Instance`, `&RegisterBlock`, or
-///   `*const RegisterBlock`),
-/// * the register you wish you access: `MODER` (a field on the `RegisterBlock`).
+/// Each half is read once. If the hardware can change the value between the two reads, as
+/// for a running counter, use `read_pair!`, which detects a rollover between the halves.
 ///
-/// In the single-value usage, the final argument is just the value to write:
-/// ```rust,no_run
-/// # use stm32ral::{read_reg, write_reg, modify_reg, reset_reg}; fn main() {
-/// # let gpioa = stm32ral::gpio::GPIOA::take().unwrap();
-/// // Turn on PA3 (and turn everything else off).
-/// write_reg!(stm32ral::gpio, gpioa, ODR, 1<<3);
-/// # }
-/// ```
+/// # Safety
+/// As for `read_reg!`.
+#[macro_export]
+macro_rules! read_reg64 {
+    ( $periph:path, $instance:expr, $lo:ident $([$lo_offset:expr])*, $hi:ident $([$hi_offset:expr])*, order = $order:expr ) => {{
+        #[allow(unused_imports)]
+        use $periph::{*};
+        let (lo, hi): (u32, u32) = match $order {
+            $crate::WordOrder::LoFirst => {
+                let lo = $crate::register!($periph, $instance, $lo $([$lo_offset])*).read();
+                (lo, $crate::register!($periph, $instance, $hi $([$hi_offset])*).read())
+            }
+            $crate::WordOrder::HiFirst => {
+                let hi = $crate::register!($periph, $instance, $hi $([$hi_offset])*).read();
+                ($crate::register!($periph, $instance, $lo $([$lo_offset])*).read(), hi)
+            }
+        };
+        $crate::must_use((hi as u64) << 32 | lo as u64)
+    }};
+    ( $periph:path, $instance:expr, $lo:ident $([$lo_offset:expr])*, $hi:ident $([$hi_offset:expr])* ) => {
+        $crate::read_reg64!($periph, $instance, $lo $([$lo_offset])*, $hi $([$hi_offset])*,
+            order = $crate::WordOrder::LoFirst)
+    };
+}
+
+/// Write to a RWRegister or UnsafeRWRegister, then issue a memory barrier.
 ///
-/// Otherwise, the remaining arguments are each `Field: Value` pairs:
-/// ```rust,no_run
-/// # use stm32ral::{read_reg, write_reg, modify_reg, reset_reg}; fn main() {
-/// // Set PA3 to Output, PA4 to Analog, and everything else to 0 (which is Input).
-/// # let gpioa = stm32ral::gpio::GPIOA::take().unwrap();
-/// write_reg!(stm32ral::gpio, gpioa, MODER, MODER3: 0b01, MODER4: 0b11);
-/// # }
-/// ```
-/// For fields with annotated values, you can also specify a named value:
-/// ```rust,no_run
-/// # use stm32ral::{read_reg, write_reg, modify_reg, reset_reg}; fn main() {
-/// // As above, but with named values.
-/// # let gpioa = stm32ral::gpio::GPIOA::take().unwrap();
-/// write_reg!(stm32ral::gpio, gpioa, MODER, MODER3: Output, MODER4: Analog);
-/// # }
-/// ```
+/// # Usage
+/// Accepts the same arguments as `write_reg!`. After the volatile write, the macro issues a
+/// `core::sync::atomic::compiler_fence(Ordering::SeqCst)`, so the compiler does not move
+/// memory accesses across the write. With the `cortex-m` feature, on ARM targets, it also
+/// issues a `DMB` instruction before the fence, so the write completes before any later
+/// memory access, for example before touching a peripheral whose clock was just enabled.
 ///
-/// This macro expands to calling `(*$instance).$register.write(value)`,
-/// where in the second usage, the value is computed as the bitwise OR of
-/// each field value, which are masked and shifted appropriately for the given field.
-/// The named values are brought into scope by `use $peripheral::$register::$field::*` for
-/// each field. The same constants could just be specified manually:
-/// ```rust,no_run
-/// # use stm32ral::{read_reg, write_reg, modify_reg, reset_reg}; fn main() {
-/// // As above, but being explicit about named values.
-/// # let gpioa = stm32ral::gpio::GPIOA::take().unwrap();
-/// write_reg!(stm32ral::gpio, gpioa, MODER, MODER3: stm32ral::gpio::MODER::MODER3::RW::Output,
-///                                          MODER4: stm32ral::gpio::MODER::MODER4::RW::Analog);
-/// # }
+/// # Safety
+/// As for `write_reg!`.
+#[macro_export]
+macro_rules! write_reg_barrier {
+    ( $($args:tt)+ ) => {{
+        $crate::write_reg!($($args)+);
+        $crate::barrier();
+    }};
+}
+
+/// Modify a RWRegister or UnsafeRWRegister, then issue a memory barrier.
+///
+/// # Usage
+/// Accepts the same arguments as `modify_reg!`, and evaluates to the value that was written.
+/// The barrier is the same as for `write_reg_barrier!`.
+///
+/// # Safety
+/// As for `modify_reg!`.
+#[macro_export]
+macro_rules! modify_reg_barrier {
+    ( $($args:tt)+ ) => {{
+        let value = $crate::modify_reg!($($args)+);
+        $crate::barrier();
+        value
+    }};
+}
+
+/// Read from a register, then issue a compiler fence with the given ordering.
+///
+/// # Examples
+/// ```rust,ignore
+/// use core::sync::atomic::Ordering;
+///
+/// // Once the DMA channel reports completion, read the buffer it wrote.
+/// if read_reg_fenced!(Ordering::Acquire, periph, dma, ISR, TCIF1 == Complete) {
+///     let data = BUFFER[0];
+/// }
 /// ```
 ///
-/// The fully expanded form is equivalent to:
-/// ```rust,no_run
-/// # use stm32ral::{read_reg, write_reg, modify_reg, reset_reg}; fn main() {
-/// // As above, but expanded.
-/// # let gpioa = stm32ral::gpio::GPIOA::take().unwrap();
-/// (*gpioa).MODER.write(
-///     ((stm32ral::gpio::MODER::MODER3::RW::Output << stm32ral::gpio::MODER::MODER3::offset)
-///      & stm32ral::gpio::MODER::MODER3::mask)
-///     |
-///     ((stm32ral::gpio::MODER::MODER4::RW::Analog << stm32ral::gpio::MODER::MODER4::offset)
-///      & stm32ral::gpio::MODER::MODER4::mask)
-/// );
-/// # }
+/// # Usage
+/// The first argument is a `core::sync::atomic::Ordering`, followed by the same arguments as
+/// `read_reg!`. The macro evaluates to the same value as `read_reg!`.
+///
+/// A volatile access is never reordered with other volatile accesses, but the compiler may
+/// move ordinary memory accesses, such as to a DMA buffer, across it. After the read, this
+/// macro calls `core::sync::atomic::compiler_fence(ordering)`; with `Acquire`, memory accesses
+/// after the macro are not moved before the read. The fence does not emit any instruction, so
+/// it does not order accesses at the hardware level; see `write_reg_barrier!` for that.
+///
+/// Panics if `ordering` is `Relaxed`, and a literal `Relaxed` is rejected at compile time.
+///
+/// # Safety
+/// As for `read_reg!`.
+#[macro_export]
+macro_rules! read_reg_fenced {
+    ( $ordering:expr, $($args:tt)+ ) => {{
+        let value = $crate::read_reg!($($args)+);
+        ::core::sync::atomic::compiler_fence($ordering);
+        $crate::must_use(value)
+    }};
+}
+
+/// Issue a compiler fence with the given ordering, then write to a register.
+///
+/// # Examples
+/// ```rust,ignore
+/// use core::sync::atomic::Ordering;
+///
+/// // Fill the buffer, then start the DMA channel which reads it.
+/// BUFFER[0] = 42;
+/// write_reg_fenced!(Ordering::Release, periph, dma, CCR1, EN: Enabled);
 /// ```
 ///
+/// # Usage
+/// The first argument is a `core::sync::atomic::Ordering`, followed by the same arguments as
+/// `write_reg!`.
+///
+/// Before the write, this macro calls `core::sync::atomic::compiler_fence(ordering)`; with
+/// `Release`, memory accesses before the macro are not moved after the write. As for
+/// `read_reg_fenced!`, the fence only constrains the compiler. The fence is before the write,
+/// rather than after it, so that it orders the accesses which the write publishes.
+///
+/// Panics if `ordering` is `Relaxed`, and a literal `Relaxed` is rejected at compile time.
+///
 /// # Safety
-/// This macro will require an unsafe function or block when used with an UnsafeRWRegister,
-/// but not if used with RWRegister.
+/// As for `write_reg!`.
+#[macro_export]
+macro_rules! write_reg_fenced {
+    ( $ordering:expr, $($args:tt)+ ) => {{
+        ::core::sync::atomic::compiler_fence($ordering);
+        $crate::write_reg!($($args)+);
+    }};
+}
+
+/// Get the address of a register.
 ///
-/// When run in an unsafe context, peripheral instances are directly accessible without requiring
-/// having called `take()` beforehand:
+/// # Examples
 /// ```rust,no_run
-/// # use stm32ral::{read_reg, write_reg, modify_reg, reset_reg}; fn main() {
-/// unsafe { write_reg!(stm32ral::gpio, GPIOA, MODER, MODER3: Output, MODER4: Analog) };
+/// # use ral_registers::reg_addr; fn main() {
+/// # let gpioa = stm32ral::gpio::GPIOA::take().unwrap();
+/// // Prints something like "ODR is at 0x48000014".
+/// let addr = reg_addr!(stm32ral::gpio, gpioa, ODR);
+/// println!("ODR is at {:#010x}", addr);
 /// # }
 /// ```
-/// This works because `GPIOA` is a `*const RegisterBlock` in the `stm32ral::gpio` module;
-/// and the macro brings such constants into scope and then dereferences the provided reference.
+///
+/// # Usage
+/// The arguments are the path to the peripheral module, the instance, and the register,
+/// followed by its indices if it is a register array, as for `read_reg!`. The macro evaluates
+/// to the address of the register as a `usize`, the same address `as_ptr()` returns. The
+/// register is not accessed.
+///
+/// # Safety
+/// Requires an unsafe block when the instance is a raw pointer, since the macro dereferences
+/// the pointer to find the register. Otherwise the macro is safe.
 #[macro_export]
-macro_rules! write_reg {
-    ( $periph:path, $instance:expr, $reg:ident $([$offset:expr])*, $( $field:ident : $value:expr ),+ ) => {{
+macro_rules! reg_addr {
+    ( $periph:path, $instance:expr, $reg:ident $([$offset:expr])* ) => {{
         #[allow(unused_imports)]
         use $periph::{*};
-        #[allow(unused_imports)]
-        (*$instance).$reg $([$offset])*.write(
-            $({
-                use $periph::{$reg::$field::{W::*, RW::*}};
-                ($value << { use $periph::{$reg::$field::offset}; offset })
-                    & { use $periph::{$reg::$field::mask}; mask }
-            }) | *
-        );
+        &$crate::register!($periph, $instance, $reg $([$offset])*) as *const _ as usize
     }};
-    ( $periph:path, $instance:expr, $reg:ident $([$offset:expr])*, $value:expr ) => {{
+}
+
+/// Iterate over the registers in a register array.
+///
+/// # Usage
+/// The arguments are the path to the peripheral module, the instance, and the register array.
+/// Indices for outer dimensions of a multi-dimensional array may be given after the register
+/// array, such as `MATRIX[1]`. The macro evaluates to an iterator over references to each
+/// register in the array, which may be used with `read_reg_ref!`, `write_reg_ref!`, and
+/// `modify_reg_ref!`.
+///
+/// This iterates over the storage of the register array, so for arrays whose register module
+/// defines an `index` mapping, it includes any padding registers.
+///
+/// # Safety
+/// This macro requires an unsafe block when used with a `*const RegisterBlock` instance.
+#[macro_export]
+macro_rules! iter_array {
+    ( $periph:path, $instance:expr, $reg:ident $([$offset:expr])* ) => {{
         #[allow(unused_imports)]
         use $periph::{*};
-        (*$instance).$reg $([$offset])*.write($value);
+        $crate::register!($periph, $instance, $reg $([$offset])*).iter()
     }};
 }
 
-/// Modify a RWRegister or UnsafeRWRegister.
+/// Get a reference to a register block at a constant address.
 ///
 /// # Examples
 /// ```rust,no_run
-/// # use stm32ral::{read_reg, write_reg, modify_reg, reset_reg}; fn main() {
-/// // Safely acquire the peripheral instance (will panic if already acquired)
-/// let gpioa = stm32ral::gpio::GPIOA::take().unwrap();
-///
-/// // Update the register to ensure bit 3 is set.
-/// modify_reg!(stm32ral::gpio, gpioa, ODR, |reg| reg | (1<<3));
-///
-/// // Write values to specific fields. Unspecified fields are left unchanged.
-/// modify_reg!(stm32ral::gpio, gpioa, MODER, MODER3: Output, MODER4: Analog);
-///
-/// // Unsafe access without requiring you to first `take()` the instance
-/// unsafe { modify_reg!(stm32ral::gpio, GPIOA, MODER, MODER3: Output, MODER4: Analog) };
+/// # use ral_registers::peripheral; fn main() {
+/// let gpioa: &stm32ral::gpio::RegisterBlock =
+///     unsafe { peripheral!(stm32ral::gpio::RegisterBlock, 0x4800_0000) };
+/// let idr = gpioa.IDR.read();
 /// # }
 /// ```
 ///
 /// # Usage
-/// Like `write_reg!`, this macro can be used in two ways, either with a modification of the entire
-/// register, or by specifying which fields to change and what value to change them to.
+/// The arguments are the type of the register block, and its base address, which must be a
+/// constant expression. The macro checks at compile time that the address is aligned for the
+/// type, and evaluates to `peripheral::<T>(base)`, a `&'static T`. Use the `peripheral`
+/// function directly for an address which is not known at compile time.
 ///
-/// In both cases, the first arguments are:
-/// * the path to the peripheral module: `stm32ral::gpio`,
-/// * a reference to the instance of that peripheral: 'gpioa' (anything which dereferences to
-///   `RegisterBlock`, such as `Instance`, `&Instance`, `&RegisterBlock`, or
-///   `*const RegisterBlock`),
-/// * the register you wish you access: `MODER` (a field on the `RegisterBlock`).
+/// Peripherals with the same layout, such as `USART1` and `USART2`, share one register block
+/// type at different base addresses, and each instance is a separate reference:
+/// ```rust,ignore
+/// fn usart1() -> &'static usart::RegisterBlock {
+///     unsafe { peripheral!(usart::RegisterBlock, 0x4001_3800) }
+/// }
 ///
-/// In the whole-register usage, the final argument is a closure that accepts the current value
-/// of the register and returns the new value to write:
-/// ```rust,no_run
-/// # use stm32ral::{read_reg, write_reg, modify_reg, reset_reg}; fn main() {
-/// # let gpioa = stm32ral::gpio::GPIOA::take().unwrap();
-/// // Turn on PA3 without affecting anything else.
-/// modify_reg!(stm32ral::gpio, gpioa, ODR, |reg| reg | (1<<3));
-/// # }
-/// ```
+/// fn usart2() -> &'static usart::RegisterBlock {
+///     unsafe { peripheral!(usart::RegisterBlock, 0x4000_4400) }
+/// }
 ///
-/// Otherwise, the remaining arguments are `Field: Value` pairs:
-/// ```rust,no_run
-/// # use stm32ral::{read_reg, write_reg, modify_reg, reset_reg}; fn main() {
-/// # let gpioa = stm32ral::gpio::GPIOA::take().unwrap();
-/// // Set PA3 to Output, PA4 to Analog, and leave everything else unchanged.
-/// modify_reg!(stm32ral::gpio, gpioa, MODER, MODER3: 0b01, MODER4: 0b11);
-/// # }
+/// // Either may be given to the register macros as the instance.
+/// write_reg!(usart, usart2(), BRR, 0x1A1);
 /// ```
 ///
-/// For fields with annotated values, you can also specify a named value:
-/// ```rust,no_run
-/// # use stm32ral::{read_reg, write_reg, modify_reg, reset_reg}; fn main() {
-/// # let gpioa = stm32ral::gpio::GPIOA::take().unwrap();
-/// // As above, but with named values.
-/// modify_reg!(stm32ral::gpio, gpioa, MODER, MODER3: Output, MODER4: Analog);
-/// # }
-/// ```
+/// # Safety
+/// Requires an unsafe block. As for `peripheral`, the address must be the address of a `T`
+/// which is valid for the `'static` lifetime.
+#[macro_export]
+macro_rules! peripheral {
+    ( $ty:ty, $base:expr ) => {{
+        const BASE: usize = $base;
+        const _: () = assert!(
+            BASE & (::core::mem::align_of::<$ty>() - 1) == 0,
+            "peripheral base address is not aligned for its register block"
+        );
+        $crate::peripheral::<$ty>(BASE)
+    }};
+}
+
+/// Get a reference to a register.
 ///
-/// This macro expands to calling `(*instance).register.write(value)`.
-/// When called with a closure, `(*instance).register.read()` is called, the result
-/// passed in to the closure, and the return value of the closure is used for `value`.
-/// When called with `Field: Value` arguments, the current value is read and then masked
-/// according to the specified fields, and then ORd with the OR of each field value,
-/// each masked and shifted appropriately for the field. The named values are brought into scope
-/// by `use peripheral::register::field::*` for each field. The same constants could just be
-/// specified manually:
+/// # Examples
 /// ```rust,no_run
-/// # use stm32ral::{read_reg, write_reg, modify_reg, reset_reg}; fn main() {
+/// # use ral_registers::reg_ref; fn main() {
 /// # let gpioa = stm32ral::gpio::GPIOA::take().unwrap();
-/// // As above, but being explicit about named values.
-/// modify_reg!(stm32ral::gpio, gpioa, MODER, MODER3: stm32ral::gpio::MODER::MODER3::RW::Output,
-///                                           MODER4: stm32ral::gpio::MODER::MODER4::RW::Analog);
+/// let odr = reg_ref!(stm32ral::gpio, &gpioa, ODR);
+/// let value = odr.read();
 /// # }
 /// ```
 ///
-/// The fully expanded form is equivalent to:
-/// ```rust,no_run
-/// # use stm32ral::{read_reg, write_reg, modify_reg, reset_reg}; fn main() {
-/// # let gpioa = stm32ral::gpio::GPIOA::take().unwrap();
-/// // As above, but expanded.
-/// (*gpioa).MODER.write(
-///     (
-///         // First read the current value...
-///         (*gpioa).MODER.read()
-///         // Then AND it with an appropriate mask...
-///         &
-///         !( stm32ral::gpio::MODER::MODER3::mask | stm32ral::gpio::MODER::MODER4::mask )
-///     )
-///     // Then OR with each field value.
-///     |
-///         ((stm32ral::gpio::MODER::MODER3::RW::Output << stm32ral::gpio::MODER::MODER3::offset)
-///          & stm32ral::gpio::MODER::MODER3::mask)
-///     |
-///         ((stm32ral::gpio::MODER::MODER4::RW::Analog << stm32ral::gpio::MODER::MODER3::offset)
-///          & stm32ral::gpio::MODER::MODER3::mask)
-/// );
-/// # }
-/// ```
+/// # Usage
+/// The arguments are the path to the peripheral module, the instance, and the register,
+/// followed by its indices if it is a register array, as for `read_reg!`. Indices are mapped
+/// and bounds checked as for `read_reg!`, so an out of bounds index panics. The macro
+/// evaluates to a reference to the register, on which its methods may be called, or which may
+/// be passed to `read_reg_ref!`, `write_reg_ref!`, and `modify_reg_ref!`. The register is not
+/// accessed.
 ///
-/// # Safety
-/// This macro will require an unsafe function or block when used with an UnsafeRWRegister,
-/// but not if used with RWRegister.
+/// The reference borrows from the instance, so pass a reference to an `Instance`, rather than
+/// the `Instance` itself.
 ///
-/// When run in an unsafe context, peripheral instances are directly accessible without requiring
-/// having called `take()` beforehand:
-/// ```rust,no_run
-/// # use stm32ral::{read_reg, write_reg, modify_reg, reset_reg}; fn main() {
-/// unsafe { modify_reg!(stm32ral::gpio, GPIOA, MODER, MODER3: Output, MODER4: Analog) };
-/// # }
-/// ```
-/// This works because `GPIOA` is a `*const RegisterBlock` in the `stm32ral::gpio` module;
-/// and the macro brings such constants into scope and then dereferences the provided reference.
+/// # Safety
+/// Requires an unsafe block when the instance is a raw pointer, since the macro dereferences
+/// the pointer to find the register. Otherwise the macro is safe.
 #[macro_export]
-macro_rules! modify_reg {
-    ( $periph:path, $instance:expr, $reg:ident $([$offset:expr])*, $( $field:ident : $value:expr ),+ ) => {{
-        #[allow(unused_imports)]
-        use $periph::{*};
-        #[allow(unused_imports)]
-        (*$instance).$reg $([$offset])*.write(
-            ((*$instance).$reg $([$offset])*.read() & !( $({ use $periph::{$reg::$field::mask}; mask }) | * ))
-            | $({
-                use $periph::{$reg::$field::{W::*, RW::*}};
-                ($value << { use $periph::{$reg::$field::offset}; offset })
-                    & { use $periph::{$reg::$field::mask}; mask }
-            }) | *
-        );
-    }};
-    ( $periph:path, $instance:expr, $reg:ident $([$offset:expr])*, $fn:expr ) => {{
+macro_rules! reg_ref {
+    ( $periph:path, $instance:expr, $reg:ident $([$offset:expr])* ) => {{
         #[allow(unused_imports)]
         use $periph::{*};
-        (*$instance).$reg $([$offset])*.write($fn((*$instance).$reg $([$offset])*.read()));
+        &$crate::register!($periph, $instance, $reg $([$offset])*)
     }};
 }
 
-/// Read the value from a RORegister, RWRegister, UnsafeRORegister, or UnsafeRWRegister.
-///
-/// # Examples
-/// ```rust,no_run
-/// # use stm32ral::{read_reg, write_reg, modify_reg, reset_reg}; fn main() {
-/// // Safely acquire the peripheral instance (will panic if already acquired)
-/// let gpioa = stm32ral::gpio::GPIOA::take().unwrap();
+/// Read from a reference to a register, like `read_reg!`.
 ///
-/// // Read the whole register.
-/// let val = read_reg!(stm32ral::gpio, gpioa, IDR);
-///
-/// // Read one field from the register.
-/// let val = read_reg!(stm32ral::gpio, gpioa, IDR, IDR2);
+/// # Usage
+/// The arguments are the path to the peripheral module, the register module, and a
+/// reference to the register, such as the ones produced by `iter_array!` and `reg_ref!`. These
+/// are followed by the same field arguments as `read_reg!`:
+/// `read_reg_ref!(periph, MY_ARRAY, reg, FIELD_A == 1)`.
 ///
-/// // Read multiple fields from the register.
-/// let (val1, val2, val3) = read_reg!(stm32ral::gpio, gpioa, IDR, IDR0, IDR1, IDR2);
+/// # Safety
+/// As for `read_reg!`.
+#[macro_export]
+macro_rules! read_reg_ref {
+    ( $periph:path, $reg:ident, $register:expr $(, $($rest:tt)+)? ) => {
+        $crate::must_use($crate::read_reg!(@place $periph, $reg, (*$register) $(, $($rest)+)?))
+    };
+}
+
+/// Write to a reference to a register, like `write_reg!`.
 ///
-/// // Check if one field is equal to a specific value, with the field's named values in scope.
-/// while read_reg!(stm32ral::gpio, gpioa, IDR, IDR2 == High) {}
+/// # Usage
+/// The arguments are the path to the peripheral module, the register module, and a
+/// reference to the register, such as the ones produced by `iter_array!` and `reg_ref!`. These
+/// are followed by the same value arguments as `write_reg!`:
+/// `write_reg_ref!(periph, MY_ARRAY, reg, FIELD_A: 1)`.
 ///
-/// // Unsafe access without requiring you to first `take()` the instance
-/// let val = unsafe { read_reg!(stm32ral::gpio, GPIOA, IDR) };
-/// # }
-/// ```
+/// # Safety
+/// As for `write_reg!`.
+#[macro_export]
+macro_rules! write_reg_ref {
+    ( $periph:path, $reg:ident, $register:expr, $($rest:tt)+ ) => {
+        $crate::write_reg!(@place $periph, $reg, (*$register), $($rest)+)
+    };
+}
+
+/// Modify a reference to a register, like `modify_reg!`.
 ///
 /// # Usage
-/// Like `write_reg!`, this macro can be used multiple ways, either reading the entire register or
-/// reading a one or more fields from it and potentially performing a comparison with one field.
-///
-/// In all cases, the first arguments are:
-/// * the path to the peripheral module: `stm32ral::gpio`,
-/// * a reference to the instance of that peripheral: 'gpioa' (anything which dereferences to
-///   `RegisterBlock`, such as `Instance`, `&Instance`, `&RegisterBlock`, or
-///   `*const RegisterBlock`),
-/// * the register you wish to access: `IDR` (a field on the `RegisterBlock`).
+/// The arguments are the path to the peripheral module, the register module, and a
+/// reference to the register, such as the ones produced by `iter_array!` and `reg_ref!`. These
+/// are followed by the same arguments as `modify_reg!`:
+/// `modify_reg_ref!(periph, MY_ARRAY, reg, FIELD_A: 1)`.
 ///
-/// In the whole-register usage, the macro simply returns the register's value:
-/// ```rust,no_run
-/// # use stm32ral::{read_reg, write_reg, modify_reg, reset_reg}; fn main() {
-/// # let gpioa = stm32ral::gpio::GPIOA::take().unwrap();
-/// // Read the entire value of GPIOA.IDR into `val`.
-/// let val = read_reg!(stm32ral::gpio, gpioa, IDR);
-/// # }
-/// ```
+/// # Safety
+/// As for `modify_reg!`.
+#[macro_export]
+macro_rules! modify_reg_ref {
+    ( $periph:path, $reg:ident, $register:expr, $($rest:tt)+ ) => {
+        $crate::modify_reg!(@place $periph, $reg, (*$register), $($rest)+)
+    };
+}
+
+/// Extract the value of one or more fields from a register value.
 ///
-/// For reading individual fields, the macro masks and shifts appropriately:
+/// # Examples
 /// ```rust,no_run
-/// # use stm32ral::{read_reg, write_reg, modify_reg, reset_reg}; fn main() {
+/// # use ral_registers::extract_field; fn main() {
 /// # let gpioa = stm32ral::gpio::GPIOA::take().unwrap();
-/// // Read just the value of the field GPIOA.IDR2 into `val`.
-/// let val = read_reg!(stm32ral::gpio, gpioa, IDR, IDR2);
+/// // Read the register once...
+/// let idr = gpioa.IDR.read();
 ///
-/// // As above, but expanded for exposition:
-/// let val = ((*gpioa).IDR.read() & stm32ral::gpio::IDR::IDR2::mask)
-///           >> stm32ral::gpio::IDR::IDR2::offset;
-///
-/// // Read multiple fields
-/// let (val1, val2) = read_reg!(stm32ral::gpio, gpioa, IDR, IDR2, IDR3);
+/// // ...then decode one field from the value.
+/// let val = extract_field!(stm32ral::gpio, IDR, IDR2, idr);
 ///
-/// // As above, but expanded for exposition:
-/// let (val1, val2) = { let val = (*gpioa).IDR.read();
-///     ((val & stm32ral::gpio::IDR::IDR2::mask) >> stm32ral::gpio::IDR::IDR2::offset,
-///      (val & stm32ral::gpio::IDR::IDR3::mask) >> stm32ral::gpio::IDR::IDR3::offset,
-///     )};
+/// // ...or decode multiple fields from the value.
+/// let (val2, val3) = extract_field!(stm32ral::gpio, IDR, [IDR2, IDR3], idr);
 /// # }
 /// ```
 ///
-/// For comparing a single field, the macro masks and shifts and then performs the comparison:
+/// # Usage
+/// The arguments are:
+/// * the path to the peripheral module: `stm32ral::gpio`,
+/// * the register module: `IDR` (without any array index),
+/// * either a single field, `IDR2`, or a list of fields in brackets, `[IDR2, IDR3]`,
+/// * the register value to decode.
+///
+/// This macro masks and shifts the value for each field exactly as `read_reg!` does, but does
+/// not access any register. With a list of fields, it evaluates to a tuple of field values.
+/// Like `build_value!`, the expansion is a constant expression when the value is, so the macro
+/// can be used in `const` initializers.
+#[macro_export]
+macro_rules! extract_field {
+    ( $periph:path, $reg:ident, [ $( $field:ident ),+ ], $value:expr ) => {{
+        let val = $value;
+        ( $({
+            $crate::check_field!($periph, $reg, $field);
+            (val & $crate::field_mask!($periph, $reg, $field))
+                >> $crate::field_offset!($periph, $reg, $field)
+        }) , *)
+    }};
+    ( $periph:path, $reg:ident, $field:ident, $value:expr ) => {{
+        $crate::check_field!($periph, $reg, $field);
+        ($value & $crate::field_mask!($periph, $reg, $field))
+            >> $crate::field_offset!($periph, $reg, $field)
+    }};
+}
+
+/// Build a register value from one or more field values.
+///
+/// # Examples
 /// ```rust,no_run
-/// # use stm32ral::{read_reg, write_reg, modify_reg, reset_reg}; fn main() {
+/// # use ral_registers::build_value; fn main() {
 /// # let gpioa = stm32ral::gpio::GPIOA::take().unwrap();
-/// # let rcc = stm32ral::rcc::RCC::take().unwrap();
-/// // Loop while PA2 is High.
-/// while read_reg!(stm32ral::gpio, gpioa, IDR, IDR2 == High) {}
-///
-/// // Only proceed if the clock is not the HSI.
-/// if read_reg!(stm32ral::rcc, rcc, CFGR, SWS != HSI) { }
+/// // Prepare a value for GPIOA.MODER without touching the register...
+/// let moder = build_value!(stm32ral::gpio, MODER, MODER3: Output, MODER4: Analog);
 ///
-/// // Equivalent expansion:
-/// if (((*rcc).CFGR.read() & stm32ral::rcc::CFGR::SWS::mask)
-///     >> stm32ral::rcc::CFGR::SWS::offset) != stm32ral::rcc::CFGR::SWS::R::HSI { }
+/// // ...and write it later.
+/// gpioa.MODER.write(moder);
 /// # }
 /// ```
 ///
-/// # Safety
-/// This macro will require an unsafe function or block when used with an UnsafeRWRegister or
-/// UnsafeRORegister, but not if used with RWRegister, or RORegister.
+/// # Usage
+/// The arguments are:
+/// * the path to the peripheral module: `stm32ral::gpio`,
+/// * the register module: `MODER` (without any array index),
+/// * one or more `Field: Value` pairs, as for `write_reg!`.
 ///
-/// When run in an unsafe context, peripheral instances are directly accessible without requiring
-/// having called `take()` beforehand:
+/// This macro evaluates to the bitwise OR of each field value, masked and shifted appropriately
+/// for the given field, which is exactly the value `write_reg!` would write for the same fields.
+/// Named values are brought into scope from each field's `W` and `RW` modules. No register is
+/// accessed. Unlike `write_reg!`, values which do not fit in their field are masked off without
+/// a debug check.
+///
+/// The expansion is a constant expression when the field values are, so the macro can be used
+/// in `const` and `static` initializers:
 /// ```rust,no_run
-/// # use stm32ral::{read_reg, write_reg, modify_reg, reset_reg}; fn main() {
-/// let val = unsafe { read_reg!(stm32ral::gpio, GPIOA, MODER) };
-/// # }
+/// # use ral_registers::build_value;
+/// // A table of MODER values, computed at compile time.
+/// const MODES: [u32; 2] = [
+///     build_value!(stm32ral::gpio, MODER, MODER3: Output),
+///     build_value!(stm32ral::gpio, MODER, MODER3: Output, MODER4: Analog),
+/// ];
 /// ```
-/// This works because `GPIOA` is a `*const RegisterBlock` in the `stm32ral::gpio` module;
-/// and the macro brings such constants into scope and then dereferences the provided reference.
 #[macro_export]
-macro_rules! read_reg {
-    ( $periph:path, $instance:expr, $reg:ident $([$offset:expr])*, $( $field:ident ),+ ) => {{
-        #[allow(unused_imports)]
-        use $periph::{*};
-        let val = ((*$instance).$reg $([$offset])*.read());
+macro_rules! build_value {
+    ( @checked $periph:path, $reg:ident, $( $field:ident : $value:expr ),+ ) => {
         ( $({
             #[allow(unused_imports)]
-            use $periph::{$reg::$field::{mask, offset, R::*, RW::*}};
-            (val & mask) >> offset
-        }) , *)
+            use $periph::{$reg::$field::{W::*, RW::*}};
+            $crate::check_field!($periph, $reg, $field);
+            let offset = $crate::field_offset!($periph, $reg, $field);
+            let mask = $crate::field_mask!($periph, $reg, $field);
+            let value = $crate::same_type(mask, $value);
+            debug_assert!(
+                value & !(mask >> offset) == 0,
+                "value {:#x} does not fit field {}::{} ({} bits)",
+                value,
+                stringify!($reg),
+                stringify!($field),
+                (mask >> offset).count_ones(),
+            );
+            (value << offset) & mask
+        }) | * )
+    };
+    ( @saturating $periph:path, $reg:ident, $( $field:ident : $value:expr ),+ ) => {
+        ( $({
+            #[allow(unused_imports)]
+            use $periph::{$reg::$field::{W::*, RW::*}};
+            $crate::check_field!($periph, $reg, $field);
+            let offset = $crate::field_offset!($periph, $reg, $field);
+            let mask = $crate::field_mask!($periph, $reg, $field);
+            let value = $crate::same_type(mask, $value);
+            let max = mask >> offset;
+            ((if value > max { max } else { value }) << offset) & mask
+        }) | * )
+    };
+    ( $periph:path, $reg:ident, $( $field:ident : $value:expr ),+ ) => {
+        ( $({
+            #[allow(unused_imports)]
+            use $periph::{$reg::$field::{W::*, RW::*}};
+            $crate::check_field!($periph, $reg, $field);
+            ($value << $crate::field_offset!($periph, $reg, $field))
+                & $crate::field_mask!($periph, $reg, $field)
+        }) | * )
+    };
+}
+
+/// Expands to the place expression for a register, such as `(*instance).REG[i][j]`.
+///
+/// Array indices are mapped with `register_index!`, and each array is indexed with
+/// `index_register`, so an out of bounds index panics with the name of the array.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! register {
+    ( @index $periph:path, $reg:ident, ($($place:tt)*) [$offset:expr] $([$rest:expr])* ) => {
+        $crate::register!(
+            @index $periph, $reg,
+            (*$crate::index_register(
+                &$($place)*,
+                $crate::register_index!($periph, $reg, $offset),
+                stringify!($reg),
+            ))
+            $([$rest])*
+        )
+    };
+    ( @index $periph:path, $reg:ident, ($($place:tt)*) ) => {
+        $($place)*
+    };
+    ( $periph:path, $instance:expr, $reg:ident $([$offset:expr])* ) => {
+        $crate::register!(@index $periph, $reg, ((*$instance).$reg) $([$offset])*)
+    };
+}
+
+/// Expands to the `ResetValue` of a register, indexed like the register.
+///
+/// Each index selects an element of the reset value if it is an array, as for `register!`,
+/// and otherwise leaves the reset value unchanged.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! reset_value {
+    ( @index $periph:path, $reg:ident, ($($value:tt)*) [$offset:expr] $([$rest:expr])* ) => {
+        $crate::reset_value!(
+            @index $periph, $reg,
+            ((&&$($value)*).at($crate::register_index!($periph, $reg, $offset)))
+            $([$rest])*
+        )
+    };
+    ( @index $periph:path, $reg:ident, ($($value:tt)*) ) => {
+        $($value)*
+    };
+    ( $periph:path, $reset:expr, $reg:ident $([$offset:expr])* ) => {{
+        #[allow(unused_imports)]
+        use $crate::{ResetElement as _, ResetScalar as _};
+        $crate::reset_value!(@index $periph, $reg, ($crate::ResetValue(&$reset.$reg)) $([$offset])*)
+    }};
+}
+
+/// Checks, at compile time, that the literal index `$index` is in bounds of the register array
+/// `$reg`.
+///
+/// The length of the array is read from the type of the `RegisterBlock` field. The top-level
+/// macros match a literal first index before any other form, and expand to this check followed
+/// by themselves with the index wrapped in parentheses, which is no longer a literal.
+#[doc(hidden)]
+#[macro_export]
+#[rustfmt::skip]
+macro_rules! check_literal_index {
+    ( $periph:path, $reg:ident, $index:literal ) => {
+        const _: () = {
+            use $periph::{RegisterBlock};
+            const fn len<T, const LEN: usize>(_: fn(&RegisterBlock) -> &[T; LEN]) -> usize {
+                LEN
+            }
+            let index: usize = $crate::register_index!($periph, $reg, $index);
+            assert!(index < len(|block| &block.$reg), "register array index out of bounds");
+        };
+    };
+}
+
+/// Evaluates each index expression once, in order, into a local, then expands to
+/// `$crate::$mac!($($args)* [index]...)` with the locals as the indices.
+///
+/// Macros which index both a register and its reset value use this, so that an index with
+/// side effects runs once, and both are indexed with the same value.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! with_indices {
+    ( [$($bound:ident)*] [$offset:expr] $([$rest:expr])* => $mac:ident!($($args:tt)*) ) => {{
+        let index: usize = $offset;
+        $crate::with_indices!([$($bound)* index] $([$rest])* => $mac!($($args)*))
+    }};
+    ( [$($bound:ident)*] => $mac:ident!($($args:tt)*) ) => {
+        $crate::$mac!($($args)* $([$bound])*)
+    };
+}
+
+/// Maps an index into a register array to an index into its storage.
+///
+/// If the register module defines `index`, it is used to map the index. Otherwise, the
+/// default `identity_index` is used. This works because the glob import of the register
+/// module shadows the `index` imported in the enclosing block.
+#[doc(hidden)]
+#[macro_export]
+#[rustfmt::skip] // rustfmt would remove the braces which are required after `$periph`.
+macro_rules! register_index {
+    ( $periph:path, $reg:ident, $offset:expr ) => {{
+        let idx: usize = $offset;
+        {
+            #[allow(unused_imports)]
+            use $crate::identity_index as index;
+            {
+                #[allow(unused_imports)]
+                use $periph::{$reg::*};
+                index(idx)
+            }
+        }
+    }};
+}
+
+/// Evaluates to `Ok` reference to a register, or the `IndexError` for the first index into a
+/// register array which is out of bounds.
+///
+/// Like `get_register!`, each index is evaluated exactly once, so the reference can be passed
+/// to the `@place` arms of the access macros without evaluating the indices again.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! checked_register {
+    ( $periph:path, $instance:expr, $reg:ident $([$offset:expr])* ) => {{
+        let register = &(*$instance).$reg;
+        $crate::get_register!(@bind check, $periph, $reg, register, [] $([$offset])*)
+    }};
+}
+
+/// Evaluates to `Some` reference to a register, or `None` if an index into a register array is
+/// out of bounds, without a panic path.
+///
+/// Every index is evaluated once, in order, into a local before any is looked up, and the
+/// lookups use `slice::get`. The expansion is a plain block, so a `?` or `return` in an index
+/// expression applies to the caller.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! get_register {
+    ( @bind $mode:ident, $periph:path, $reg:ident, $register:ident, [$($index:ident)*] [$offset:expr] $([$rest:expr])* ) => {{
+        let index: usize = $crate::register_index!($periph, $reg, $offset);
+        $crate::get_register!(@bind $mode, $periph, $reg, $register, [$($index)* index] $([$rest])*)
+    }};
+    ( @bind $mode:ident, $periph:path, $reg:ident, $register:ident, [$($index:ident)*] ) => {
+        $crate::get_register!(@$mode $register $($index)*)
+    };
+    ( @get $register:ident $index:ident $($rest:ident)* ) => {
+        match $register.get($index) {
+            Some(register) => $crate::get_register!(@get register $($rest)*),
+            None => None,
+        }
+    };
+    ( @get $register:ident ) => {
+        Some($register)
+    };
+    ( @check $register:ident $index:ident $($rest:ident)* ) => {
+        match $crate::IndexError::check($register, $index) {
+            Ok(register) => $crate::get_register!(@check register $($rest)*),
+            Err(err) => Err(err),
+        }
+    };
+    ( @check $register:ident ) => {
+        Ok::<_, $crate::IndexError>($register)
+    };
+    ( $periph:path, $instance:expr, $reg:ident $([$offset:expr])* ) => {{
+        let register = &(*$instance).$reg;
+        $crate::get_register!(@bind get, $periph, $reg, register, [] $([$offset])*)
     }};
-    ( $periph:path, $instance:expr, $reg:ident $([$offset:expr])*, $field:ident $($cmp:tt)* ) => {{
+}
+
+/// Expands to the `mask` of a field, which may be a module or a `FieldMarker` type.
+///
+/// The field is imported into its own block, so the mask does not shadow locals named `mask`.
+#[doc(hidden)]
+#[macro_export]
+#[rustfmt::skip]
+macro_rules! field_mask {
+    ( $periph:path, $reg:ident, $field:ident ) => {{
         #[allow(unused_imports)]
-        use $periph::{*};
+        use $crate::FieldConsts as _;
         #[allow(unused_imports)]
-        use $periph::{$reg::$field::{mask, offset, R::*, RW::*}};
-        (((*$instance).$reg $([$offset])*.read() & mask) >> offset) $($cmp)*
+        use $crate::BIT_NUMBERING;
+        {
+            #[allow(unused_imports)]
+            use $periph::{$reg::*};
+            use $periph::{$reg::{$field}};
+            $crate::field_bits!(mask, $field, BIT_NUMBERING)
+        }
     }};
-    ( $periph:path, $instance:expr, $reg:ident $([$offset:expr])* ) => {{
+}
+
+/// Expands to the `offset` of a field, which may be a module or a `FieldMarker` type, as a
+/// `u32`.
+#[doc(hidden)]
+#[macro_export]
+#[rustfmt::skip]
+macro_rules! field_offset {
+    ( $periph:path, $reg:ident, $field:ident ) => {{
         #[allow(unused_imports)]
-        use $periph::{*};
-        ((*$instance).$reg $([$offset])*.read())
+        use $crate::FieldConsts as _;
+        #[allow(unused_imports)]
+        use $crate::BIT_NUMBERING;
+        {
+            #[allow(unused_imports)]
+            use $periph::{$reg::*};
+            use $periph::{$reg::{$field}};
+            $crate::field_bits!(offset, $field, BIT_NUMBERING)
+        }
     }};
 }
 
-/// Reset a RWRegister, UnsafeRWRegister, WORegister, or UnsafeWORegister to its reset value.
-///
-/// # Examples
-/// ```rust,no_run
-/// # use stm32ral::{read_reg, write_reg, modify_reg, reset_reg}; fn main() {
-/// // Safely acquire the peripheral instance (will panic if already acquired)
-/// let gpioa = stm32ral::gpio::GPIOA::take().unwrap();
+/// Expands to the `mask` or `offset` of the field `$field`, which is in scope, translated from
+/// the `BitOrder` `$order` of its register module to `BitOrder::LsbZero`.
 ///
-/// // Reset PA14 and PA15 to their reset state
-/// reset_reg!(stm32ral::gpio, gpioa, GPIOA, MODER, MODER14, MODER15);
+/// `field_mask!` and `field_offset!` find the register module's `BIT_NUMBERING`, or the crate's
+/// default, in the same way that `register_index!` finds `index`.
+#[doc(hidden)]
+#[macro_export]
+#[rustfmt::skip]
+macro_rules! field_bits {
+    ( mask, $field:ident, $order:expr ) => {
+        match $order {
+            $crate::BitOrder::LsbZero => $field::mask,
+            $crate::BitOrder::MsbZero => ($field::mask >> $field::offset) << $crate::msb_zero_offset(
+                $crate::bit_width(&$field::mask),
+                $field::offset as u32,
+                ($field::mask >> $field::offset).count_ones(),
+            ),
+        }
+    };
+    ( offset, $field:ident, $order:expr ) => {
+        match $order {
+            $crate::BitOrder::LsbZero => $field::offset as u32,
+            $crate::BitOrder::MsbZero => $crate::msb_zero_offset(
+                $crate::bit_width(&$field::mask),
+                $field::offset as u32,
+                ($field::mask >> $field::offset).count_ones(),
+            ),
+        }
+    };
+}
+
+/// Checks, at compile time, that a field's `mask` and `offset` describe a field of the register.
 ///
-/// // Reset the entire GPIOA.MODER to its reset state
-/// reset_reg!(stm32ral::gpio, gpioa, GPIOA, MODER);
-/// # }
+/// Expands to a `const` item, so an inconsistent field module is a compile error wherever the
+/// field is used:
+/// ```compile_fail
+/// # #![allow(non_upper_case_globals, non_snake_case)]
+/// mod periph {
+///     pub mod MY_REG {
+///         pub mod FIELD {
+///             // A generator bug: the offset is past the end of a 32-bit register.
+///             pub const offset: u32 = 40;
+///             pub const mask: u32 = 0x7F;
+///         }
+///     }
+/// }
+/// ral_registers::check_field!(periph, MY_REG, FIELD);
+/// # fn main() {}
 /// ```
+#[doc(hidden)]
+#[macro_export]
+#[rustfmt::skip]
+macro_rules! check_field {
+    ( $periph:path, $reg:ident, $field:ident ) => {
+        #[allow(clippy::unnecessary_cast)]
+        const _: () = {
+            let mask = $crate::field_mask!($periph, $reg, $field);
+            let offset = $crate::field_offset!($periph, $reg, $field);
+            $crate::check_field(mask as u64, offset as u32, $crate::bit_width(&mask))
+        };
+    };
+}
+
+/// Read from a register like `read_reg!`, returning an error if an array index is out of bounds.
 ///
 /// # Usage
-/// Like `write_reg!`, this macro can be used in two ways, either resetting the entire register
-/// or just resetting specific fields within in. The register or fields are written with their
-/// reset values.
+/// This macro accepts every form of `read_reg!`. For register arrays, each index is checked
+/// before the register is read, and if any is out of bounds the macro evaluates to
+/// `Err(IndexError)` without accessing the register. Otherwise, the macro evaluates to
+/// `Ok(value)`, where `value` is what `read_reg!` would have returned. For scalar registers
+/// the macro always evaluates to `Ok`.
 ///
-/// In both cases, the first arguments are:
-/// * the path to the peripheral module: `stm32ral::gpio`,
-/// * a reference to the instance of that peripheral: 'gpioa' (anything which dereferences to
-///   `RegisterBlock`, such as `Instance`, `&Instance`, `&RegisterBlock`, or
-///   `*const RegisterBlock`),
-/// * the module for the instance of that peripheral: `GPIOA`,
-/// * the register you wish to access: `MODER` (a field on the `RegisterBlock`).
+/// Each index expression is evaluated once, and the register which was checked is the one
+/// accessed.
 ///
-/// In the whole-register usage, that's it:
-/// ```rust,no_run
-/// # use stm32ral::{read_reg, write_reg, modify_reg, reset_reg}; fn main() {
-/// # let gpioa = stm32ral::gpio::GPIOA::take().unwrap();
-/// // Reset the entire GPIOA.MODER
-/// reset_reg!(stm32ral::gpio, gpioa, GPIOA, MODER);
-/// # }
-/// ```
+/// # Safety
+/// As for `read_reg!`.
+#[macro_export]
+macro_rules! try_read_reg {
+    ( $periph:path, $instance:expr, $reg:ident $([$offset:expr])*, ordering = $ordering:expr $(, $($rest:tt)+)? ) => {
+        match $crate::checked_register!($periph, $instance, $reg $([$offset])*) {
+            Ok(register) => Ok($crate::read_reg!(@place $periph, $reg, ((*register).ordered($ordering)) $(, $($rest)+)?)),
+            Err(err) => Err(err),
+        }
+    };
+    ( $periph:path, $instance:expr, $reg:ident $([$offset:expr])* $(, $($rest:tt)+)? ) => {
+        match $crate::checked_register!($periph, $instance, $reg $([$offset])*) {
+            Ok(register) => Ok($crate::read_reg!(@place $periph, $reg, (*register) $(, $($rest)+)?)),
+            Err(err) => Err(err),
+        }
+    };
+}
+
+/// Read from a register like `read_reg!`, evaluating to `None` if an array index is out of
+/// bounds.
 ///
-/// Otherwise, the remaining arguments are each field names:
-/// ```rust,no_run
-/// # use stm32ral::{read_reg, write_reg, modify_reg, reset_reg}; fn main() {
-/// # let gpioa = stm32ral::gpio::GPIOA::take().unwrap();
-/// // Reset the JTAG pins
-/// reset_reg!(stm32ral::gpio, gpioa, GPIOA, MODER, MODER13, MODER14, MODER15);
-/// reset_reg!(stm32ral::gpio, gpioa, GPIOB, MODER, MODER3, MODER4);
-/// # }
+/// # Examples
+/// ```rust,ignore
+/// match get_reg!(periph, dma, CHANNEL_CR[channel]) {
+///     Some(cr) => { /* ... */ }
+///     None => { /* no such channel */ }
+/// }
 /// ```
 ///
-/// The second form is only available to RWRegister and UnsafeRWRegister, since `.read()` is
-/// not available for WORegister and UnsafeWORegister.
+/// # Usage
+/// This macro accepts every form of `read_reg!`, and evaluates to `Some(value)`, where `value`
+/// is what `read_reg!` would have returned, or to `None` without accessing the register if
+/// any array index is out of bounds. For scalar registers the macro always evaluates to
+/// `Some`. Unlike `try_read_reg!`, there is no error describing the index.
 ///
-/// This macro expands to calling `(*$instance).$register.write(value)`, where
-/// `value` is either the register's reset value, or the current read value of the register
-/// masked appropriately and combined with the reset value for each field.
+/// The register is found with `slice::get` rather than by indexing, so unlike `read_reg!` the
+/// expansion has no bounds check which panics. Each index expression is evaluated once, in the
+/// caller's function, so a `?` in an index returns from the caller.
 ///
 /// # Safety
-/// This macro will require an unsafe function or block when used with an UnsafeRWRegister or
-/// UnsafeRORegister, but not if used with RWRegister or RORegister.
+/// As for `read_reg!`.
+#[macro_export]
+macro_rules! get_reg {
+    ( $periph:path, $instance:expr, $reg:ident $([$offset:expr])* $(, $($rest:tt)+)? ) => {
+        match $crate::get_register!($periph, $instance, $reg $([$offset])*) {
+            Some(register) => Some($crate::read_reg!(@place $periph, $reg, (*register) $(, $($rest)+)?)),
+            None => None,
+        }
+    };
+}
+
+/// Read fields from a register like `read_reg!`, evaluating to `None` if an array index is out
+/// of bounds.
 ///
-/// When run in an unsafe context, peripheral instances are directly accessible without requiring
-/// having called `take()` beforehand:
-/// ```rust,no_run
-/// # use stm32ral::{read_reg, write_reg, modify_reg, reset_reg}; fn main() {
-/// unsafe { reset_reg!(stm32ral::gpio, GPIOA, GPIOA, MODER) };
-/// # }
-/// ```
-/// This works because `GPIOA` is a `*const RegisterBlock` in the `stm32ral::gpio` module;
-/// and the macro brings such constants into scope and then dereferences the provided reference.
+/// # Usage
+/// The arguments are the same as the field forms of `read_reg!`: one field, several fields,
+/// or one field compared with a value. The macro is `get_reg!` with those fields, and
+/// evaluates to `Some` of the field values, or `None` if any array index is out of bounds.
 ///
-/// Note that the second argument is a `*const` and the third is a path; despite both being written
-/// `GPIOA` they are not the same thing.
+/// # Safety
+/// As for `read_reg!`.
 #[macro_export]
-macro_rules! reset_reg {
-    ( $periph:path, $instance:expr, $instancemod:path, $reg:ident $([$offset:expr])*, $( $field:ident ),+ ) => {{
-        #[allow(unused_imports)]
-        use $periph::{*};
-        use $periph::{$instancemod::{reset}};
-        #[allow(unused_imports)]
-        (*$instance).$reg $([$offset])*.write({
-            let resetmask: u32 = $({ use $periph::{$reg::$field::mask}; mask }) | *;
-            ((*$instance).$reg $([$offset])*.read() & !resetmask) | (reset.$reg & resetmask)
-        });
-    }};
-    ( $periph:path, $instance:expr, $instancemod:path, $reg:ident $([$offset:expr])*) => {{
-        #[allow(unused_imports)]
-        use $periph::{*};
-        use $periph::{$instancemod::{reset}};
-        (*$instance).$reg $([$offset])*.write(reset.$reg);
-    }};
+macro_rules! get_field {
+    ( $periph:path, $instance:expr, $reg:ident $([$offset:expr])*, $($fields:tt)+ ) => {
+        $crate::get_reg!($periph, $instance, $reg $([$offset])*, $($fields)+)
+    };
+}
+
+/// Write to a register like `write_reg!`, returning an error if an array index is out of bounds.
+///
+/// # Usage
+/// This macro accepts every form of `write_reg!`, and evaluates to `Ok(())` if the register
+/// was written, or `Err(IndexError)` without writing the register if any array index is out of
+/// bounds. See `try_read_reg!` for more details.
+///
+/// # Safety
+/// As for `write_reg!`.
+#[macro_export]
+macro_rules! try_write_reg {
+    ( $periph:path, $instance:expr, $reg:ident $([$offset:expr])*, ordering = $ordering:expr, $($rest:tt)+ ) => {
+        match $crate::checked_register!($periph, $instance, $reg $([$offset])*) {
+            Ok(register) => Ok($crate::write_reg!(@place $periph, $reg, ((*register).ordered($ordering)), $($rest)+)),
+            Err(err) => Err(err),
+        }
+    };
+    ( $periph:path, $instance:expr, $reg:ident $([$offset:expr])*, $($rest:tt)+ ) => {
+        match $crate::checked_register!($periph, $instance, $reg $([$offset])*) {
+            Ok(register) => Ok($crate::write_reg!(@place $periph, $reg, (*register), $($rest)+)),
+            Err(err) => Err(err),
+        }
+    };
+}
+
+/// Modify a register like `modify_reg!`, returning an error if an array index is out of bounds.
+///
+/// # Usage
+/// This macro accepts every form of `modify_reg!`, and evaluates to `Ok(value)` with the value
+/// written to the register, or `Err(IndexError)` without accessing the register if any array
+/// index is out of bounds. See `try_read_reg!` for more details.
+///
+/// # Safety
+/// As for `modify_reg!`.
+#[macro_export]
+macro_rules! try_modify_reg {
+    ( $periph:path, $instance:expr, $reg:ident $([$offset:expr])*, ordering = $ordering:expr, $($rest:tt)+ ) => {
+        match $crate::checked_register!($periph, $instance, $reg $([$offset])*) {
+            Ok(register) => Ok($crate::modify_reg!(@ordered $periph, $reg, ((*register).ordered($ordering)), $($rest)+)),
+            Err(err) => Err(err),
+        }
+    };
+    ( $periph:path, $instance:expr, $reg:ident $([$offset:expr])*, $($rest:tt)+ ) => {
+        match $crate::checked_register!($periph, $instance, $reg $([$offset])*) {
+            Ok(register) => Ok($crate::modify_reg!(@place $periph, $reg, (*register), $($rest)+)),
+            Err(err) => Err(err),
+        }
+    };
 }
@@ -0,0 +1,144 @@
+//! Host-side mock registers for testing drivers off-target.
+//!
+//! A [`MockRegister`] has the same `read` and `write` methods as the register types, so it can
+//! be used in a test `RegisterBlock` with the field macros, and as a [`ReadRegister`] or
+//! [`WriteRegister`] in generic driver code. Unlike a real register, it records every access in
+//! a log, and can compute the values it returns from reads with a callback.
+//!
+//! ```
+//! use ral_registers::mock::{Access, MockRegister};
+//!
+//! let sr = MockRegister::new(0u32);
+//! let mut polls = 0;
+//! // Report "ready" on the third read.
+//! sr.on_read(move |value| {
+//!     polls += 1;
+//!     if polls == 3 { value | 1 } else { value }
+//! });
+//!
+//! while sr.read() & 1 == 0 {}
+//! assert_eq!(sr.take_log(), [Access::Read(0), Access::Read(0), Access::Read(1)]);
+//! ```
+//!
+//! This module requires the `mock` feature, which depends on `std`.
+
+extern crate std;
+
+use crate::{ReadRegister, WriteRegister};
+use core::cell::{Cell, RefCell};
+use core::fmt;
+use std::boxed::Box;
+use std::vec::Vec;
+
+/// A single access to a [`MockRegister`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Access<T> {
+    /// The register was read, returning this value.
+    Read(T),
+    /// The register was written with this value.
+    Write(T),
+}
+
+/// A callback installed with `MockRegister::on_read`.
+type OnRead<T> = Box<dyn FnMut(T) -> T>;
+
+/// A register which records every access, for host-side tests.
+///
+/// Reads return the last value written, unless a callback is installed with `on_read`.
+pub struct MockRegister<T> {
+    value: Cell<T>,
+    log: RefCell<Vec<Access<T>>>,
+    on_read: RefCell<Option<OnRead<T>>>,
+}
+
+impl<T: Copy> MockRegister<T> {
+    /// Creates a new mock register containing `value`, with an empty log.
+    pub fn new(value: T) -> Self {
+        Self {
+            value: Cell::new(value),
+            log: RefCell::new(Vec::new()),
+            on_read: RefCell::new(None),
+        }
+    }
+
+    /// Reads the value of the register, and records the access.
+    ///
+    /// If a callback is installed, it is called with the stored value, and its result is
+    /// returned instead. The stored value is not changed.
+    pub fn read(&self) -> T {
+        let value = match self.on_read.borrow_mut().as_mut() {
+            Some(on_read) => on_read(self.value.get()),
+            None => self.value.get(),
+        };
+        self.log.borrow_mut().push(Access::Read(value));
+        value
+    }
+
+    /// Writes a new value to the register, and records the access.
+    pub fn write(&self, val: T) {
+        self.value.set(val);
+        self.log.borrow_mut().push(Access::Write(val));
+    }
+
+    /// Returns the stored value, without recording an access or calling the callback.
+    pub fn get(&self) -> T {
+        self.value.get()
+    }
+
+    /// Sets the stored value, without recording an access.
+    pub fn set(&self, value: T) {
+        self.value.set(value);
+    }
+
+    /// Installs a callback which computes the result of each read from the stored value.
+    ///
+    /// This replaces any previous callback.
+    pub fn on_read(&self, on_read: impl FnMut(T) -> T + 'static) {
+        *self.on_read.borrow_mut() = Some(Box::new(on_read));
+    }
+
+    /// Returns a copy of the accesses recorded so far, oldest first.
+    pub fn log(&self) -> Vec<Access<T>> {
+        self.log.borrow().clone()
+    }
+
+    /// Returns the accesses recorded so far, oldest first, and clears the log.
+    pub fn take_log(&self) -> Vec<Access<T>> {
+        self.log.take()
+    }
+
+    /// Consumes the register, returning the stored value.
+    ///
+    /// Like `get`, this does not record an access or call the callback.
+    pub fn into_inner(self) -> T {
+        self.value.into_inner()
+    }
+}
+
+impl<T: Copy + Default> Default for MockRegister<T> {
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+impl<T: Copy> ReadRegister<T> for MockRegister<T> {
+    fn read(&self) -> T {
+        MockRegister::read(self)
+    }
+}
+
+impl<T: Copy> WriteRegister<T> for MockRegister<T> {
+    fn write(&self, val: T) {
+        MockRegister::write(self, val)
+    }
+}
+
+/// Formats the stored value and the number of recorded accesses, without reading the register.
+impl<T: Copy + fmt::Debug> fmt::Debug for MockRegister<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("MockRegister")
+            .field("value", &self.value.get())
+            .field("accesses", &self.log.borrow().len())
+            .finish()
+    }
+}
@@ -0,0 +1,248 @@
+//! Serializable register snapshots.
+//!
+//! `snapshot_reg!` reads a register once and captures its raw value and named fields in a
+//! [`Snapshot`], which implements `serde::Serialize`. Serialized as JSON, a snapshot of
+//! `MY_ARRAY[1]` with two fields looks like:
+//!
+//! ```text
+//! {"value":402653311,"fields":{"FIELD_A":127,"FIELD_B":3}}
+//! ```
+//!
+//! Field names are `&'static str`s taken from the field modules, so a `Snapshot` cannot be
+//! deserialized. Deserialize a [`RawSnapshot`] instead, which keeps the raw value so that it
+//! can be written back to the register, and ignores the fields.
+//!
+//! `snapshot_all!` reads several registers of a block into a [`BlockSnapshot`]. Comparing two
+//! block snapshots with [`BlockSnapshot::diff`] lists the registers whose values changed
+//! between them, and `diff_snapshot!` takes the snapshots either side of a block of code:
+//!
+//! ```rust,ignore
+//! let diff = diff_snapshot!(periph, &rb, [CR, SR, DR[0], DR[1]], {
+//!     start_conversion(&rb);
+//! });
+//! for (name, old, new) in &diff {
+//!     println!("{}: {:#x} -> {:#x}", name, old, new);
+//! }
+//! ```
+//!
+//! This module requires the `serde` feature.
+
+use core::slice;
+
+use serde::ser::{Serialize, SerializeMap, SerializeStruct, Serializer};
+use serde::Deserialize;
+
+/// The raw value and decoded fields of a register, returned by `snapshot_reg!`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Snapshot<T, const F: usize> {
+    value: T,
+    fields: [(&'static str, T); F],
+}
+
+impl<T, const F: usize> Snapshot<T, F> {
+    #[doc(hidden)]
+    pub const fn new(value: T, fields: [(&'static str, T); F]) -> Self {
+        Snapshot { value, fields }
+    }
+
+    /// Returns the raw value of the register.
+    pub fn value(&self) -> &T {
+        &self.value
+    }
+
+    /// Returns the name and decoded value of each field.
+    pub fn fields(&self) -> &[(&'static str, T)] {
+        &self.fields
+    }
+}
+
+/// Serializes as a struct with the raw `value`, and a map of `fields` from name to value.
+impl<T: Serialize, const F: usize> Serialize for Snapshot<T, F> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        struct Fields<'a, T>(&'a [(&'static str, T)]);
+
+        impl<T: Serialize> Serialize for Fields<'_, T> {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                let mut map = serializer.serialize_map(Some(self.0.len()))?;
+                for (name, value) in self.0 {
+                    map.serialize_entry(name, value)?;
+                }
+                map.end()
+            }
+        }
+
+        let mut snapshot = serializer.serialize_struct("Snapshot", 2)?;
+        snapshot.serialize_field("value", &self.value)?;
+        snapshot.serialize_field("fields", &Fields(&self.fields))?;
+        snapshot.end()
+    }
+}
+
+/// The raw value of a deserialized `Snapshot`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+pub struct RawSnapshot<T> {
+    /// The raw value of the register.
+    pub value: T,
+}
+
+/// The raw values of several registers, returned by `snapshot_all!`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BlockSnapshot<T, const N: usize> {
+    registers: [(&'static str, T); N],
+}
+
+impl<T, const N: usize> BlockSnapshot<T, N> {
+    #[doc(hidden)]
+    pub const fn new(registers: [(&'static str, T); N]) -> Self {
+        BlockSnapshot { registers }
+    }
+
+    /// Returns the name and raw value of each register, in the order they were read.
+    pub fn registers(&self) -> &[(&'static str, T)] {
+        &self.registers
+    }
+}
+
+impl<T: Copy + Default + PartialEq, const N: usize> BlockSnapshot<T, N> {
+    /// Compares this snapshot with a later snapshot of the same registers, and returns the
+    /// name, old value, and new value of each register that changed.
+    ///
+    /// Registers are compared by position, so `after` should be taken by the same
+    /// `snapshot_all!` register list as `self`.
+    pub fn diff(&self, after: &Self) -> Diff<T, N> {
+        let mut diff = Diff {
+            changes: [("", T::default(), T::default()); N],
+            len: 0,
+        };
+        for (&(name, old), &(_, new)) in self.registers.iter().zip(after.registers.iter()) {
+            if old != new {
+                diff.changes[diff.len] = (name, old, new);
+                diff.len += 1;
+            }
+        }
+        diff
+    }
+}
+
+/// Serializes as a map from register name to raw value.
+impl<T: Serialize, const N: usize> Serialize for BlockSnapshot<T, N> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(N))?;
+        for (name, value) in self.registers.iter() {
+            map.serialize_entry(name, value)?;
+        }
+        map.end()
+    }
+}
+
+/// The registers that changed between two `BlockSnapshot`s, returned by
+/// `BlockSnapshot::diff` and `diff_snapshot!`.
+///
+/// Each change is the name, old value, and new value of a register, in the order the
+/// registers were read. A `Diff` holds at most one change for each register of the snapshot,
+/// so it needs no allocation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Diff<T, const N: usize> {
+    changes: [(&'static str, T, T); N],
+    len: usize,
+}
+
+impl<T, const N: usize> Diff<T, N> {
+    /// Returns each change as the name, old value, and new value of a register.
+    pub fn as_slice(&self) -> &[(&'static str, T, T)] {
+        &self.changes[..self.len]
+    }
+
+    /// Returns an iterator over the changes.
+    pub fn iter(&self) -> slice::Iter<'_, (&'static str, T, T)> {
+        self.as_slice().iter()
+    }
+
+    /// Returns the number of registers that changed.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if no register changed.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+impl<'a, T, const N: usize> IntoIterator for &'a Diff<T, N> {
+    type Item = &'a (&'static str, T, T);
+    type IntoIter = slice::Iter<'a, (&'static str, T, T)>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Read a register and capture its raw value and named fields in a serializable `Snapshot`.
+///
+/// # Usage
+/// The arguments are the same as the multiple field form of `read_reg!`. The register is read
+/// once, and the macro evaluates to a `Snapshot` of its value and the value of each field.
+///
+/// # Safety
+/// As for `read_reg!`.
+#[macro_export]
+macro_rules! snapshot_reg {
+    ( $periph:path, $instance:expr, $reg:ident $([$offset:expr])*, $( $field:ident ),+ ) => {{
+        #[allow(unused_imports)]
+        use $periph::{*};
+        let val = $crate::register!($periph, $instance, $reg $([$offset])*).read();
+        $crate::snapshot::Snapshot::new(
+            val,
+            [$((
+                stringify!($field),
+                (val & $crate::field_mask!($periph, $reg, $field))
+                    >> $crate::field_offset!($periph, $reg, $field),
+            )),+],
+        )
+    }};
+}
+
+/// Read several registers and capture their raw values in a serializable `BlockSnapshot`.
+///
+/// # Usage
+/// The arguments are the same as for `assert_reset!`, without the instance module: the path to
+/// the peripheral module, a reference to the instance, and a list of registers in square
+/// brackets, with an index for each element of a register array to read:
+/// `snapshot_all!(periph, inst, [CR1, CR2, MY_ARRAY[0]])`. Each register is read once, in the
+/// order listed, and named by its name and indices. Every register listed must have the same
+/// value type.
+///
+/// # Safety
+/// As for `read_reg!`.
+#[macro_export]
+macro_rules! snapshot_all {
+    ( $periph:path, $instance:expr, [ $( $reg:ident $([$offset:expr])* ),+ $(,)? ] ) => {{
+        #[allow(unused_imports)]
+        use $periph::{*};
+        $crate::snapshot::BlockSnapshot::new([$((
+            concat!(stringify!($reg) $(, "[", stringify!($offset), "]")*),
+            $crate::register!($periph, $instance, $reg $([$offset])*).read(),
+        )),+])
+    }};
+}
+
+/// Snapshot several registers before and after running a block of code, and list the registers
+/// that changed.
+///
+/// # Usage
+/// The arguments are the same as for `snapshot_all!`, followed by a block. The registers are
+/// read by `snapshot_all!`, the block is run, and the registers are read again. The macro
+/// evaluates to the `Diff` between the two snapshots, which lists the name, old value, and new
+/// value of each register that changed, in the order listed.
+///
+/// # Safety
+/// As for `read_reg!`.
+#[macro_export]
+macro_rules! diff_snapshot {
+    ( $periph:path, $instance:expr, [ $( $reg:ident $([$offset:expr])* ),+ $(,)? ], $body:block ) => {{
+        let before = $crate::snapshot_all!($periph, $instance, [ $( $reg $([$offset])* ),+ ]);
+        $body;
+        let after = $crate::snapshot_all!($periph, $instance, [ $( $reg $([$offset])* ),+ ]);
+        before.diff(&after)
+    }};
+}
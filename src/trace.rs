@@ -0,0 +1,149 @@
+//! Tracing of register accesses.
+//!
+//! With the `trace` feature enabled, every `read` and `write` on the register types calls the
+//! hook installed with [`set_hook`], after the access. Without the feature, none of this code
+//! is compiled, and register accesses are unchanged.
+//!
+//! ```
+//! fn log_access(addr: usize, width: u8, value: u64, is_write: bool) {
+//!     let op = if is_write { "write" } else { "read" };
+//!     println!("{} u{} {:#x} @ {:#010x}", op, width, value, addr);
+//! }
+//!
+//! ral_registers::trace::set_hook(log_access);
+//! ```
+//!
+//! # Counting accesses
+//! Every access to the register types is also counted, whether or not a hook is installed. An
+//! [`AccessCounter`] reports how many reads and writes were made after it was started, so a
+//! test can check that a driver makes no more register accesses than it needs to:
+//!
+//! ```
+//! # #![allow(non_snake_case, non_upper_case_globals)]
+//! # mod periph {
+//! #     pub struct RegisterBlock { pub CR: ral_registers::RWRegister<u32> }
+//! #     pub mod CR {
+//! #         pub mod EN { pub const offset: u32 = 0; pub const mask: u32 = 1 << offset;
+//! #             pub mod R {} pub mod W {} pub mod RW {} }
+//! #     }
+//! # }
+//! use ral_registers::{modify_reg, trace::AccessCounter, RWRegister};
+//!
+//! let block = periph::RegisterBlock { CR: RWRegister::new(0) };
+//! let counter = AccessCounter::start();
+//! modify_reg!(periph, &block, CR, EN: 1);
+//! assert_eq!((counter.reads(), counter.writes()), (1, 1));
+//! ```
+//!
+//! The counts are shared by every thread, so accesses made by other threads while the counter
+//! is running are counted too. Tests which count accesses should not run in parallel with other
+//! tests which access registers, for example by putting them in their own test binary. Counting
+//! requires atomic read-modify-write operations, so `AccessCounter` is not available on targets
+//! without them, such as `thumbv6m-none-eabi`.
+//!
+//! # Reentrancy
+//! The hook is called from inside the register methods, possibly from an interrupt handler,
+//! and must not itself access a register through this crate's register types; doing so would
+//! call the hook again, recursively. A hook which needs to log to a peripheral (such as a UART)
+//! should write to it through raw pointers, or buffer the accesses for later. The hook should
+//! also be short, since it runs on every register access.
+
+#[cfg(target_has_atomic = "ptr")]
+use core::sync::atomic::AtomicUsize;
+use core::sync::atomic::{AtomicPtr, Ordering};
+
+/// A trace hook.
+///
+/// The arguments are the address of the register, its width in bits, the value which was read
+/// or written (zero-extended), and whether the access was a write. Registers wider than 64 bits
+/// are not traced.
+pub type Hook = fn(addr: usize, width: u8, value: u64, is_write: bool);
+
+static HOOK: AtomicPtr<()> = AtomicPtr::new(core::ptr::null_mut());
+
+#[cfg(target_has_atomic = "ptr")]
+static READS: AtomicUsize = AtomicUsize::new(0);
+#[cfg(target_has_atomic = "ptr")]
+static WRITES: AtomicUsize = AtomicUsize::new(0);
+
+/// Installs `hook`, replacing any previous hook.
+pub fn set_hook(hook: Hook) {
+    HOOK.store(hook as *mut (), Ordering::Release);
+}
+
+/// Removes the installed hook, if any.
+pub fn clear_hook() {
+    HOOK.store(core::ptr::null_mut(), Ordering::Release);
+}
+
+/// Calls the installed hook, if any, for an access to the register at `addr`.
+#[inline]
+pub(crate) fn record<T: Copy>(addr: *const T, value: T, is_write: bool) {
+    #[cfg(target_has_atomic = "ptr")]
+    if is_write {
+        WRITES.fetch_add(1, Ordering::Relaxed);
+    } else {
+        READS.fetch_add(1, Ordering::Relaxed);
+    }
+
+    let hook = HOOK.load(Ordering::Acquire);
+    let size = core::mem::size_of::<T>();
+    if hook.is_null() || size > 8 {
+        return;
+    }
+    // Safety: the only non-null values stored in HOOK are `Hook`s.
+    let hook: Hook = unsafe { core::mem::transmute(hook) };
+
+    // Zero-extend the value to a u64, keeping its numeric value on either endianness.
+    let mut bytes = [0u8; 8];
+    let start = if cfg!(target_endian = "big") {
+        8 - size
+    } else {
+        0
+    };
+    // Safety: `value` is `size` bytes, and `bytes[start..]` has at least `size` bytes.
+    unsafe {
+        core::ptr::copy_nonoverlapping(
+            &value as *const T as *const u8,
+            bytes[start..].as_mut_ptr(),
+            size,
+        )
+    };
+    hook(
+        addr as usize,
+        (size * 8) as u8,
+        u64::from_ne_bytes(bytes),
+        is_write,
+    );
+}
+
+/// Counts the register reads and writes made after it was started.
+///
+/// See "Counting accesses" in the module documentation.
+#[cfg(target_has_atomic = "ptr")]
+#[derive(Clone, Copy, Debug)]
+pub struct AccessCounter {
+    reads: usize,
+    writes: usize,
+}
+
+#[cfg(target_has_atomic = "ptr")]
+impl AccessCounter {
+    /// Starts counting from the current number of accesses.
+    pub fn start() -> Self {
+        AccessCounter {
+            reads: READS.load(Ordering::Relaxed),
+            writes: WRITES.load(Ordering::Relaxed),
+        }
+    }
+
+    /// The number of register reads since the counter was started.
+    pub fn reads(&self) -> usize {
+        READS.load(Ordering::Relaxed).wrapping_sub(self.reads)
+    }
+
+    /// The number of register writes since the counter was started.
+    pub fn writes(&self) -> usize {
+        WRITES.load(Ordering::Relaxed).wrapping_sub(self.writes)
+    }
+}
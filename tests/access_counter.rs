@@ -0,0 +1,58 @@
+//! Tests for counting register accesses with `AccessCounter`.
+
+#![cfg(feature = "trace")]
+#![allow(non_upper_case_globals, non_snake_case)] // Macro conventions.
+
+use ral_registers as ral;
+use ral_registers::trace::AccessCounter;
+
+mod periph {
+    #[repr(C)]
+    pub struct RegisterBlock {
+        pub MY_SCALAR: ral_registers::RWRegister<u32>,
+    }
+
+    pub mod MY_SCALAR {
+        pub mod FIELD_A {
+            pub const offset: u32 = 0;
+            pub const mask: u32 = 0x7F << offset;
+            pub mod R {}
+            pub mod W {}
+            pub mod RW {}
+        }
+        pub mod FIELD_B {
+            pub const offset: u32 = 27;
+            pub const mask: u32 = 0b11 << offset;
+            pub mod R {}
+            pub mod W {}
+            pub mod RW {}
+        }
+    }
+}
+
+/// The counts are global, so this is the only test in this file.
+#[test]
+fn count_accesses() {
+    let rb = periph::RegisterBlock {
+        MY_SCALAR: ral::RWRegister::new(0),
+    };
+
+    let counter = AccessCounter::start();
+    ral::modify_reg!(periph, &rb, MY_SCALAR, FIELD_A: 0x55, FIELD_B: 0b10);
+    assert_eq!((counter.reads(), counter.writes()), (1, 1), "modify_reg!");
+
+    let counter = AccessCounter::start();
+    ral::write_reg!(periph, &rb, MY_SCALAR, FIELD_A: 1, FIELD_B: 1);
+    assert_eq!((counter.reads(), counter.writes()), (0, 1), "write_reg!");
+
+    let counter = AccessCounter::start();
+    let (a, b) = ral::read_reg!(periph, &rb, MY_SCALAR, FIELD_A, FIELD_B);
+    assert_eq!((a, b), (1, 1));
+    assert_eq!((counter.reads(), counter.writes()), (1, 0), "read_reg!");
+
+    // A counter counts every access made after it was started.
+    let total = AccessCounter::start();
+    ral::modify_reg!(periph, &rb, MY_SCALAR, |reg| reg + 1);
+    ral::write_reg!(periph, &rb, MY_SCALAR, 0);
+    assert_eq!((total.reads(), total.writes()), (1, 2));
+}
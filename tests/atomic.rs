@@ -0,0 +1,169 @@
+//! Tests for the atomic register type, and the `ordering` argument of the macros.
+
+#![allow(non_upper_case_globals, non_snake_case)] // Macro conventions.
+
+use core::sync::atomic::Ordering::{self, SeqCst};
+use ral_registers as ral;
+use ral_registers::AtomicRWRegister;
+
+mod periph {
+    use ral_registers::AtomicRWRegister;
+
+    #[repr(C)]
+    pub struct RegisterBlock {
+        pub MAILBOX: AtomicRWRegister<u32>,
+        pub MY_ARRAY: [AtomicRWRegister<u32>; 2],
+    }
+
+    impl Default for RegisterBlock {
+        fn default() -> Self {
+            Self {
+                MAILBOX: AtomicRWRegister::new(0),
+                MY_ARRAY: [AtomicRWRegister::new(0), AtomicRWRegister::new(0)],
+            }
+        }
+    }
+
+    pub mod MAILBOX {
+        pub mod COUNT {
+            pub const offset: u32 = 0;
+            pub const mask: u32 = 0xFFFF << offset;
+            pub mod R {}
+            pub mod W {}
+            pub mod RW {}
+        }
+        pub mod FLAG {
+            pub const offset: u32 = 31;
+            pub const mask: u32 = 1 << offset;
+            pub mod R {}
+            pub mod W {}
+            pub mod RW {
+                pub const Set: u32 = 1;
+            }
+        }
+    }
+
+    pub mod MY_ARRAY {
+        pub use super::MAILBOX::*;
+    }
+}
+
+#[test]
+fn methods() {
+    let reg = AtomicRWRegister::new(5u32);
+    assert_eq!(reg.read(SeqCst), 5);
+    reg.write(7, SeqCst);
+    assert_eq!(reg.read(Ordering::Acquire), 7);
+
+    assert_eq!(reg.fetch_update(SeqCst, SeqCst, |val| Some(val + 1)), Ok(7));
+    assert_eq!(reg.fetch_update(SeqCst, SeqCst, |_| None), Err(8));
+    assert_eq!(reg.modify(SeqCst, |val| val * 2), 16);
+    assert_eq!(reg.modify(Ordering::AcqRel, |val| val + 1), 17);
+
+    let ordered = reg.ordered(Ordering::AcqRel);
+    ordered.write(3);
+    assert_eq!(ordered.read(), 3);
+    assert_eq!(format!("{:?}", reg), "AtomicRWRegister(0x00000003)");
+}
+
+#[test]
+fn macros() {
+    let rb = periph::RegisterBlock::default();
+
+    ral::write_reg!(periph, &rb, MAILBOX, ordering = SeqCst, COUNT: 3, FLAG: Set);
+    assert_eq!(rb.MAILBOX.read(SeqCst), 1 << 31 | 3);
+    assert_eq!(
+        ral::read_reg!(periph, &rb, MAILBOX, ordering = SeqCst),
+        1 << 31 | 3
+    );
+    assert_eq!(
+        ral::read_reg!(periph, &rb, MAILBOX, ordering = SeqCst, COUNT),
+        3
+    );
+    assert!(ral::read_reg!(
+        periph,
+        &rb,
+        MAILBOX,
+        ordering = SeqCst,
+        FLAG == Set
+    ));
+
+    let value = ral::modify_reg!(periph, &rb, MAILBOX, ordering = SeqCst, COUNT: 9);
+    assert_eq!(value, 1 << 31 | 9);
+    let value = ral::modify_reg!(periph, &rb, MAILBOX, ordering = SeqCst, |reg| reg & !1);
+    assert_eq!(value, 1 << 31 | 8);
+    let value =
+        ral::modify_reg!(periph, &rb, MAILBOX, ordering = SeqCst, [COUNT] => |count| count + 1);
+    assert_eq!(value, 1 << 31 | 9);
+    let value = ral::modify_reg!(periph, &rb, MAILBOX, ordering = SeqCst, COUNT: |count| count * 2);
+    assert_eq!(value, 1 << 31 | 18);
+    let value =
+        ral::modify_reg!(periph, &rb, MAILBOX, ordering = SeqCst, clear: FLAG, set: COUNT: 3);
+    assert_eq!(value, 3);
+    let value = ral::modify_reg!(
+        periph,
+        &rb,
+        MAILBOX,
+        ordering = SeqCst,
+        COUNT |= 0x10,
+        FLAG ^= Set
+    );
+    assert_eq!(value, 1 << 31 | 0x13);
+    ral::write_reg!(periph, &rb, MAILBOX, ordering = SeqCst, COUNT: 0xFFFF);
+    let value = ral::increment_field!(periph, &rb, MAILBOX, ordering = SeqCst, COUNT);
+    assert_eq!(value, 0);
+
+    ral::write_reg!(periph, &rb, MY_ARRAY[1], ordering = SeqCst, 0x1234);
+    assert_eq!(
+        ral::read_reg!(periph, &rb, MY_ARRAY[1], ordering = SeqCst, COUNT),
+        0x1234
+    );
+    assert_eq!(rb.MY_ARRAY[0].read(SeqCst), 0);
+}
+
+#[test]
+fn try_macros() {
+    let rb = periph::RegisterBlock::default();
+    assert_eq!(
+        ral::try_write_reg!(periph, &rb, MY_ARRAY[1], ordering = SeqCst, COUNT: 3),
+        Ok(())
+    );
+    assert_eq!(
+        ral::try_modify_reg!(periph, &rb, MY_ARRAY[1], ordering = SeqCst, FLAG: Set),
+        Ok(1 << 31 | 3)
+    );
+    assert_eq!(
+        ral::try_read_reg!(periph, &rb, MY_ARRAY[1], ordering = SeqCst, COUNT),
+        Ok(3)
+    );
+
+    let err = ral::IndexError { index: 2, len: 2 };
+    assert_eq!(
+        ral::try_read_reg!(periph, &rb, MY_ARRAY[2], ordering = SeqCst),
+        Err(err)
+    );
+    assert_eq!(
+        ral::try_write_reg!(periph, &rb, MY_ARRAY[2], ordering = SeqCst, 0),
+        Err(err)
+    );
+}
+
+#[test]
+fn concurrent_modify() {
+    let rb = periph::RegisterBlock::default();
+
+    std::thread::scope(|scope| {
+        for _ in 0..4 {
+            scope.spawn(|| {
+                for _ in 0..1000 {
+                    ral::modify_reg!(periph, &rb, MAILBOX, ordering = SeqCst, [COUNT] => |count| count + 1);
+                }
+            });
+        }
+    });
+
+    assert_eq!(
+        ral::read_reg!(periph, &rb, MAILBOX, ordering = SeqCst, COUNT),
+        4000
+    );
+}
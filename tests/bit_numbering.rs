@@ -0,0 +1,134 @@
+//! Tests that the macros translate the fields of a register module
+//! numbered `BitOrder::MsbZero`.
+//!
+//! `LSB` and `MSB` describe the same physical bits, once numbered from
+//! the least significant bit, and once from the most significant bit.
+
+#![allow(non_upper_case_globals, non_snake_case)] // Macro conventions.
+
+use ral_registers as ral;
+use ral_registers::RegisterFields;
+
+mod periph {
+    #[repr(C)]
+    pub struct RegisterBlock {
+        pub LSB: ral_registers::RWRegister<u32>,
+        pub MSB: ral_registers::RWRegister<u32>,
+        pub MSB16: ral_registers::RWRegister<u16>,
+    }
+
+    pub mod LSB {
+        /// Bits 0 to 6.
+        pub mod FIELD_A {
+            pub const offset: u32 = 0;
+            pub const mask: u32 = 0x7F << offset;
+            pub mod R {}
+            pub mod W {}
+            pub mod RW {}
+        }
+        /// Bits 27 and 28.
+        pub mod FIELD_B {
+            pub const offset: u32 = 27;
+            pub const mask: u32 = 0b11 << offset;
+            pub mod R {}
+            pub mod W {}
+            pub mod RW {
+                pub const Fast: u32 = 0b10;
+            }
+        }
+        ral_registers::impl_register_fields!(LSB, [FIELD_A, FIELD_B]);
+    }
+
+    pub mod MSB {
+        use ral_registers::BitOrder;
+        pub const BIT_NUMBERING: BitOrder = BitOrder::MsbZero;
+
+        /// Bits 25 to 31, numbered from the most significant bit.
+        pub mod FIELD_A {
+            pub const offset: u32 = 25;
+            pub const mask: u32 = 0x7F << offset;
+            pub mod R {}
+            pub mod W {}
+            pub mod RW {}
+        }
+        /// Bits 3 and 4, numbered from the most significant bit.
+        pub mod FIELD_B {
+            pub const offset: u32 = 3;
+            pub const mask: u32 = 0b11 << offset;
+            pub mod R {}
+            pub mod W {}
+            pub mod RW {
+                pub const Fast: u32 = 0b10;
+            }
+        }
+        ral_registers::impl_register_fields!(MSB, [FIELD_A, FIELD_B]);
+    }
+
+    pub mod MSB16 {
+        pub use super::MSB::BIT_NUMBERING;
+
+        /// Bits 0 to 3, the most significant nibble.
+        pub mod TOP {
+            pub const offset: u16 = 0;
+            pub const mask: u16 = 0xF << offset;
+            pub mod R {}
+            pub mod W {}
+            pub mod RW {}
+        }
+    }
+}
+
+fn register_block() -> periph::RegisterBlock {
+    periph::RegisterBlock {
+        LSB: ral::RWRegister::new(0),
+        MSB: ral::RWRegister::new(0),
+        MSB16: ral::RWRegister::new(0),
+    }
+}
+
+#[test]
+fn read_same_bits() {
+    let rb = register_block();
+    for value in [
+        0,
+        u32::MAX,
+        0x5A5A_A5A5,
+        0b10 << 27 | 0x2A,
+        1 << 29 | 1 << 7,
+    ] {
+        rb.LSB.write(value);
+        rb.MSB.write(value);
+        assert_eq!(
+            ral::read_reg!(periph, &rb, LSB, FIELD_A, FIELD_B),
+            ral::read_reg!(periph, &rb, MSB, FIELD_A, FIELD_B),
+            "{:#x}",
+            value
+        );
+    }
+}
+
+#[test]
+fn write_same_bits() {
+    let rb = register_block();
+    ral::write_reg!(periph, &rb, LSB, FIELD_A: 0x2A, FIELD_B: Fast);
+    ral::write_reg!(periph, &rb, MSB, FIELD_A: 0x2A, FIELD_B: Fast);
+    assert_eq!(rb.MSB.read(), 0b10 << 27 | 0x2A);
+    assert_eq!(rb.MSB.read(), rb.LSB.read());
+
+    ral::modify_reg!(periph, &rb, MSB, FIELD_B: 0b01);
+    assert_eq!(rb.MSB.read(), 0b01 << 27 | 0x2A);
+    assert!(ral::read_reg!(periph, &rb, MSB, FIELD_A == 0x2A));
+}
+
+#[test]
+fn register_fields() {
+    assert_eq!(periph::LSB::Fields::FIELDS, periph::MSB::Fields::FIELDS);
+}
+
+#[test]
+fn narrow_register() {
+    let rb = register_block();
+    ral::write_reg!(periph, &rb, MSB16, TOP: 0xA);
+    assert_eq!(rb.MSB16.read(), 0xA000);
+    assert_eq!(ral::read_reg!(periph, &rb, MSB16, TOP), 0xA);
+}
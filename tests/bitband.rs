@@ -0,0 +1,63 @@
+//! Tests for bit-band address calculations.
+
+#![cfg(feature = "cortex-m")]
+
+use ral_registers::bitband::bit_band_addr;
+
+#[test]
+fn sram() {
+    assert_eq!(bit_band_addr(0x2000_0000, 0) as usize, 0x2200_0000);
+    assert_eq!(bit_band_addr(0x2000_0000, 31) as usize, 0x2200_007C);
+    assert_eq!(bit_band_addr(0x2000_0300, 2) as usize, 0x2200_6008);
+    assert_eq!(bit_band_addr(0x200F_FFFC, 31) as usize, 0x23FF_FFFC);
+}
+
+#[test]
+fn peripheral() {
+    assert_eq!(bit_band_addr(0x4000_0000, 0) as usize, 0x4200_0000);
+    assert_eq!(bit_band_addr(0x4002_1014, 17) as usize, 0x4242_02C4);
+    assert_eq!(bit_band_addr(0x400F_FFFC, 31) as usize, 0x43FF_FFFC);
+}
+
+#[should_panic(expected = "not in a bit-band region")]
+#[test]
+fn outside_region() {
+    bit_band_addr(0x4010_0000, 0);
+}
+
+#[should_panic(expected = "not in a bit-band region")]
+#[test]
+fn below_region() {
+    bit_band_addr(0x1FFF_FFFC, 0);
+}
+
+#[should_panic(expected = "out of range")]
+#[test]
+fn bit_out_of_range() {
+    bit_band_addr(0x4000_0000, 32);
+}
+
+#[allow(non_upper_case_globals, non_snake_case)] // Macro conventions.
+mod periph {
+    #[repr(C)]
+    pub struct RegisterBlock {
+        pub MY_REG: ral_registers::RWRegister<u32>,
+    }
+
+    pub mod MY_REG {
+        pub mod FLAG {
+            pub const offset: u32 = 5;
+            pub const mask: u32 = 1 << offset;
+        }
+    }
+}
+
+/// Checks that the macros expand in an `unsafe` block; they can't run on the host.
+#[allow(dead_code)]
+fn bitband_macros(inst: &periph::RegisterBlock) {
+    // Safety: only called on a device which implements bit-banding.
+    unsafe {
+        ral_registers::bitband_set!(periph, inst, MY_REG, FLAG);
+        ral_registers::bitband_clear!(periph, inst, MY_REG, FLAG);
+    }
+}
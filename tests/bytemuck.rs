@@ -0,0 +1,49 @@
+//! Tests for the `bytemuck::Zeroable` impls.
+
+#![cfg(feature = "bytemuck")]
+#![allow(non_upper_case_globals, non_snake_case)] // Macro conventions.
+
+use bytemuck::Zeroable;
+use ral_registers as ral;
+
+mod periph {
+    use ral_registers::{RORegister, RWRegister, RWRegisterBE, UnsafeRWRegister, WORegister};
+
+    #[repr(C)]
+    pub struct RegisterBlock {
+        pub MY_RW: RWRegister<u32>,
+        pub MY_RO: RORegister<u16>,
+        pub MY_WO: WORegister<u16>,
+        pub MY_UNSAFE_RW: UnsafeRWRegister<u64>,
+        pub MY_BE: RWRegisterBE<u32>,
+        pub MY_ARRAY: [RWRegister<u8>; 4],
+    }
+
+    // Safety: every field is `Zeroable`.
+    unsafe impl bytemuck::Zeroable for RegisterBlock {}
+
+    pub mod MY_RW {
+        pub mod FIELD {
+            pub const offset: u32 = 4;
+            pub const mask: u32 = 0xF << offset;
+            pub mod R {}
+            pub mod W {}
+            pub mod RW {}
+        }
+    }
+}
+
+#[test]
+fn zeroed_register_block() {
+    let inst = periph::RegisterBlock::zeroed();
+
+    assert_eq!(ral::read_reg!(periph, &inst, MY_RW), 0);
+    assert_eq!(inst.MY_RO.read(), 0);
+    assert_eq!(unsafe { inst.MY_UNSAFE_RW.read() }, 0);
+    assert_eq!(inst.MY_BE.read(), 0);
+    assert!(inst.MY_ARRAY.iter().all(|reg| reg.read() == 0));
+
+    ral::modify_reg!(periph, &inst, MY_RW, FIELD: 0b1010);
+    assert_eq!(ral::read_reg!(periph, &inst, MY_RW, FIELD), 0b1010);
+    inst.MY_WO.write(1);
+}
@@ -0,0 +1,595 @@
+//! Tests that count the register accesses performed by the macros.
+//!
+//! The macros only require that a register has `read()` and `write()`
+//! methods, so these tests use a register that counts each access.
+
+#![allow(non_upper_case_globals, non_snake_case)] // Macro conventions.
+
+use ral_registers as ral;
+use std::cell::{Cell, RefCell};
+
+/// A register access.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Access {
+    Read(u32),
+    Write(u32),
+}
+
+std::thread_local! {
+    /// Every access performed on this thread, with the address of the register.
+    static LOG: RefCell<Vec<(usize, Access)>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Takes the log of accesses performed on this thread.
+fn take_log() -> Vec<(usize, Access)> {
+    LOG.with(|log| log.take())
+}
+
+/// A register which counts its reads and writes.
+#[derive(Default)]
+pub struct CountingRegister {
+    value: Cell<u32>,
+    reads: Cell<u32>,
+    writes: Cell<u32>,
+    /// After this many reads, the value changes.
+    change: Cell<Option<(u32, u32)>>,
+}
+
+impl CountingRegister {
+    pub fn read(&self) -> u32 {
+        self.reads.set(self.reads.get() + 1);
+        if let Some((reads, value)) = self.change.get() {
+            if self.reads.get() >= reads {
+                self.value.set(value);
+            }
+        }
+        self.log(Access::Read(self.value.get()));
+        self.value.get()
+    }
+
+    pub fn write(&self, value: u32) {
+        self.writes.set(self.writes.get() + 1);
+        self.log(Access::Write(value));
+        self.value.set(value);
+    }
+
+    fn log(&self, access: Access) {
+        let addr = self.addr();
+        LOG.with(|log| log.borrow_mut().push((addr, access)));
+    }
+
+    /// Returns the address of the register, which identifies it in the log.
+    fn addr(&self) -> usize {
+        self as *const Self as usize
+    }
+
+    /// Sets the value without counting an access.
+    fn set(&self, value: u32) {
+        self.value.set(value);
+    }
+
+    /// Changes the value to `value` on the `reads`th read, as if the hardware changed it.
+    fn change_on_read(&self, reads: u32, value: u32) {
+        self.change.set(Some((reads, value)));
+    }
+
+    /// Returns the value without counting an access.
+    fn get(&self) -> u32 {
+        self.value.get()
+    }
+
+    /// Returns the (reads, writes) counts.
+    fn accesses(&self) -> (u32, u32) {
+        (self.reads.get(), self.writes.get())
+    }
+}
+
+mod periph {
+    #[derive(Default)]
+    pub struct RegisterBlock {
+        pub MY_ARRAY: [super::CountingRegister; 3],
+    }
+
+    pub mod MY_ARRAY {
+        /// Bits 8 to 15 are reserved, and must be written back as read.
+        pub const preserve_mask: u32 = 0xFF << 8;
+
+        pub mod FIELD_A {
+            pub const offset: u32 = 0;
+            pub const mask: u32 = 0x7F << offset;
+            pub mod R {}
+            pub mod W {}
+            pub mod RW {}
+        }
+        pub mod FIELD_B {
+            pub const offset: u32 = 27;
+            pub const mask: u32 = 0b11 << offset;
+            pub mod R {}
+            pub mod W {}
+            pub mod RW {}
+        }
+    }
+}
+
+#[test]
+fn bit_set() {
+    let rb = periph::RegisterBlock::default();
+    rb.MY_ARRAY[1].set(1 << 8);
+
+    ral::bit_set!(periph, &rb, MY_ARRAY[1], FIELD_A);
+    assert_eq!(rb.MY_ARRAY[1].get(), 1 << 8 | 0x7F);
+    assert_eq!(rb.MY_ARRAY[1].accesses(), (1, 1));
+
+    let value = ral::bit_set!(periph, &rb, MY_ARRAY[1], FIELD_B);
+    assert_eq!(value, 0b11 << 27 | 1 << 8 | 0x7F);
+    assert_eq!(rb.MY_ARRAY[1].get(), value);
+    assert_eq!(rb.MY_ARRAY[1].accesses(), (2, 2));
+
+    rb.MY_ARRAY[2].set(0);
+    ral::bit_set!(periph, &rb, MY_ARRAY[2], FIELD_A, FIELD_B);
+    assert_eq!(rb.MY_ARRAY[2].get(), 0b11 << 27 | 0x7F);
+    assert_eq!(rb.MY_ARRAY[2].accesses(), (1, 1));
+}
+
+#[test]
+fn bit_clear() {
+    let rb = periph::RegisterBlock::default();
+    rb.MY_ARRAY[1].set(u32::MAX);
+
+    ral::bit_clear!(periph, &rb, MY_ARRAY[1], FIELD_A);
+    assert_eq!(rb.MY_ARRAY[1].get(), !0x7F);
+    assert_eq!(rb.MY_ARRAY[1].accesses(), (1, 1));
+
+    let value = ral::bit_clear!(periph, &rb, MY_ARRAY[1], FIELD_B);
+    assert_eq!(value, !0x7F & !(0b11 << 27));
+    assert_eq!(rb.MY_ARRAY[1].get(), value);
+    assert_eq!(rb.MY_ARRAY[1].accesses(), (2, 2));
+
+    rb.MY_ARRAY[2].set(u32::MAX);
+    ral::bit_clear!(periph, &rb, MY_ARRAY[2], FIELD_A, FIELD_B);
+    assert_eq!(rb.MY_ARRAY[2].get(), !0x7F & !(0b11 << 27));
+    assert_eq!(rb.MY_ARRAY[2].accesses(), (1, 1));
+}
+
+#[test]
+fn increment() {
+    let rb = periph::RegisterBlock::default();
+    let others = 0x55 | 1 << 8 | 1 << 31;
+    rb.MY_ARRAY[1].set(others | 0b10 << 27);
+
+    let value = ral::increment_field!(periph, &rb, MY_ARRAY[1], FIELD_B);
+    assert_eq!(value, others | 0b11 << 27);
+    assert_eq!(rb.MY_ARRAY[1].get(), value);
+    assert_eq!(rb.MY_ARRAY[1].accesses(), (1, 1));
+
+    // The field wraps from 3 to 0, without carrying into bit 29.
+    let value = ral::increment_field!(periph, &rb, MY_ARRAY[1], FIELD_B);
+    assert_eq!(value, others);
+    assert_eq!(rb.MY_ARRAY[1].get(), others);
+    assert_eq!(rb.MY_ARRAY[1].accesses(), (2, 2));
+
+    ral::increment_field!(periph, &rb, MY_ARRAY[1], FIELD_B);
+    assert_eq!(rb.MY_ARRAY[1].get(), others | 0b01 << 27);
+
+    rb.MY_ARRAY[2].set(0x7F);
+    ral::increment_field!(periph, &rb, MY_ARRAY[2], FIELD_A);
+    assert_eq!(rb.MY_ARRAY[2].get(), 0);
+    assert_eq!(rb.MY_ARRAY[0].accesses(), (0, 0));
+}
+
+#[test]
+fn toggle() {
+    let rb = periph::RegisterBlock::default();
+    let original = 0x55 | 0b01 << 27 | 1 << 8;
+    rb.MY_ARRAY[1].set(original);
+
+    let value = ral::toggle_reg!(periph, &rb, MY_ARRAY[1], FIELD_A);
+    assert_eq!(value, 0x2A | 0b01 << 27 | 1 << 8);
+    assert_eq!(rb.MY_ARRAY[1].get(), value);
+    assert_eq!(rb.MY_ARRAY[1].accesses(), (1, 1));
+
+    ral::toggle_reg!(periph, &rb, MY_ARRAY[1], FIELD_A);
+    assert_eq!(rb.MY_ARRAY[1].get(), original);
+    assert_eq!(rb.MY_ARRAY[1].accesses(), (2, 2));
+
+    ral::toggle_reg!(periph, &rb, MY_ARRAY[1], FIELD_A, FIELD_B);
+    assert_eq!(rb.MY_ARRAY[1].get(), 0x2A | 0b10 << 27 | 1 << 8);
+    assert_eq!(rb.MY_ARRAY[1].accesses(), (3, 3));
+
+    ral::toggle_reg!(periph, &rb, MY_ARRAY[1], FIELD_A, FIELD_B);
+    assert_eq!(rb.MY_ARRAY[1].get(), original);
+    assert_eq!(rb.MY_ARRAY[1].accesses(), (4, 4));
+}
+
+#[test]
+fn write_if_changed() {
+    let rb = periph::RegisterBlock::default();
+    rb.MY_ARRAY[1].set(1 << 8);
+
+    assert!(ral::write_reg_if_changed!(periph, &rb, MY_ARRAY[1], FIELD_A: 3));
+    assert_eq!(rb.MY_ARRAY[1].get(), 3);
+    assert_eq!(rb.MY_ARRAY[1].accesses(), (1, 1));
+
+    assert!(!ral::write_reg_if_changed!(periph, &rb, MY_ARRAY[1], FIELD_A: 3));
+    assert_eq!(rb.MY_ARRAY[1].accesses(), (2, 1));
+
+    assert!(!ral::write_reg_if_changed!(periph, &rb, MY_ARRAY[1], 3));
+    assert_eq!(rb.MY_ARRAY[1].accesses(), (3, 1));
+
+    assert!(ral::write_reg_if_changed!(periph, &rb, MY_ARRAY[1], FIELD_A: 3, FIELD_B: 1));
+    assert_eq!(rb.MY_ARRAY[1].get(), 1 << 27 | 3);
+    assert_eq!(rb.MY_ARRAY[1].accesses(), (4, 2));
+}
+
+#[test]
+fn swap() {
+    let rb = periph::RegisterBlock::default();
+    rb.MY_ARRAY[0].set(0x1111_1111);
+    rb.MY_ARRAY[2].set(0x2222_2222);
+
+    ral::swap_reg!(periph, &rb, MY_ARRAY[0], MY_ARRAY[2]);
+    assert_eq!(rb.MY_ARRAY[0].get(), 0x2222_2222);
+    assert_eq!(rb.MY_ARRAY[2].get(), 0x1111_1111);
+    assert_eq!(
+        take_log(),
+        [
+            (rb.MY_ARRAY[0].addr(), Access::Read(0x1111_1111)),
+            (rb.MY_ARRAY[2].addr(), Access::Read(0x2222_2222)),
+            (rb.MY_ARRAY[0].addr(), Access::Write(0x2222_2222)),
+            (rb.MY_ARRAY[2].addr(), Access::Write(0x1111_1111)),
+        ]
+    );
+}
+
+#[test]
+fn write_preserve() {
+    let rb = periph::RegisterBlock::default();
+    rb.MY_ARRAY[1].set(0xA5 << 8 | 0b11 << 27 | 0x7F);
+
+    let value = ral::write_reg_preserve!(periph, &rb, MY_ARRAY[1], FIELD_A: 3);
+    assert_eq!(value, 0xA5 << 8 | 3);
+    assert_eq!(rb.MY_ARRAY[1].get(), value);
+    assert_eq!(rb.MY_ARRAY[1].accesses(), (1, 1));
+
+    ral::write_reg_preserve!(periph, &rb, MY_ARRAY[1], 0xFFFF_0000);
+    assert_eq!(rb.MY_ARRAY[1].get(), 0xFFFF_0000 | 0xA5 << 8);
+    assert_eq!(rb.MY_ARRAY[1].accesses(), (2, 2));
+}
+
+#[test]
+fn clear_flags() {
+    let rb = periph::RegisterBlock::default();
+    rb.MY_ARRAY[1].set(u32::MAX);
+
+    let value = ral::clear_flags!(periph, &rb, MY_ARRAY[1], FIELD_B);
+    assert_eq!(value, 0b11 << 27);
+    assert_eq!(
+        take_log(),
+        [(rb.MY_ARRAY[1].addr(), Access::Write(0b11 << 27))]
+    );
+
+    ral::clear_flags!(periph, &rb, MY_ARRAY[1], FIELD_A, FIELD_B);
+    assert_eq!(
+        take_log(),
+        [(rb.MY_ARRAY[1].addr(), Access::Write(0b11 << 27 | 0x7F))]
+    );
+    assert_eq!(rb.MY_ARRAY[1].accesses(), (0, 2));
+}
+
+#[test]
+fn wait() {
+    let rb = periph::RegisterBlock::default();
+    rb.MY_ARRAY[1].set(0b01 << 27);
+    rb.MY_ARRAY[1].change_on_read(4, 0);
+
+    ral::wait_reg!(periph, &rb, MY_ARRAY[1], FIELD_B == 0);
+    assert_eq!(rb.MY_ARRAY[1].accesses(), (4, 0));
+}
+
+#[test]
+fn wait_bounded() {
+    let rb = periph::RegisterBlock::default();
+    rb.MY_ARRAY[1].set(0x7F);
+    rb.MY_ARRAY[1].change_on_read(3, 0x2A);
+
+    let result = ral::wait_reg!(periph, &rb, MY_ARRAY[1], FIELD_A == 0x2A, max_iters = 10);
+    assert_eq!(result, Ok(()));
+    assert_eq!(rb.MY_ARRAY[1].accesses(), (3, 0));
+
+    let result = ral::wait_reg!(periph, &rb, MY_ARRAY[1], FIELD_A != 0x2A, max_iters = 10);
+    assert_eq!(result, Err(ral::Timeout));
+    assert_eq!(rb.MY_ARRAY[1].accesses(), (13, 0));
+}
+
+#[test]
+fn wait_backoff() {
+    let rb = periph::RegisterBlock::default();
+    rb.MY_ARRAY[1].set(0b01 << 27);
+    rb.MY_ARRAY[1].change_on_read(4, 0);
+
+    let mut backoffs = 0;
+    ral::wait_reg!(
+        periph,
+        &rb,
+        MY_ARRAY[1],
+        FIELD_B == 0,
+        backoff = || backoffs += 1
+    );
+    assert_eq!(backoffs, 3);
+    assert_eq!(rb.MY_ARRAY[1].accesses(), (4, 0));
+}
+
+#[test]
+fn wait_bounded_backoff() {
+    let rb = periph::RegisterBlock::default();
+    rb.MY_ARRAY[1].set(0x7F);
+    rb.MY_ARRAY[1].change_on_read(3, 0x2A);
+
+    // Each backoff happens between two reads.
+    let backoffs = Cell::new(0);
+    let backoff = || {
+        assert_eq!(rb.MY_ARRAY[1].accesses().0, backoffs.get() + 1);
+        backoffs.set(backoffs.get() + 1);
+    };
+    let result = ral::wait_reg!(
+        periph,
+        &rb,
+        MY_ARRAY[1],
+        FIELD_A == 0x2A,
+        max_iters = 10,
+        backoff = backoff
+    );
+    assert_eq!(result, Ok(()));
+    assert_eq!(backoffs.get(), 2);
+    assert_eq!(rb.MY_ARRAY[1].accesses(), (3, 0));
+
+    let mut backoffs = 0;
+    let result = ral::wait_reg!(
+        periph,
+        &rb,
+        MY_ARRAY[1],
+        FIELD_A != 0x2A,
+        max_iters = 10,
+        backoff = || backoffs += 1
+    );
+    assert_eq!(result, Err(ral::Timeout));
+    assert_eq!(backoffs, 9);
+    assert_eq!(rb.MY_ARRAY[1].accesses(), (13, 0));
+}
+
+#[test]
+fn poll() {
+    let rb = periph::RegisterBlock::default();
+    rb.MY_ARRAY[1].set(0b01 << 27);
+    rb.MY_ARRAY[1].change_on_read(5, 0);
+
+    let reads = ral::poll_reg!(periph, &rb, MY_ARRAY[1], FIELD_B == 0);
+    assert_eq!(reads, 5);
+    assert_eq!(rb.MY_ARRAY[1].accesses(), (5, 0));
+}
+
+#[test]
+fn poll_bounded() {
+    let rb = periph::RegisterBlock::default();
+    rb.MY_ARRAY[1].set(0b01 << 27);
+    rb.MY_ARRAY[1].change_on_read(5, 0);
+
+    let result = ral::poll_reg!(periph, &rb, MY_ARRAY[1], FIELD_B == 0, max_iters = 10);
+    assert_eq!(result, Ok(5));
+    assert_eq!(rb.MY_ARRAY[1].accesses(), (5, 0));
+
+    let result = ral::poll_reg!(periph, &rb, MY_ARRAY[1], FIELD_B != 0, max_iters = 10);
+    assert_eq!(result, Err(ral::Timeout));
+    assert_eq!(rb.MY_ARRAY[1].accesses(), (15, 0));
+}
+
+#[test]
+fn read_raw_and_fields() {
+    let rb = periph::RegisterBlock::default();
+    let value = 0b10 << 27 | 0xAB << 8 | 0x2A;
+    rb.MY_ARRAY[1].set(value);
+
+    let (raw, (a, b)) = ral::read_reg!(periph, &rb, MY_ARRAY[1], raw + (FIELD_A, FIELD_B));
+    assert_eq!(raw, value);
+    assert_eq!((a, b), (0x2A, 0b10));
+    assert_eq!(take_log(), [(rb.MY_ARRAY[1].addr(), Access::Read(value))]);
+
+    let (raw, b) = ral::read_reg!(periph, &rb, MY_ARRAY[1], raw + (FIELD_B));
+    assert_eq!((raw, b), (value, 0b10));
+    assert_eq!(rb.MY_ARRAY[1].accesses(), (2, 0));
+}
+
+#[test]
+fn read_many() {
+    let rb = periph::RegisterBlock::default();
+    rb.MY_ARRAY[0].set(10);
+    rb.MY_ARRAY[1].set(11);
+    rb.MY_ARRAY[2].set(12);
+
+    let values: [u32; 3] = ral::read_many!(periph, &rb, MY_ARRAY, 0..3);
+    assert_eq!(values, [10, 11, 12]);
+    assert_eq!(
+        take_log(),
+        [
+            (rb.MY_ARRAY[0].addr(), Access::Read(10)),
+            (rb.MY_ARRAY[1].addr(), Access::Read(11)),
+            (rb.MY_ARRAY[2].addr(), Access::Read(12)),
+        ]
+    );
+
+    assert_eq!(ral::read_many!(periph, &rb, MY_ARRAY, 1..3), [11, 12]);
+    assert_eq!(
+        take_log(),
+        [
+            (rb.MY_ARRAY[1].addr(), Access::Read(11)),
+            (rb.MY_ARRAY[2].addr(), Access::Read(12)),
+        ]
+    );
+}
+
+#[should_panic]
+#[test]
+fn read_many_out_of_bounds() {
+    let rb = periph::RegisterBlock::default();
+    let _ = ral::read_many!(periph, &rb, MY_ARRAY, 1..4);
+}
+
+#[test]
+fn with_reg() {
+    let rb = periph::RegisterBlock::default();
+    rb.MY_ARRAY[1].set(0b01 << 27 | 1 << 8 | 5);
+
+    let old = ral::with_reg!(periph, &rb, MY_ARRAY[1], [FIELD_A, FIELD_B], |r| {
+        let a = r.get(FIELD_A);
+        r.set(FIELD_A, a + 1);
+        r.set(FIELD_B, r.get(FIELD_B) + 1);
+        a
+    });
+    assert_eq!(old, 5);
+    assert_eq!(
+        take_log(),
+        [
+            (rb.MY_ARRAY[1].addr(), Access::Read(0b01 << 27 | 1 << 8 | 5)),
+            (
+                rb.MY_ARRAY[1].addr(),
+                Access::Write(0b10 << 27 | 1 << 8 | 6)
+            ),
+        ]
+    );
+
+    let b = ral::with_reg!(periph, &rb, MY_ARRAY[1], [FIELD_B], |r| r.get(FIELD_B));
+    assert_eq!(b, 0b10);
+    assert_eq!(rb.MY_ARRAY[1].accesses(), (2, 1));
+}
+
+#[test]
+fn read_pair() {
+    let rb = periph::RegisterBlock::default();
+    rb.MY_ARRAY[0].set(1);
+    rb.MY_ARRAY[1].set(0x1234);
+    assert_eq!(
+        ral::read_pair!(periph, &rb, MY_ARRAY[0], MY_ARRAY[1]),
+        1 << 32 | 0x1234
+    );
+    assert_eq!(rb.MY_ARRAY[0].accesses(), (2, 0));
+    assert_eq!(rb.MY_ARRAY[1].accesses(), (1, 0));
+}
+
+#[test]
+fn read_pair_rollover() {
+    let rb = periph::RegisterBlock::default();
+    let (hi, lo) = (&rb.MY_ARRAY[0], &rb.MY_ARRAY[1]);
+    hi.set(1);
+    lo.set(0xFFFF_FFFF);
+    // The low half rolls over after it is first read.
+    hi.change_on_read(2, 2);
+    lo.change_on_read(2, 3);
+    take_log();
+
+    assert_eq!(
+        ral::read_pair!(periph, &rb, MY_ARRAY[0], MY_ARRAY[1]),
+        2 << 32 | 3
+    );
+    assert_eq!(
+        take_log(),
+        [
+            (hi.addr(), Access::Read(1)),
+            (lo.addr(), Access::Read(0xFFFF_FFFF)),
+            (hi.addr(), Access::Read(2)),
+            (lo.addr(), Access::Read(3)),
+            (hi.addr(), Access::Read(2)),
+        ]
+    );
+}
+
+#[test]
+fn write_reg64_lo_first() {
+    let rb = periph::RegisterBlock::default();
+    let (lo, hi) = (&rb.MY_ARRAY[0], &rb.MY_ARRAY[1]);
+
+    ral::write_reg64!(periph, &rb, MY_ARRAY[0], MY_ARRAY[1], 0x1_0000_0002);
+    assert_eq!(
+        take_log(),
+        [
+            (lo.addr(), Access::Write(0x0000_0002)),
+            (hi.addr(), Access::Write(0x0000_0001)),
+        ]
+    );
+
+    ral::write_reg64!(
+        periph,
+        &rb,
+        MY_ARRAY[0],
+        MY_ARRAY[1],
+        0x8765_4321_DEAD_BEEF,
+        order = ral::WordOrder::LoFirst
+    );
+    assert_eq!(
+        take_log(),
+        [
+            (lo.addr(), Access::Write(0xDEAD_BEEF)),
+            (hi.addr(), Access::Write(0x8765_4321)),
+        ]
+    );
+}
+
+#[test]
+fn write_reg64_hi_first() {
+    let rb = periph::RegisterBlock::default();
+    let (lo, hi) = (&rb.MY_ARRAY[1], &rb.MY_ARRAY[2]);
+
+    ral::write_reg64!(
+        periph,
+        &rb,
+        MY_ARRAY[1],
+        MY_ARRAY[2],
+        0x1_0000_0002,
+        order = ral::WordOrder::HiFirst
+    );
+    assert_eq!(
+        take_log(),
+        [
+            (hi.addr(), Access::Write(0x0000_0001)),
+            (lo.addr(), Access::Write(0x0000_0002)),
+        ]
+    );
+    assert_eq!(rb.MY_ARRAY[0].accesses(), (0, 0));
+}
+
+#[test]
+fn read_reg64() {
+    let rb = periph::RegisterBlock::default();
+    let (lo, hi) = (&rb.MY_ARRAY[0], &rb.MY_ARRAY[1]);
+    lo.set(0xDEAD_BEEF);
+    hi.set(0x8765_4321);
+
+    assert_eq!(
+        ral::read_reg64!(periph, &rb, MY_ARRAY[0], MY_ARRAY[1]),
+        0x8765_4321_DEAD_BEEF
+    );
+    assert_eq!(
+        take_log(),
+        [
+            (lo.addr(), Access::Read(0xDEAD_BEEF)),
+            (hi.addr(), Access::Read(0x8765_4321)),
+        ]
+    );
+
+    assert_eq!(
+        ral::read_reg64!(
+            periph,
+            &rb,
+            MY_ARRAY[0],
+            MY_ARRAY[1],
+            order = ral::WordOrder::HiFirst
+        ),
+        0x8765_4321_DEAD_BEEF
+    );
+    assert_eq!(
+        take_log(),
+        [
+            (hi.addr(), Access::Read(0x8765_4321)),
+            (lo.addr(), Access::Read(0xDEAD_BEEF)),
+        ]
+    );
+}
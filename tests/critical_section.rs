@@ -0,0 +1,84 @@
+//! Tests for `modify_reg_cs!` and `read_pair_cs!`, using the `critical-section` std implementation.
+
+#![cfg(feature = "critical-section")]
+#![allow(non_upper_case_globals, non_snake_case)] // Macro conventions.
+
+use ral_registers as ral;
+
+mod periph {
+    #[repr(C)]
+    pub struct RegisterBlock {
+        pub MY_SCALAR: ral_registers::RWRegister<u32>,
+        pub MY_ARRAY: [ral_registers::RWRegister<u32>; 3],
+    }
+
+    pub mod MY_SCALAR {
+        pub mod FIELD_A {
+            pub const offset: u32 = 0;
+            pub const mask: u32 = 0x7F << offset;
+            pub mod R {}
+            pub mod W {}
+            pub mod RW {}
+        }
+        pub mod FIELD_B {
+            pub const offset: u32 = 27;
+            pub const mask: u32 = 0b11 << offset;
+            pub mod R {}
+            pub mod W {}
+            pub mod RW {
+                pub const Enabled: u32 = 0b10;
+            }
+        }
+    }
+
+    pub use MY_SCALAR as MY_ARRAY;
+}
+
+fn register_block() -> periph::RegisterBlock {
+    periph::RegisterBlock {
+        MY_SCALAR: ral::RWRegister::new(0),
+        MY_ARRAY: [
+            ral::RWRegister::new(0),
+            ral::RWRegister::new(0),
+            ral::RWRegister::new(0),
+        ],
+    }
+}
+
+#[test]
+fn closure() {
+    let inst = register_block();
+    ral::write_reg!(periph, &inst, MY_SCALAR, 0x10);
+    let value = ral::modify_reg_cs!(periph, &inst, MY_SCALAR, |reg| {
+        assert_eq!(reg, 0x10);
+        reg | 0x3
+    });
+    assert_eq!(value, 0x13);
+    assert_eq!(ral::read_reg!(periph, &inst, MY_SCALAR), 0x13);
+}
+
+#[test]
+fn fields() {
+    let inst = register_block();
+    ral::write_reg!(periph, &inst, MY_ARRAY[1], u32::MAX);
+    let value = ral::modify_reg_cs!(periph, &inst, MY_ARRAY[1], FIELD_A: 0, FIELD_B: Enabled);
+    assert_eq!(value, !0x7F & !(0b01 << 27));
+    assert_eq!(ral::read_reg!(periph, &inst, MY_ARRAY[1]), value);
+}
+
+#[test]
+fn nested() {
+    let inst = register_block();
+    let value =
+        critical_section::with(|_| ral::modify_reg_cs!(periph, &inst, MY_SCALAR, FIELD_A: 0x55));
+    assert_eq!(value, 0x55);
+}
+
+#[test]
+fn read_pair() {
+    let inst = register_block();
+    ral::write_reg!(periph, &inst, MY_ARRAY[0], 7);
+    ral::write_reg!(periph, &inst, MY_ARRAY[1], 0xFFFF_FFFF);
+    let value = ral::read_pair_cs!(periph, &inst, MY_ARRAY[0], MY_ARRAY[1]);
+    assert_eq!(value, 7 << 32 | 0xFFFF_FFFF);
+}
@@ -0,0 +1,90 @@
+//! Tests that fields written as `FieldMarker` types, rather than as
+//! modules, work with the macros that do not import named values.
+
+#![allow(non_upper_case_globals, non_snake_case, non_camel_case_types)] // Macro conventions.
+
+use ral_registers as ral;
+
+mod periph {
+    #[repr(C)]
+    pub struct RegisterBlock {
+        pub MY_ARRAY: [ral_registers::RWRegister<u32>; 3],
+    }
+
+    pub mod MY_ARRAY {
+        /// A field written as a module.
+        pub mod FIELD_A {
+            pub const offset: u32 = 0;
+            pub const mask: u32 = 0x7F << offset;
+            pub mod R {}
+            pub mod W {}
+            pub mod RW {}
+        }
+
+        /// A field written as a type.
+        pub enum FIELD_B {}
+
+        impl ral_registers::FieldMarker for FIELD_B {
+            type Value = u32;
+            const MASK: u32 = 0b11 << Self::OFFSET;
+            const OFFSET: u32 = 27;
+        }
+
+        ral_registers::impl_register_fields!(MY_ARRAY, [FIELD_A, FIELD_B]);
+    }
+
+    ral_registers::assert_no_overlap!(self, MY_ARRAY, [FIELD_A, FIELD_B]);
+}
+
+fn register_block() -> periph::RegisterBlock {
+    periph::RegisterBlock {
+        MY_ARRAY: [
+            ral::RWRegister::new(0),
+            ral::RWRegister::new(0),
+            ral::RWRegister::new(0),
+        ],
+    }
+}
+
+#[test]
+fn read() {
+    let rb = register_block();
+    rb.MY_ARRAY[1].write(0b10 << 27 | 0x55);
+    assert_eq!(ral::read_reg!(periph, &rb, MY_ARRAY[1], FIELD_B), 0b10);
+    assert_eq!(
+        ral::read_reg!(periph, &rb, MY_ARRAY[1], FIELD_A, FIELD_B),
+        (0x55, 0b10)
+    );
+    assert_eq!(
+        ral::extract_field!(periph, MY_ARRAY, FIELD_B, 0b01 << 27),
+        0b01
+    );
+}
+
+#[test]
+fn modify() {
+    let rb = register_block();
+    ral::bit_set!(periph, &rb, MY_ARRAY[1], FIELD_B);
+    assert_eq!(rb.MY_ARRAY[1].read(), 0b11 << 27);
+    ral::toggle_reg!(periph, &rb, MY_ARRAY[1], FIELD_A, FIELD_B);
+    assert_eq!(rb.MY_ARRAY[1].read(), 0x7F);
+
+    // Module fields still take values alongside type fields.
+    ral::modify_reg!(periph, &rb, MY_ARRAY[1], FIELD_A: 3);
+    ral::with_reg!(periph, &rb, MY_ARRAY[1], [FIELD_A, FIELD_B], |r| {
+        r.set(FIELD_B, r.get(FIELD_A) - 1);
+    });
+    assert_eq!(rb.MY_ARRAY[1].read(), 0b10 << 27 | 3);
+}
+
+#[test]
+fn register_fields() {
+    use ral::{FieldInfo, RegisterFields};
+    assert_eq!(
+        periph::MY_ARRAY::Fields::FIELDS,
+        [
+            FieldInfo::new("FIELD_A", 0, 0x7F),
+            FieldInfo::new("FIELD_B", 27, 0b11 << 27),
+        ]
+    );
+}
@@ -0,0 +1,97 @@
+//! Tests that fields given as bit ranges with `field_range!` work with
+//! the read, write, and modify macros.
+
+#![allow(non_upper_case_globals, non_snake_case)] // Macro conventions.
+
+use ral_registers as ral;
+
+mod periph {
+    #[repr(C)]
+    pub struct RegisterBlock {
+        pub MY_SCALAR: ral_registers::RWRegister<u32>,
+        pub MY_HALF: ral_registers::RWRegister<u16>,
+    }
+
+    pub mod MY_SCALAR {
+        pub mod FIELD_A {
+            pub const lsb: u32 = 0;
+            pub const msb: u32 = 6;
+            ral_registers::field_range!(u32);
+            pub mod R {}
+            pub mod W {}
+            pub mod RW {}
+        }
+        pub mod FIELD_B {
+            pub const lsb: u32 = 27;
+            pub const msb: u32 = 28;
+            ral_registers::field_range!(u32);
+            pub mod R {}
+            pub mod W {}
+            pub mod RW {
+                pub const Fast: u32 = 0b10;
+            }
+        }
+        pub mod FIELD_ALL {
+            pub const lsb: u32 = 0;
+            pub const msb: u32 = 31;
+            ral_registers::field_range!(u32);
+            pub mod R {}
+            pub mod W {}
+            pub mod RW {}
+        }
+    }
+
+    pub mod MY_HALF {
+        pub mod FIELD_TOP {
+            pub const lsb: u32 = 12;
+            pub const msb: u32 = 15;
+            ral_registers::field_range!(u16);
+            pub mod R {}
+            pub mod W {}
+            pub mod RW {}
+        }
+    }
+}
+
+#[test]
+fn consts() {
+    use periph::{MY_HALF, MY_SCALAR};
+    assert_eq!(
+        (MY_SCALAR::FIELD_A::offset, MY_SCALAR::FIELD_A::mask),
+        (0, 0x7F)
+    );
+    assert_eq!(
+        (MY_SCALAR::FIELD_B::offset, MY_SCALAR::FIELD_B::mask),
+        (27, 0b11 << 27)
+    );
+    assert_eq!(MY_SCALAR::FIELD_ALL::mask, u32::MAX);
+    let mask: u16 = MY_HALF::FIELD_TOP::mask;
+    assert_eq!(mask, 0xF000);
+}
+
+#[test]
+fn read_write_modify() {
+    let rb = periph::RegisterBlock {
+        MY_SCALAR: ral::RWRegister::new(0),
+        MY_HALF: ral::RWRegister::new(0x0123),
+    };
+
+    ral::write_reg!(periph, &rb, MY_SCALAR, FIELD_A: 0x55, FIELD_B: Fast);
+    assert_eq!(rb.MY_SCALAR.read(), 0b10 << 27 | 0x55);
+    assert_eq!(
+        ral::read_reg!(periph, &rb, MY_SCALAR, FIELD_A, FIELD_B),
+        (0x55, 0b10)
+    );
+    assert!(ral::read_reg!(periph, &rb, MY_SCALAR, FIELD_B == Fast));
+
+    ral::modify_reg!(periph, &rb, MY_SCALAR, FIELD_A: 1);
+    assert_eq!(rb.MY_SCALAR.read(), 0b10 << 27 | 1);
+    assert_eq!(
+        ral::read_reg!(periph, &rb, MY_SCALAR, FIELD_ALL),
+        0b10 << 27 | 1
+    );
+
+    ral::modify_reg!(periph, &rb, MY_HALF, FIELD_TOP: 0xA);
+    assert_eq!(rb.MY_HALF.read(), 0xA123);
+    assert_eq!(ral::read_reg!(periph, &rb, MY_HALF, FIELD_TOP), 0xA);
+}
@@ -0,0 +1,36 @@
+//! Tests that register accesses through an opaque reference are all
+//! performed, as in a driver's polling loop.
+//!
+//! `black_box` hides the register and the values from the optimizer, so
+//! this exercises the inlined `read` and `write` as a driver would use
+//! them. The generated code itself is not checked here: in a release
+//! build, `read_reg!` and `write_reg!` in a loop compile to bare loads and
+//! stores with no calls.
+
+#![allow(non_snake_case)] // Register conventions.
+
+use core::hint::black_box;
+use ral_registers::{RORegister, RWRegister, WORegister};
+
+#[test]
+fn read_write_loop() {
+    let register = RWRegister::new(0u32);
+    let register = black_box(&register);
+    for i in 0..1000 {
+        register.write(black_box(i));
+        assert_eq!(register.read(), i);
+    }
+    assert_eq!(register.update(|v| v + 1), 1000);
+    assert_eq!(register.replace(black_box(7)), 1000);
+    assert_eq!(register.read(), 7);
+}
+
+#[test]
+fn read_only_and_write_only() {
+    let (ro, wo) = (RORegister::new(0x1234u16), WORegister::new(0u16));
+    let (ro, wo) = (black_box(&ro), black_box(&wo));
+    for _ in 0..1000 {
+        wo.write(black_box(ro.read()));
+    }
+    assert_eq!(unsafe { core::ptr::read_volatile(wo.as_ptr()) }, 0x1234);
+}
@@ -26,8 +26,12 @@ mod periph {
         pub mod FIELD_A {
             pub const offset: u32 = 0;
             pub const mask: u32 = 0x7F << offset;
-            pub mod R {}
-            pub mod W {}
+            pub mod R {
+                pub const Enabled: u32 = 1;
+            }
+            pub mod W {
+                pub const Enabled: u32 = 1;
+            }
             pub mod RW {}
         }
         pub mod FIELD_B {
@@ -35,7 +39,10 @@ mod periph {
             pub const mask: u32 = 0b11 << offset;
             pub mod R {}
             pub mod W {}
-            pub mod RW {}
+            pub mod RW {
+                pub const Slow: u32 = 0b01;
+                pub const Fast: u32 = 0b10;
+            }
         }
     }
 
@@ -47,10 +54,12 @@ mod periph {
         // For testing convenience, we're pretending that MY_ARRAY
         // has the same fields as MY_SCALAR.
         pub use super::MY_SCALAR::*;
+
+        ral_registers::impl_register_fields!(MY_ARRAY, [FIELD_A, FIELD_B]);
     }
 
-    /// Reset values are always expressed as a scalar, no matter
-    /// the register plurality.
+    /// A scalar reset value applies to every register of an array.
+    /// See `reset_values.rs` for per-element reset values.
     pub struct ResetValues {
         pub MY_SCALAR: u32,
         pub MY_ARRAY: u32,
@@ -83,7 +92,7 @@ impl<'a> Instance<'a> {
 impl std::ops::Deref for Instance<'_> {
     type Target = periph::RegisterBlock;
     fn deref(&self) -> &Self::Target {
-        &self.rb
+        self.rb
     }
 }
 
@@ -159,11 +168,106 @@ fn read_array_ptr() {
     }
 }
 
-#[should_panic]
+#[test]
+fn read_enumerated_values() {
+    let rb = register_block();
+    rb.MY_ARRAY[1].write(1);
+    assert!(ral::read_reg!(periph, &rb, MY_ARRAY[1], FIELD_A == Enabled));
+    assert!(!ral::read_reg!(
+        periph,
+        &rb,
+        MY_ARRAY[1],
+        FIELD_A != Enabled
+    ));
+    assert_eq!(ral::read_reg!(periph, &rb, MY_ARRAY[1], FIELD_A), 1);
+
+    rb.MY_ARRAY[1].write(2);
+    assert!(!ral::read_reg!(
+        periph,
+        &rb,
+        MY_ARRAY[1],
+        FIELD_A == Enabled
+    ));
+}
+
+#[test]
+fn compare_enumerated_values() {
+    let rb = register_block();
+    rb.MY_ARRAY[1].write(0b10 << 27);
+    assert!(ral::read_reg!(periph, &rb, MY_ARRAY[1], FIELD_B == Fast));
+    assert!(ral::read_reg!(periph, &rb, MY_ARRAY[1], FIELD_B != Slow));
+    assert!(ral::read_reg!(periph, &rb, MY_ARRAY[1], FIELD_B > Slow));
+    assert!(ral::read_reg!(periph, &rb, MY_ARRAY[1], FIELD_B >= Fast));
+    assert!(!ral::read_reg!(periph, &rb, MY_ARRAY[1], FIELD_B < Fast));
+    assert!(ral::read_reg!(
+        periph,
+        &rb,
+        MY_ARRAY[1],
+        FIELD_B <= periph::MY_ARRAY::FIELD_B::RW::Fast
+    ));
+    assert_ne!(
+        ral::read_reg!(periph, &rb, MY_ARRAY[1], FIELD_B),
+        periph::MY_ARRAY::FIELD_B::RW::Slow
+    );
+    assert!(ral::read_reg!(periph, &rb, MY_ARRAY[1], FIELD_A != Enabled));
+    assert!(ral::read_reg!(periph, &rb, MY_ARRAY[1], FIELD_A < Enabled));
+}
+
+#[test]
+fn read_any_all() {
+    let rb = register_block();
+    // Bits outside of the fields are ignored.
+    rb.MY_SCALAR.write(!(0x7F | 0b11 << 27));
+    assert!(!ral::read_reg!(
+        periph,
+        &rb,
+        MY_SCALAR,
+        any(FIELD_A, FIELD_B)
+    ));
+    assert!(!ral::read_reg!(
+        periph,
+        &rb,
+        MY_SCALAR,
+        all(FIELD_A, FIELD_B)
+    ));
+
+    rb.MY_SCALAR.write(0b01 << 27);
+    assert!(ral::read_reg!(
+        periph,
+        &rb,
+        MY_SCALAR,
+        any(FIELD_A, FIELD_B)
+    ));
+    assert!(!ral::read_reg!(
+        periph,
+        &rb,
+        MY_SCALAR,
+        all(FIELD_A, FIELD_B)
+    ));
+    assert!(ral::read_reg!(periph, &rb, MY_SCALAR, all(FIELD_B)));
+
+    rb.MY_ARRAY[2].write(0b10 << 27 | 0x40);
+    assert!(ral::read_reg!(
+        periph,
+        &rb,
+        MY_ARRAY[2],
+        any(FIELD_A, FIELD_B)
+    ));
+    assert!(ral::read_reg!(
+        periph,
+        &rb,
+        MY_ARRAY[2],
+        all(FIELD_A, FIELD_B,)
+    ));
+    assert!(!ral::read_reg!(periph, &rb, MY_ARRAY[1], any(FIELD_A)));
+}
+
+#[should_panic(expected = "MY_ARRAY index 42 out of bounds (len 3)")]
 #[test]
 fn read_array_out_of_bounds() {
     let rb = register_block();
-    ral::read_reg!(periph, &rb, MY_ARRAY[42]);
+    let index = 42;
+    let _ = ral::read_reg!(periph, &rb, MY_ARRAY[index]);
 }
 
 /// Base test cases for write_reg.
@@ -171,17 +275,28 @@ fn read_array_out_of_bounds() {
 /// See [read_reg_test_cases] for more information.
 macro_rules! write_reg_test_cases {
     ($instance:expr, $register:ident $([$offset:expr])*) => {
-        ral::write_reg!(periph, $instance, $register $([$offset])*, FIELD_A: u32::MAX);
+        // Release builds mask field values to the field; debug builds panic instead.
+        #[cfg(not(debug_assertions))]
+        {
+            ral::write_reg!(periph, $instance, $register $([$offset])*, FIELD_A: u32::MAX);
+            assert_eq!((*$instance).$register $([$offset])*.read(), 0x7F, "1:1 write:field (A), masked");
+            ral::write_reg!(periph, $instance, $register $([$offset])*, FIELD_B: u32::MAX);
+            assert_eq!((*$instance).$register $([$offset])*.read(), 0b11 << 27, "1:1 write:field (B), masked");
+            ral::write_reg!(periph, $instance, $register $([$offset])*, FIELD_A: u32::MAX, FIELD_B: u32::MAX);
+            assert_eq!((*$instance).$register $([$offset])*.read(), (0b11 << 27) | 0x7F, "1:N write:field, masked");
+        }
+
+        ral::write_reg!(periph, $instance, $register $([$offset])*, FIELD_A: 0x7F);
         assert_eq!((*$instance).$register $([$offset])*.read(), 0x7F, "1:1 write:field (A)");
-        ral::write_reg!(periph, $instance, $register $([$offset])*, FIELD_B: u32::MAX);
+        ral::write_reg!(periph, $instance, $register $([$offset])*, FIELD_B: 0b11);
         assert_eq!((*$instance).$register $([$offset])*.read(), 0b11 << 27, "1:1 write:field (B)");
 
         ral::write_reg!(
             periph,
             $instance,
             $register $([$offset])*,
-            FIELD_A: u32::MAX,
-            FIELD_B: u32::MAX
+            FIELD_A: 0x7F,
+            FIELD_B: 0b11
         );
         assert_eq!((*$instance).$register $([$offset])*.read(), (0b11 << 27) | 0x7F, "1:N write:field");
 
@@ -192,7 +307,7 @@ macro_rules! write_reg_test_cases {
         #[deny(warnings)]
         {
             let mask = 7;
-            let offset = 8;
+            let offset = 2;
             ral::write_reg!(periph, $instance, $register $([$offset])*, FIELD_A: mask, FIELD_B: offset);
         }
     };
@@ -204,6 +319,61 @@ fn write_scalar_ref() {
     write_reg_test_cases!(&rb, MY_SCALAR);
 }
 
+#[test]
+fn write_with_base() {
+    let rb = register_block();
+    ral::write_reg!(periph, &rb, MY_ARRAY[1], base = 0xDEAD_0000, FIELD_A: 3);
+    assert_eq!(rb.MY_ARRAY[1].read(), 0xDEAD_0003);
+
+    ral::write_reg!(periph, &rb, MY_ARRAY[1], base = u32::MAX, FIELD_A: 3, FIELD_B: Slow);
+    assert_eq!(rb.MY_ARRAY[1].read(), !(0b10 << 27 | 0x7C));
+
+    let base = 0x0ABC_0000;
+    ral::write_reg!(periph, &rb, MY_SCALAR, base = base | 0x7F, FIELD_A: 0);
+    assert_eq!(rb.MY_SCALAR.read(), 0x0ABC_0000);
+}
+
+#[test]
+fn write_saturating() {
+    let rb = register_block();
+    let value = ral::write_reg_saturating!(periph, &rb, MY_ARRAY[1], FIELD_A: 0xFF);
+    assert_eq!(value, 0x7F);
+    assert_eq!(rb.MY_ARRAY[1].read(), 0x7F);
+
+    ral::write_reg_saturating!(periph, &rb, MY_ARRAY[1], FIELD_A: 5, FIELD_B: u32::MAX);
+    assert_eq!(rb.MY_ARRAY[1].read(), 0b11 << 27 | 5);
+    ral::write_reg_saturating!(periph, &rb, MY_SCALAR, FIELD_B: Slow);
+    assert_eq!(rb.MY_SCALAR.read(), 0b01 << 27);
+}
+
+#[test]
+fn write_checked() {
+    let rb = register_block();
+    rb.MY_ARRAY[1].write(0x1234);
+    let result = ral::write_reg_checked!(periph, &rb, MY_ARRAY[1], FIELD_A: 0xFF);
+    assert_eq!(
+        result,
+        Err(ral::FieldOverflow {
+            register: "MY_ARRAY",
+            field: "FIELD_A",
+            width: 7
+        })
+    );
+    assert_eq!(rb.MY_ARRAY[1].read(), 0x1234);
+    assert_eq!(
+        result.unwrap_err().to_string(),
+        "value does not fit field MY_ARRAY::FIELD_A (7 bits)"
+    );
+
+    let result = ral::write_reg_checked!(periph, &rb, MY_ARRAY[1], FIELD_A: 0x7F, FIELD_B: 4);
+    assert_eq!(result.unwrap_err().field, "FIELD_B");
+    assert_eq!(rb.MY_ARRAY[1].read(), 0x1234);
+
+    let result = ral::write_reg_checked!(periph, &rb, MY_ARRAY[1], FIELD_A: 0x7F, FIELD_B: Fast);
+    assert_eq!(result, Ok(0b10 << 27 | 0x7F));
+    assert_eq!(rb.MY_ARRAY[1].read(), 0b10 << 27 | 0x7F);
+}
+
 #[test]
 fn write_scalar_deref() {
     let rb = register_block();
@@ -240,11 +410,12 @@ fn write_array_ptr() {
     }
 }
 
-#[should_panic]
+#[should_panic(expected = "MY_ARRAY index 42 out of bounds (len 3)")]
 #[test]
 fn write_array_out_of_bounds() {
     let rb = register_block();
-    ral::write_reg!(periph, &rb, MY_ARRAY[42], 7);
+    let index = 42;
+    ral::write_reg!(periph, &rb, MY_ARRAY[index], 7);
 }
 
 /// Base test cases for modify_reg.
@@ -252,9 +423,19 @@ fn write_array_out_of_bounds() {
 /// See [read_reg_test_cases] for more information.
 macro_rules! modify_reg_test_cases {
     ($instance:expr, $register:ident $([$offset:expr])*) => {
-        ral::modify_reg!(periph, $instance, $register $([$offset])*, FIELD_A: u32::MAX);
+        // Release builds mask field values to the field; debug builds panic instead.
+        #[cfg(not(debug_assertions))]
+        {
+            ral::modify_reg!(periph, $instance, $register $([$offset])*, FIELD_A: u32::MAX);
+            assert_eq!((*$instance).$register $([$offset])*.read(), 0x7F, "RMW individual fields (A), masked");
+            ral::modify_reg!(periph, $instance, $register $([$offset])*, FIELD_B: u32::MAX);
+            assert_eq!((*$instance).$register $([$offset])*.read(), 0x7F | (0b11 << 27), "RMW individual fields (B), masked");
+            (*$instance).$register $([$offset])*.write(0);
+        }
+
+        ral::modify_reg!(periph, $instance, $register $([$offset])*, FIELD_A: 0x7F);
         assert_eq!((*$instance).$register $([$offset])*.read(), 0x7F, "RMW individual fields (A)");
-        ral::modify_reg!(periph, $instance, $register $([$offset])*, FIELD_B: u32::MAX);
+        ral::modify_reg!(periph, $instance, $register $([$offset])*, FIELD_B: 0b11);
         assert_eq!((*$instance).$register $([$offset])*.read(), 0x7F | (0b11 << 27), "RMW individual fields (B)");
 
         ral::modify_reg!(periph, $instance, $register $([$offset])*, FIELD_A: 2, FIELD_B: 2);
@@ -266,11 +447,19 @@ macro_rules! modify_reg_test_cases {
         });
         assert_eq!((*$instance).$register $([$offset])*.read(), u32::MAX, "RMW whole register");
 
+        let value = ral::modify_reg!(periph, $instance, $register $([$offset])*, FIELD_A: 3);
+        assert_eq!(value, u32::MAX & !0x7F | 3, "RMW returns written value (fields)");
+        assert_eq!((*$instance).$register $([$offset])*.read(), value, "RMW returns written value (fields)");
+
+        let value = ral::modify_reg!(periph, $instance, $register $([$offset])*, |reg| reg & !(0b11 << 27));
+        assert_eq!(value, u32::MAX & !0x7F & !(0b11 << 27) | 3, "RMW returns written value (closure)");
+        assert_eq!((*$instance).$register $([$offset])*.read(), value, "RMW returns written value (closure)");
+
         // Make sure that local variables mask, offset don't shadow macro details.
         #[deny(warnings)]
         {
             let mask = 7;
-            let offset = 8;
+            let offset = 2;
             ral::modify_reg!(periph, $instance, $register $([$offset])*, FIELD_A: mask, FIELD_B: offset);
         }
     };
@@ -318,11 +507,118 @@ fn modify_array_ptr() {
     }
 }
 
-#[should_panic]
+#[test]
+fn write_enumerated_values() {
+    let rb = register_block();
+    ral::write_reg!(periph, &rb, MY_ARRAY[1], FIELD_A: Enabled);
+    assert_eq!(rb.MY_ARRAY[1].read(), 1);
+    ral::write_reg!(periph, &rb, MY_SCALAR, FIELD_A: Enabled, FIELD_B: 2);
+    assert_eq!(rb.MY_SCALAR.read(), 2 << 27 | 1);
+}
+
+#[test]
+fn modify_enumerated_values() {
+    let rb = register_block();
+    rb.MY_ARRAY[1].write(u32::MAX);
+    ral::modify_reg!(periph, &rb, MY_ARRAY[1], FIELD_A: Enabled);
+    assert_eq!(rb.MY_ARRAY[1].read(), !0x7F | 1);
+}
+
+#[test]
+fn read_then_write() {
+    let rb = register_block();
+    rb.MY_SCALAR.write(0b01 << 27 | 0x12);
+    let old = ral::read_then_write_reg!(periph, &rb, MY_SCALAR, 0x34);
+    assert_eq!(old, 0b01 << 27 | 0x12);
+    assert_eq!(rb.MY_SCALAR.read(), 0x34);
+
+    rb.MY_ARRAY[1].write(0b10 << 27 | 0x56);
+    let (a, b) =
+        ral::read_then_write_reg!(periph, &rb, MY_ARRAY[1], [FIELD_A, FIELD_B], FIELD_A: Enabled);
+    assert_eq!((a, b), (0x56, 0b10));
+    assert_eq!(rb.MY_ARRAY[1].read(), 1);
+
+    let b =
+        ral::read_then_write_reg!(periph, &rb, MY_ARRAY[1], [FIELD_B], base = 0x7F, FIELD_B: Fast);
+    assert_eq!(b, 0);
+    assert_eq!(rb.MY_ARRAY[1].read(), 0b10 << 27 | 0x7F);
+    assert_eq!(rb.MY_ARRAY[0].read(), 0);
+}
+
+#[test]
+fn modify_clear_then_set() {
+    let rb = register_block();
+    rb.MY_SCALAR.write(u32::MAX);
+    let value = ral::modify_reg!(periph, &rb, MY_SCALAR, clear: FIELD_A, set: FIELD_B: Slow);
+    assert_eq!(value, !(0x7F | 0b11 << 27) | 0b01 << 27);
+    assert_eq!(rb.MY_SCALAR.read(), value);
+
+    rb.MY_ARRAY[1].write(u32::MAX);
+    ral::modify_reg!(periph, &rb, MY_ARRAY[1], clear: FIELD_A | FIELD_B, set: FIELD_A: Enabled);
+    assert_eq!(rb.MY_ARRAY[1].read(), !(0x7F | 0b11 << 27) | 1);
+    assert_eq!(rb.MY_ARRAY[0].read(), 0);
+}
+
+#[test]
+fn modify_returning() {
+    let rb = register_block();
+    rb.MY_SCALAR.write(0x10);
+    let was_set: u32 = ral::modify_reg!(periph, &rb, MY_SCALAR, |v| (v | 1, v & 1));
+    assert_eq!(was_set, 0);
+    assert_eq!(rb.MY_SCALAR.read(), 0x11);
+
+    let was_set = ral::modify_reg!(periph, &rb, MY_SCALAR, |v| (v | 1, v & 1));
+    assert_eq!(was_set, 1);
+    assert_eq!(rb.MY_SCALAR.read(), 0x11);
+
+    rb.MY_ARRAY[2].write(0b11 << 27 | 5);
+    let (old, field) =
+        ral::modify_reg!(periph, &rb, MY_ARRAY[2], |v: u32| (v & !0x7F, (v, v >> 27)));
+    assert_eq!((old, field), (0b11 << 27 | 5, 0b11));
+    assert_eq!(rb.MY_ARRAY[2].read(), 0b11 << 27);
+    assert_eq!(rb.MY_ARRAY[1].read(), 0);
+}
+
+#[test]
+fn modify_compound_ops() {
+    let rb = register_block();
+    rb.MY_SCALAR.write(0x8000_0000 | 0b01 << 27 | 0x0F00 | 0x41);
+    let value = ral::modify_reg!(periph, &rb, MY_SCALAR, FIELD_A |= 0x06, FIELD_B |= Fast);
+    assert_eq!(value, 0x8000_0000 | 0b11 << 27 | 0x0F00 | 0x47);
+    assert_eq!(rb.MY_SCALAR.read(), value);
+
+    let value = ral::modify_reg!(periph, &rb, MY_SCALAR, FIELD_A &= 0x0F, FIELD_B &= Slow);
+    assert_eq!(value, 0x8000_0000 | 0b01 << 27 | 0x0F00 | 0x07);
+
+    let value = ral::modify_reg!(periph, &rb, MY_SCALAR, FIELD_A ^= 0x7F);
+    assert_eq!(value, 0x8000_0000 | 0b01 << 27 | 0x0F00 | 0x78);
+
+    let mask = 0x18;
+    let value = ral::modify_reg!(periph, &rb, MY_SCALAR, FIELD_A &= mask, FIELD_B ^= 0b11);
+    assert_eq!(value, 0x8000_0000 | 0b10 << 27 | 0x0F00 | 0x18);
+    assert_eq!(rb.MY_SCALAR.read(), value);
+
+    rb.MY_ARRAY[2].write(!0x7F);
+    ral::modify_reg!(periph, &rb, MY_ARRAY[2], FIELD_A |= Enabled);
+    assert_eq!(rb.MY_ARRAY[2].read(), !0x7F | 1);
+    assert_eq!(rb.MY_ARRAY[1].read(), 0);
+}
+
+#[cfg(debug_assertions)]
+#[should_panic(expected = "value 0x80 does not fit field MY_SCALAR::FIELD_A (7 bits)")]
+#[test]
+fn modify_compound_value_does_not_fit() {
+    let rb = register_block();
+    let value = 0x80;
+    ral::modify_reg!(periph, &rb, MY_SCALAR, FIELD_A |= value);
+}
+
+#[should_panic(expected = "MY_ARRAY index 42 out of bounds (len 3)")]
 #[test]
 fn modify_array_out_of_bounds() {
     let rb = register_block();
-    ral::modify_reg!(periph, &rb, MY_ARRAY[42], |_| 7);
+    let index = 42;
+    ral::modify_reg!(periph, &rb, MY_ARRAY[index], |_| 7);
 }
 
 /// Base test cases for reset_reg.
@@ -331,8 +627,9 @@ fn modify_array_out_of_bounds() {
 macro_rules! reset_reg_test_cases {
     ($instance:expr, $register:ident $([$offset:expr])*) => {
         (*$instance).$register $([$offset])*.write(u32::MAX);
-        ral::reset_reg!(periph, $instance, INST, $register $([$offset])*);
+        let value = ral::reset_reg!(periph, $instance, INST, $register $([$offset])*);
         assert_eq!((*$instance).$register $([$offset])*.read(), 42, "Entire register");
+        assert_eq!(value, 42, "Reset returns written value (register)");
 
         (*$instance).$register $([$offset])*.write(u32::MAX);
         ral::reset_reg!(periph, $instance, INST, $register $([$offset])*, FIELD_B);
@@ -345,15 +642,36 @@ macro_rules! reset_reg_test_cases {
         );
 
         (*$instance).$register $([$offset])*.write(u32::MAX);
-        ral::reset_reg!(periph, $instance, INST, $register $([$offset])*, FIELD_B, FIELD_A);
+        let value = ral::reset_reg!(periph, $instance, INST, $register $([$offset])*, FIELD_B, FIELD_A);
         assert_eq!(
             (*$instance).$register $([$offset])*.read(),
             u32::MAX & !(0b11 << 27) & !0x7F | 42,
             "Fields in register"
         );
+        assert_eq!(value, u32::MAX & !(0b11 << 27) & !0x7F | 42, "Reset returns written value (fields)");
     };
 }
 
+/// Resetting fields keeps every bit outside the fields, and takes every
+/// bit inside them from the reset value, whatever the register held.
+#[test]
+fn reset_fields_math() {
+    let rb = register_block();
+    let reset = periph::INST::reset.MY_SCALAR;
+    let masks = 0x7F | 0b11 << 27;
+    for current in [0, u32::MAX, 0xA5A5_A5A5, 0x5A5A_5A5A, !reset, reset] {
+        rb.MY_SCALAR.write(current);
+        let value = ral::reset_reg!(periph, &rb, INST, MY_SCALAR, FIELD_A, FIELD_B);
+        assert_eq!(value, (current & !masks) | (reset & masks));
+        assert_eq!(rb.MY_SCALAR.read(), value);
+
+        rb.MY_ARRAY[1].write(current);
+        ral::reset_reg!(periph, &rb, INST, MY_ARRAY[1], FIELD_B);
+        ral::reset_reg!(periph, &rb, INST, MY_ARRAY[1], FIELD_A);
+        assert_eq!(rb.MY_ARRAY[1].read(), value);
+    }
+}
+
 #[test]
 fn reset_scalar_ref() {
     let rb = register_block();
@@ -381,6 +699,32 @@ fn reset_array_ref() {
     reset_reg_test_cases!(&rb, MY_ARRAY[1]);
 }
 
+#[test]
+fn reset_whole_array() {
+    let rb = register_block();
+    for reg in rb.MY_ARRAY.iter() {
+        reg.write(u32::MAX);
+    }
+    rb.MY_SCALAR.write(u32::MAX);
+
+    let value = ral::reset_reg!(periph, &rb, INST, MY_ARRAY[..]);
+    assert_eq!(value, 42);
+    assert!(rb.MY_ARRAY.iter().all(|reg| reg.read() == 42));
+    assert_eq!(rb.MY_SCALAR.read(), u32::MAX);
+
+    rb.MY_ARRAY[0].write(u32::MAX);
+    rb.MY_ARRAY[2].write(0);
+    ral::reset_reg!(periph, &rb, INST, MY_ARRAY[..], FIELD_A);
+    assert_eq!(rb.MY_ARRAY[0].read(), !0x7F | 42);
+    assert_eq!(rb.MY_ARRAY[1].read(), 42);
+    assert_eq!(rb.MY_ARRAY[2].read(), 42);
+
+    let ptr: *const _ = &rb;
+    rb.MY_ARRAY[1].write(0);
+    unsafe { ral::reset_reg!(periph, ptr, INST, MY_ARRAY[..], FIELD_A, FIELD_B) };
+    assert!(rb.MY_ARRAY.iter().all(|reg| reg.read() & 0x7F == 42));
+}
+
 #[test]
 fn reset_array_deref() {
     let rb = register_block();
@@ -396,9 +740,890 @@ fn reset_array_ptr() {
     }
 }
 
-#[should_panic]
+#[should_panic(expected = "MY_ARRAY index 42 out of bounds (len 3)")]
 #[test]
 fn reset_array_out_of_bounds() {
     let rb = register_block();
-    ral::reset_reg!(periph, &rb, INST, MY_ARRAY[42]);
+    let index = 42;
+    ral::reset_reg!(periph, &rb, INST, MY_ARRAY[index]);
+}
+
+#[test]
+fn const_index_without_register_block() {
+    /// A peripheral module whose register block is not named `RegisterBlock`.
+    mod other {
+        pub struct Block {
+            pub MY_ARRAY: [ral_registers::RWRegister<u32>; 2],
+        }
+        pub mod MY_ARRAY {}
+    }
+    let block = other::Block {
+        MY_ARRAY: [ral::RWRegister::new(0), ral::RWRegister::new(0)],
+    };
+    // A constant is not a literal, so the index is checked when the register is accessed.
+    const SECOND: usize = 1;
+    ral::write_reg!(other, &block, MY_ARRAY[SECOND], 7);
+    assert_eq!(ral::read_reg!(other, &block, MY_ARRAY[SECOND]), 7);
+    assert_eq!(
+        ral::modify_reg!(other, &block, MY_ARRAY[SECOND - 1], |reg| reg + 1),
+        1
+    );
+}
+
+#[test]
+fn try_read() {
+    let rb = register_block();
+    rb.MY_ARRAY[1].write(u32::MAX);
+    assert_eq!(ral::try_read_reg!(periph, &rb, MY_ARRAY[1]), Ok(u32::MAX));
+    assert_eq!(
+        ral::try_read_reg!(periph, &rb, MY_ARRAY[1], FIELD_A),
+        Ok(0x7F)
+    );
+    assert_eq!(
+        ral::try_read_reg!(periph, &rb, MY_ARRAY[1], FIELD_A, FIELD_B),
+        Ok((0x7F, 0b11))
+    );
+    assert_eq!(
+        ral::try_read_reg!(periph, &rb, MY_ARRAY[1], FIELD_B == 0b11),
+        Ok(true)
+    );
+    assert_eq!(ral::try_read_reg!(periph, &rb, MY_SCALAR), Ok(0));
+
+    let err = ral::IndexError { index: 42, len: 3 };
+    assert_eq!(ral::try_read_reg!(periph, &rb, MY_ARRAY[42]), Err(err));
+    assert_eq!(
+        ral::try_read_reg!(periph, &rb, MY_ARRAY[42], FIELD_A),
+        Err(err)
+    );
+    assert_eq!(
+        ral::try_read_reg!(periph, &rb, MY_ARRAY[42], FIELD_A == 0),
+        Err(err)
+    );
+}
+
+#[test]
+fn try_write() {
+    let rb = register_block();
+    assert_eq!(
+        ral::try_write_reg!(periph, &rb, MY_ARRAY[2], FIELD_A: 3),
+        Ok(())
+    );
+    assert_eq!(rb.MY_ARRAY[2].read(), 3);
+    assert_eq!(ral::try_write_reg!(periph, &rb, MY_SCALAR, 7), Ok(()));
+    assert_eq!(rb.MY_SCALAR.read(), 7);
+
+    let err = ral::IndexError { index: 42, len: 3 };
+    assert_eq!(
+        ral::try_write_reg!(periph, &rb, MY_ARRAY[42], FIELD_A: 3),
+        Err(err)
+    );
+    assert_eq!(ral::try_write_reg!(periph, &rb, MY_ARRAY[42], 7), Err(err));
+}
+
+#[test]
+fn try_modify() {
+    let rb = register_block();
+    rb.MY_ARRAY[0].write(1 << 8);
+    assert_eq!(
+        ral::try_modify_reg!(periph, &rb, MY_ARRAY[0], FIELD_A: 3),
+        Ok(1 << 8 | 3)
+    );
+    assert_eq!(rb.MY_ARRAY[0].read(), 1 << 8 | 3);
+
+    let err = ral::IndexError { index: 42, len: 3 };
+    assert_eq!(
+        ral::try_modify_reg!(periph, &rb, MY_ARRAY[42], |reg| reg),
+        Err(err)
+    );
+}
+
+#[test]
+fn try_ptr() {
+    let ptr: *const _ = &register_block();
+    unsafe {
+        assert_eq!(ral::try_write_reg!(periph, ptr, MY_ARRAY[1], 5), Ok(()));
+        assert_eq!(ral::try_read_reg!(periph, ptr, MY_ARRAY[1]), Ok(5));
+        assert!(ral::try_read_reg!(periph, ptr, MY_ARRAY[3]).is_err());
+    }
+}
+
+#[test]
+fn try_evaluates_index_once() {
+    let rb = register_block();
+    let mut indices = vec![1, 42, 2, 42, 0, 42].into_iter();
+    let err = ral::IndexError { index: 42, len: 3 };
+
+    rb.MY_ARRAY[1].write(5);
+    assert_eq!(
+        ral::try_read_reg!(periph, &rb, MY_ARRAY[indices.next().unwrap()]),
+        Ok(5)
+    );
+    assert_eq!(
+        ral::try_read_reg!(periph, &rb, MY_ARRAY[indices.next().unwrap()]),
+        Err(err)
+    );
+    assert_eq!(
+        ral::try_write_reg!(periph, &rb, MY_ARRAY[indices.next().unwrap()], 7),
+        Ok(())
+    );
+    assert_eq!(
+        ral::try_write_reg!(periph, &rb, MY_ARRAY[indices.next().unwrap()], 7),
+        Err(err)
+    );
+    assert_eq!(
+        ral::try_modify_reg!(periph, &rb, MY_ARRAY[indices.next().unwrap()], |reg| reg
+            | 1),
+        Ok(1)
+    );
+    assert_eq!(
+        ral::try_modify_reg!(periph, &rb, MY_ARRAY[indices.next().unwrap()], |reg| reg
+            | 1),
+        Err(err)
+    );
+    assert_eq!(indices.next(), None, "Each index is evaluated once");
+    assert_eq!(
+        [
+            rb.MY_ARRAY[0].read(),
+            rb.MY_ARRAY[1].read(),
+            rb.MY_ARRAY[2].read()
+        ],
+        [1, 5, 7]
+    );
+}
+
+#[test]
+fn try_evaluates_index_in_caller() {
+    fn write(rb: &periph::RegisterBlock, index: Option<usize>) -> Option<bool> {
+        Some(ral::try_write_reg!(periph, rb, MY_ARRAY[index?], FIELD_A: 3).is_ok())
+    }
+    let rb = register_block();
+    assert_eq!(write(&rb, None), None, "`?` returns from the caller");
+    assert_eq!(write(&rb, Some(2)), Some(true));
+    assert_eq!(write(&rb, Some(3)), Some(false));
+    assert_eq!(rb.MY_ARRAY[2].read(), 3);
+}
+
+#[test]
+fn get() {
+    let rb = register_block();
+    rb.MY_ARRAY[1].write(0b10 << 27 | 0x7F);
+    assert_eq!(
+        ral::get_reg!(periph, &rb, MY_ARRAY[1]),
+        Some(0b10 << 27 | 0x7F)
+    );
+    assert_eq!(
+        ral::get_field!(periph, &rb, MY_ARRAY[1], FIELD_A),
+        Some(0x7F)
+    );
+    assert_eq!(
+        ral::get_field!(periph, &rb, MY_ARRAY[1], FIELD_A, FIELD_B),
+        Some((0x7F, 0b10))
+    );
+    assert_eq!(
+        ral::get_field!(periph, &rb, MY_ARRAY[1], FIELD_B == Fast),
+        Some(true)
+    );
+    assert_eq!(ral::get_reg!(periph, &rb, MY_SCALAR), Some(0));
+
+    assert_eq!(ral::get_reg!(periph, &rb, MY_ARRAY[99]), None);
+    assert_eq!(ral::get_field!(periph, &rb, MY_ARRAY[99], FIELD_A), None);
+    let index = 3;
+    assert_eq!(
+        ral::get_field!(periph, &rb, MY_ARRAY[index], FIELD_A == 0),
+        None
+    );
+
+    let ptr: *const _ = &rb;
+    unsafe {
+        assert_eq!(ral::get_reg!(periph, ptr, MY_ARRAY[1], FIELD_B), Some(0b10));
+        assert_eq!(ral::get_reg!(periph, ptr, MY_ARRAY[3]), None);
+    }
+}
+
+#[test]
+fn get_is_slice_get() {
+    let rb = register_block();
+    for (i, register) in rb.MY_ARRAY.iter().enumerate() {
+        register.write(0x10 + i as u32);
+    }
+    // Out of bounds indices are `None` because `slice::get` is, without unwinding.
+    for i in 0..8 {
+        assert_eq!(
+            ral::get_reg!(periph, &rb, MY_ARRAY[i]),
+            rb.MY_ARRAY.get(i).map(|register| register.read())
+        );
+    }
+    assert_eq!(
+        ral::get_reg!(periph, &rb, MY_ARRAY[usize::MAX]),
+        rb.MY_ARRAY.get(usize::MAX).map(|register| register.read())
+    );
+}
+
+#[test]
+fn get_evaluates_index_in_caller() {
+    fn get(rb: &periph::RegisterBlock, index: Option<usize>) -> Option<Option<u32>> {
+        Some(ral::get_reg!(periph, rb, MY_ARRAY[index?]))
+    }
+    let rb = register_block();
+    rb.MY_ARRAY[1].write(7);
+    assert_eq!(get(&rb, None), None, "`?` returns from the caller");
+    assert_eq!(get(&rb, Some(1)), Some(Some(7)));
+    assert_eq!(get(&rb, Some(3)), Some(None));
+
+    let mut indices = vec![1, 3].into_iter();
+    assert_eq!(
+        ral::get_reg!(periph, &rb, MY_ARRAY[indices.next().unwrap()]),
+        Some(7)
+    );
+    assert_eq!(
+        ral::get_reg!(periph, &rb, MY_ARRAY[indices.next().unwrap()]),
+        None
+    );
+    assert_eq!(indices.next(), None, "Each index is evaluated once");
+}
+
+#[test]
+fn extract_field() {
+    let value = 0x7F | (0b10 << 27);
+    assert_eq!(ral::extract_field!(periph, MY_ARRAY, FIELD_A, value), 0x7F);
+    assert_eq!(ral::extract_field!(periph, MY_SCALAR, FIELD_B, value), 0b10);
+    assert_eq!(
+        ral::extract_field!(periph, MY_ARRAY, [FIELD_A, FIELD_B], value),
+        (0x7F, 0b10)
+    );
+
+    // Make sure that local variables mask, offset don't shadow macro details.
+    #[deny(warnings)]
+    {
+        let mask = 0x7F;
+        let offset = 0b01 << 27;
+        assert_eq!(ral::extract_field!(periph, MY_ARRAY, FIELD_A, mask), 0x7F);
+        assert_eq!(
+            ral::extract_field!(periph, MY_ARRAY, [FIELD_A, FIELD_B], offset),
+            (0, 0b01)
+        );
+    }
+}
+
+#[test]
+fn build_value() {
+    assert_eq!(ral::build_value!(periph, MY_ARRAY, FIELD_A: 3), 3);
+    assert_eq!(
+        ral::build_value!(periph, MY_ARRAY, FIELD_A: 3, FIELD_B: 1),
+        3 | (1 << 27)
+    );
+    assert_eq!(
+        ral::build_value!(periph, MY_SCALAR, FIELD_A: u32::MAX, FIELD_B: u32::MAX),
+        0x7F | (0b11 << 27)
+    );
+    assert_eq!(ral::build_value!(periph, MY_ARRAY, FIELD_A: Enabled), 1);
+
+    let rb = register_block();
+    ral::write_reg!(periph, &rb, MY_ARRAY[1], FIELD_A: 3, FIELD_B: 1);
+    assert_eq!(
+        rb.MY_ARRAY[1].read(),
+        ral::build_value!(periph, MY_ARRAY, FIELD_A: 3, FIELD_B: 1)
+    );
+}
+
+#[test]
+fn const_build_and_extract() {
+    const CW: u32 = ral::build_value!(periph, MY_ARRAY, FIELD_A: 3, FIELD_B: 1);
+    const TABLE: [u32; 3] = [
+        ral::build_value!(periph, MY_ARRAY, FIELD_A: 1),
+        ral::build_value!(periph, MY_ARRAY, FIELD_B: Fast),
+        ral::build_value!(periph, MY_SCALAR, FIELD_A: Enabled, FIELD_B: Slow),
+    ];
+    const A: u32 = ral::extract_field!(periph, MY_ARRAY, FIELD_A, CW);
+    const AB: (u32, u32) = ral::extract_field!(periph, MY_ARRAY, [FIELD_A, FIELD_B], CW);
+
+    assert_eq!(CW, 3 | 1 << 27);
+    assert_eq!(TABLE, [1, 0b10 << 27, 1 | 0b01 << 27]);
+    assert_eq!(A, 3);
+    assert_eq!(AB, (3, 1));
+}
+
+#[cfg(debug_assertions)]
+#[test]
+#[should_panic(expected = "value 0xffff does not fit field MY_ARRAY::FIELD_A (7 bits)")]
+fn write_field_too_wide() {
+    let rb = register_block();
+    ral::write_reg!(periph, &rb, MY_ARRAY[1], FIELD_A: 0xFFFF);
+}
+
+#[cfg(debug_assertions)]
+#[test]
+#[should_panic(expected = "value 0x4 does not fit field MY_SCALAR::FIELD_B (2 bits)")]
+fn modify_field_too_wide() {
+    let rb = register_block();
+    ral::modify_reg!(periph, &rb, MY_SCALAR, FIELD_A: 1, FIELD_B: 4);
+}
+
+#[test]
+fn reset_all() {
+    let rb = register_block();
+    rb.MY_SCALAR.write(1);
+    for (i, register) in rb.MY_ARRAY.iter().enumerate() {
+        register.write(i as u32);
+    }
+    ral::reset_all!(periph, &rb, INST, [MY_SCALAR, MY_ARRAY]);
+    assert_eq!(rb.MY_SCALAR.read(), 42);
+    assert!(rb.MY_ARRAY.iter().all(|register| register.read() == 42));
+
+    rb.MY_ARRAY[1].write(1);
+    rb.MY_ARRAY[2].write(2);
+    ral::reset_all!(periph, &rb, INST, [MY_ARRAY[2]]);
+    assert_eq!(rb.MY_ARRAY[1].read(), 1);
+    assert_eq!(rb.MY_ARRAY[2].read(), 42);
+}
+
+#[test]
+fn field_table() {
+    let rb = register_block();
+    rb.MY_ARRAY[1].write(0x2A | 0b10 << 27);
+    let table = ral::field_table!(periph, &rb, MY_ARRAY[1]);
+    rb.MY_ARRAY[1].write(0);
+    assert_eq!(table.indices(), [1]);
+    assert_eq!(table.value(), 0x1000002A);
+    assert_eq!(
+        table.to_string(),
+        "MY_ARRAY[1] = 0x1000002a\n  FIELD_A = 42 (0x2a)\n  FIELD_B = 2 (0x2)"
+    );
+}
+
+#[test]
+fn read_signed_field() {
+    mod cal {
+        pub struct RegisterBlock {
+            pub CAL: ral_registers::RWRegister<u32>,
+        }
+
+        pub mod CAL {
+            pub mod TRIM {
+                pub const offset: u32 = 4;
+                pub const mask: u32 = 0xF << offset;
+            }
+        }
+    }
+
+    let rb = cal::RegisterBlock {
+        CAL: ral::RWRegister::new(0b1111 << 4 | 0xF),
+    };
+    let trim: i32 = ral::read_signed_field!(cal, &rb, CAL, TRIM);
+    assert_eq!(trim, -1);
+
+    rb.CAL.write(0b1000 << 4);
+    assert_eq!(ral::read_signed_field!(cal, &rb, CAL, TRIM), -8);
+    rb.CAL.write(0b0111 << 4 | 0xF00);
+    assert_eq!(ral::read_signed_field!(cal, &rb, CAL, TRIM), 7);
+
+    let rb = register_block();
+    rb.MY_ARRAY[1].write(0b10 << 27 | 0x3F);
+    assert_eq!(
+        ral::read_signed_field!(periph, &rb, MY_ARRAY[1], FIELD_B),
+        -2
+    );
+    assert_eq!(
+        ral::read_signed_field!(periph, &rb, MY_ARRAY[1], FIELD_A),
+        63
+    );
+}
+
+#[test]
+fn reg_ref() {
+    let rb = register_block();
+    let index = 2;
+    let register = ral::reg_ref!(periph, &rb, MY_ARRAY[index]);
+    assert!(std::ptr::eq(register, &rb.MY_ARRAY[2]));
+    register.update(|value| value + 5);
+    assert_eq!(rb.MY_ARRAY[2].read(), 5);
+    assert_eq!(ral::read_reg_ref!(periph, MY_ARRAY, register), 5);
+
+    let inst = Instance::new(&rb);
+    assert!(std::ptr::eq(
+        ral::reg_ref!(periph, &inst, MY_SCALAR),
+        &rb.MY_SCALAR
+    ));
+}
+
+#[should_panic]
+#[test]
+fn reg_ref_out_of_bounds() {
+    let rb = register_block();
+    let index = 3;
+    let _ = ral::reg_ref!(periph, &rb, MY_ARRAY[index]);
+}
+
+#[test]
+fn read_enum() {
+    mod uart {
+        pub struct RegisterBlock {
+            pub SR: ral_registers::RORegister<u32>,
+        }
+
+        pub mod SR {
+            pub mod STATE {
+                pub const offset: u32 = 2;
+                pub const mask: u32 = 0b11 << offset;
+                pub mod R {
+                    use core::convert::TryFrom;
+
+                    #[derive(Debug, PartialEq, Eq)]
+                    pub enum State {
+                        Idle = 0,
+                        Busy = 1,
+                    }
+
+                    impl TryFrom<u32> for State {
+                        type Error = u32;
+                        fn try_from(bits: u32) -> Result<Self, u32> {
+                            match bits {
+                                0 => Ok(State::Idle),
+                                1 => Ok(State::Busy),
+                                _ => Err(bits),
+                            }
+                        }
+                    }
+
+                    pub fn from_bits(bits: u32) -> Result<State, u32> {
+                        State::try_from(bits)
+                    }
+                }
+            }
+        }
+    }
+    use uart::SR::STATE::R::State;
+
+    let rb = uart::RegisterBlock {
+        SR: ral::RORegister::new(0b01 << 2 | 0b11),
+    };
+    assert_eq!(ral::read_enum!(uart, &rb, SR, STATE), Ok(State::Busy));
+
+    let rb = uart::RegisterBlock {
+        SR: ral::RORegister::new(0b11),
+    };
+    assert_eq!(ral::read_enum!(uart, &rb, SR, STATE), Ok(State::Idle));
+
+    let rb = uart::RegisterBlock {
+        SR: ral::RORegister::new(0b10 << 2),
+    };
+    assert_eq!(ral::read_enum!(uart, &rb, SR, STATE), Err(0b10));
+}
+
+#[test]
+fn set_fields_from_table() {
+    let table: Vec<(ral::Field<u32>, u32)> = vec![
+        (ral::field!(periph, MY_ARRAY, FIELD_A), 0x55),
+        (ral::field!(periph, MY_ARRAY, FIELD_B), 0b01),
+        (ral::field!(periph, MY_ARRAY, FIELD_A), 0x2A),
+    ];
+    let mut value = ral::CachedRegister::new(1 << 8);
+    value.set_fields(table);
+    assert_eq!(value.value(), 0b01 << 27 | 1 << 8 | 0x2A);
+
+    let rb = register_block();
+    ral::write_reg!(periph, &rb, MY_ARRAY[1], value.value());
+    assert_eq!(
+        ral::read_reg!(periph, &rb, MY_ARRAY[1], FIELD_A, FIELD_B),
+        (0x2A, 0b01)
+    );
+}
+
+#[test]
+fn assert_reset() {
+    let rb = register_block();
+    ral::reset_all!(periph, &rb, INST, [MY_SCALAR, MY_ARRAY]);
+    ral::assert_reset!(periph, &rb, INST, [MY_SCALAR, MY_ARRAY[0], MY_ARRAY[2]]);
+}
+
+#[should_panic(expected = "MY_ARRAY[1] is 0x7, but its reset value is 0x2a")]
+#[test]
+fn assert_reset_differs() {
+    let rb = register_block();
+    ral::reset_all!(periph, &rb, INST, [MY_SCALAR, MY_ARRAY]);
+    rb.MY_ARRAY[1].write(7);
+    ral::assert_reset!(periph, &rb, INST, [MY_SCALAR, MY_ARRAY[0], MY_ARRAY[1]]);
+}
+
+#[test]
+fn barrier() {
+    let rb = register_block();
+    ral::write_reg_barrier!(periph, &rb, MY_ARRAY[1], FIELD_A: 3, FIELD_B: 1);
+    assert_eq!(rb.MY_ARRAY[1].read(), 1 << 27 | 3);
+    ral::write_reg_barrier!(periph, &rb, MY_SCALAR, 7);
+    assert_eq!(rb.MY_SCALAR.read(), 7);
+
+    let value = ral::modify_reg_barrier!(periph, &rb, MY_ARRAY[1], FIELD_A: 5);
+    assert_eq!(value, 1 << 27 | 5);
+    assert_eq!(rb.MY_ARRAY[1].read(), value);
+    assert_eq!(
+        ral::modify_reg_barrier!(periph, &rb, MY_SCALAR, |reg| reg + 1),
+        8
+    );
+}
+
+#[test]
+fn fenced() {
+    use std::sync::atomic::Ordering;
+
+    let rb = register_block();
+    ral::write_reg_fenced!(Ordering::Release, periph, &rb, MY_ARRAY[1], FIELD_A: 3, FIELD_B: 1);
+    assert_eq!(rb.MY_ARRAY[1].read(), 1 << 27 | 3);
+    ral::write_reg_fenced!(Ordering::SeqCst, periph, &rb, MY_SCALAR, 7);
+    assert_eq!(rb.MY_SCALAR.read(), 7);
+
+    assert_eq!(
+        ral::read_reg_fenced!(Ordering::Acquire, periph, &rb, MY_ARRAY[1], FIELD_A),
+        3
+    );
+    assert!(ral::read_reg_fenced!(
+        Ordering::AcqRel,
+        periph,
+        &rb,
+        MY_ARRAY[1],
+        FIELD_B == 1
+    ));
+    assert_eq!(
+        ral::read_reg_fenced!(Ordering::SeqCst, periph, &rb, MY_SCALAR),
+        7
+    );
+}
+
+#[test]
+fn reg_addr() {
+    let rb = register_block();
+    let base = &rb as *const periph::RegisterBlock as usize;
+    assert_eq!(ral::reg_addr!(periph, &rb, MY_SCALAR), base);
+    assert_eq!(ral::reg_addr!(periph, &rb, MY_ARRAY[0]), base + 4);
+    assert_eq!(
+        ral::reg_addr!(periph, &rb, MY_ARRAY[2]) - ral::reg_addr!(periph, &rb, MY_ARRAY[1]),
+        4
+    );
+
+    let ptr: *const periph::RegisterBlock = &rb;
+    assert_eq!(
+        unsafe { ral::reg_addr!(periph, ptr, MY_ARRAY[1]) },
+        base + 8
+    );
+}
+
+#[test]
+fn modify_fields_closure() {
+    let rb = register_block();
+    let untouched = 1 << 31 | 1 << 8;
+    rb.MY_ARRAY[1].write(untouched | 0b01 << 27 | 41);
+
+    let value = ral::modify_reg!(periph, &rb, MY_ARRAY[1], [FIELD_A, FIELD_B] => |a, b| {
+        assert_eq!((a, b), (41, 0b01));
+        (a + 1, 0b10)
+    });
+    assert_eq!(value, untouched | 0b10 << 27 | 42);
+    assert_eq!(rb.MY_ARRAY[1].read(), value);
+
+    let value = ral::modify_reg!(periph, &rb, MY_ARRAY[1], [FIELD_B] => |b| b >> 1);
+    assert_eq!(value, untouched | 0b01 << 27 | 42);
+
+    ral::modify_reg!(periph, &rb, MY_SCALAR, [FIELD_B, FIELD_A] => |b, a| (b, a));
+    assert_eq!(rb.MY_SCALAR.read(), 0);
+}
+
+#[test]
+#[deny(warnings)]
+fn modify_field_closures() {
+    let rb = register_block();
+    rb.MY_ARRAY[1].write(1 << 20 | 0b01 << 27 | 40);
+
+    ral::modify_reg!(periph, &rb, MY_ARRAY[1], FIELD_A: |old_a| old_a + 1);
+    let value = ral::modify_reg!(periph, &rb, MY_ARRAY[1], FIELD_A: |old_a| old_a + 1);
+    assert_eq!(value, 1 << 20 | 0b01 << 27 | 42);
+    assert_eq!(rb.MY_ARRAY[1].read(), value);
+
+    let value = ral::modify_reg!(
+        periph,
+        &rb,
+        MY_ARRAY[1],
+        FIELD_A: |a: u32| a.saturating_sub(2),
+        FIELD_B: |b| b << 1
+    );
+    assert_eq!(value, 1 << 20 | 0b10 << 27 | 40);
+
+    ral::modify_reg!(periph, &rb, MY_SCALAR, FIELD_B: |_| 0b11);
+    assert_eq!(rb.MY_SCALAR.read(), 0b11 << 27);
+}
+
+#[test]
+fn first_set_field_bit() {
+    let rb = register_block();
+    rb.MY_ARRAY[1].write(1 << 27 | 1 << 8);
+    assert_eq!(
+        ral::first_set_field_bit!(periph, &rb, MY_ARRAY[1], FIELD_A),
+        None
+    );
+    assert_eq!(
+        ral::first_set_field_bit!(periph, &rb, MY_ARRAY[1], FIELD_B),
+        Some(0)
+    );
+
+    rb.MY_ARRAY[1].write(1 << 5);
+    assert_eq!(
+        ral::first_set_field_bit!(periph, &rb, MY_ARRAY[1], FIELD_A),
+        Some(5)
+    );
+
+    rb.MY_SCALAR.write(0b1100_0000 | 0b10 << 27);
+    assert_eq!(
+        ral::first_set_field_bit!(periph, &rb, MY_SCALAR, FIELD_A),
+        Some(6)
+    );
+    assert_eq!(
+        ral::first_set_field_bit!(periph, &rb, MY_SCALAR, FIELD_B),
+        Some(1)
+    );
+}
+
+#[test]
+fn swap() {
+    let rb = register_block();
+    rb.MY_SCALAR.write(0x1111_1111);
+    rb.MY_ARRAY[1].write(0x2222_2222);
+
+    ral::swap_reg!(periph, &rb, MY_SCALAR, MY_ARRAY[1]);
+    assert_eq!(rb.MY_SCALAR.read(), 0x2222_2222);
+    assert_eq!(rb.MY_ARRAY[1].read(), 0x1111_1111);
+
+    ral::swap_reg!(periph, &rb, MY_ARRAY[1], MY_ARRAY[1]);
+    assert_eq!(rb.MY_ARRAY[1].read(), 0x1111_1111);
+}
+
+#[test]
+fn register_fields() {
+    use ral::{FieldInfo, RegisterFields};
+
+    fn describe<R: RegisterFields>(value: u64) -> Vec<String> {
+        R::FIELDS
+            .iter()
+            .map(|field| format!("{}.{} = {:#x}", R::NAME, field.name, field.extract(value)))
+            .collect()
+    }
+
+    assert_eq!(periph::MY_ARRAY::Fields::NAME, "MY_ARRAY");
+    assert_eq!(
+        periph::MY_ARRAY::Fields::FIELDS,
+        [
+            FieldInfo::new("FIELD_A", 0, 0x7F),
+            FieldInfo::new("FIELD_B", 27, 0b11 << 27),
+        ]
+    );
+
+    let rb = register_block();
+    rb.MY_ARRAY[1].write(0x2A | 0b10 << 27);
+    assert_eq!(
+        describe::<periph::MY_ARRAY::Fields>(ral::read_reg!(periph, &rb, MY_ARRAY[1]).into()),
+        ["MY_ARRAY.FIELD_A = 0x2a", "MY_ARRAY.FIELD_B = 0x2"]
+    );
+}
+
+#[test]
+fn dump() {
+    let rb = register_block();
+    rb.MY_ARRAY[1].write(u32::MAX);
+    let dump = ral::dump_reg!(periph, &rb, MY_ARRAY[1], FIELD_A, FIELD_B);
+    assert_eq!(dump.name(), "MY_ARRAY");
+    assert_eq!(dump.indices(), &[1]);
+    assert_eq!(dump.fields(), &[("FIELD_A", 0x7F), ("FIELD_B", 0b11)]);
+    assert_eq!(
+        format!("{}", dump),
+        "MY_ARRAY[1] { FIELD_A: 0x7f, FIELD_B: 0x3 }"
+    );
+    assert_eq!(
+        format!("{:?}", dump),
+        "MY_ARRAY[1] { FIELD_A: 0x7f, FIELD_B: 0x3 }"
+    );
+
+    let idx = 2;
+    rb.MY_ARRAY[idx].write(0x2A);
+    let dump = ral::dump_reg!(periph, &rb, MY_ARRAY[idx], FIELD_A);
+    assert_eq!(format!("{}", dump), "MY_ARRAY[2] { FIELD_A: 0x2a }");
+
+    rb.MY_SCALAR.write(0b10 << 27);
+    let dump = ral::dump_reg!(periph, &rb, MY_SCALAR, FIELD_B, FIELD_A);
+    assert_eq!(
+        format!("{}", dump),
+        "MY_SCALAR { FIELD_B: 0x2, FIELD_A: 0x0 }"
+    );
+}
+
+#[test]
+fn read_struct() {
+    let rb = register_block();
+    rb.MY_ARRAY[1].write(0x2A | (0b10 << 27));
+
+    let fields = ral::read_reg_struct!(periph, &rb, MY_ARRAY[1], { FIELD_A, FIELD_B });
+    assert_eq!(fields.FIELD_A, 0x2A);
+    assert_eq!(fields.FIELD_B, 0b10);
+    assert_eq!(
+        format!("{:?}", fields),
+        "Fields { FIELD_A: 42, FIELD_B: 2 }"
+    );
+
+    let copy = fields;
+    assert_eq!(copy, fields);
+
+    rb.MY_SCALAR.write(0x7F);
+    let fields = ral::read_reg_struct!(periph, &rb, MY_SCALAR, { FIELD_B, });
+    assert_eq!(fields.FIELD_B, 0);
+}
+
+#[test]
+fn iter_array() {
+    let rb = register_block();
+    rb.MY_ARRAY[0].write(1);
+    rb.MY_ARRAY[1].write(2 | (1 << 27));
+    rb.MY_ARRAY[2].write(3);
+
+    let sum: u32 = ral::iter_array!(periph, &rb, MY_ARRAY)
+        .map(|reg| ral::read_reg_ref!(periph, MY_ARRAY, reg, FIELD_A))
+        .sum();
+    assert_eq!(sum, 6);
+
+    assert_eq!(
+        ral::iter_array!(periph, &rb, MY_ARRAY)
+            .filter(|reg| ral::read_reg_ref!(periph, MY_ARRAY, reg, FIELD_B == 1))
+            .count(),
+        1
+    );
+
+    for (i, reg) in ral::iter_array!(periph, &rb, MY_ARRAY).enumerate() {
+        ral::write_reg_ref!(periph, MY_ARRAY, reg, FIELD_B: i as u32);
+    }
+    for reg in ral::iter_array!(periph, &rb, MY_ARRAY) {
+        ral::modify_reg_ref!(periph, MY_ARRAY, reg, FIELD_A: 0x7F);
+    }
+    assert_eq!(
+        [
+            rb.MY_ARRAY[0].read(),
+            rb.MY_ARRAY[1].read(),
+            rb.MY_ARRAY[2].read()
+        ],
+        [0x7F, 0x7F | (1 << 27), 0x7F | (2 << 27)]
+    );
+
+    let ptr: *const _ = &rb;
+    let count = unsafe { ral::iter_array!(periph, ptr, MY_ARRAY).count() };
+    assert_eq!(count, 3);
+
+    ral::write_reg_ref!(periph, MY_ARRAY, &rb.MY_ARRAY[1], 7);
+    assert_eq!(
+        ral::modify_reg_ref!(periph, MY_ARRAY, &rb.MY_ARRAY[1], |reg| reg + 1),
+        8
+    );
+}
+
+/// Index and value expressions may use any local name, including the names of bindings
+/// inside the macros.
+#[test]
+#[deny(warnings)]
+fn index_and_value_hygiene() {
+    #[derive(Clone, Copy)]
+    enum Channel {
+        Zero,
+        Two = 2,
+    }
+
+    let rb = register_block();
+    let reg = 1usize;
+    let val = 2usize;
+    let idx = 0usize;
+    let mask = 0x0Fu32;
+    let offset = 0b01u32;
+    let value = 5u32;
+    let result = 0x2Au32;
+    let reads = 3u32;
+    let max = 10u32;
+    let channel = || Channel::Two;
+    let first = || Channel::Zero;
+
+    ral::write_reg!(periph, &rb, MY_ARRAY[reg], FIELD_A: mask, FIELD_B: offset);
+    assert_eq!(rb.MY_ARRAY[1].read(), 0b01 << 27 | 0x0F);
+    assert_eq!(ral::read_reg!(periph, &rb, MY_ARRAY[reg], FIELD_A), mask);
+    assert!(ral::read_reg!(periph, &rb, MY_ARRAY[reg], FIELD_A == mask));
+    assert!(ral::read_reg!(
+        periph,
+        &rb,
+        MY_ARRAY[offset as usize],
+        FIELD_B == offset
+    ));
+    assert!(ral::read_reg!(
+        periph,
+        &rb,
+        MY_ARRAY[mask as usize - 14],
+        FIELD_A != offset
+    ));
+
+    ral::write_reg!(periph, &rb, MY_ARRAY[val], value);
+    ral::modify_reg!(periph, &rb, MY_ARRAY[val], FIELD_A: result, FIELD_B: offset);
+    assert_eq!(rb.MY_ARRAY[2].read(), 0b01 << 27 | 0x2A);
+    ral::modify_reg!(periph, &rb, MY_ARRAY[val], |reg| reg & !mask | value);
+    assert_eq!(rb.MY_ARRAY[2].read(), 0b01 << 27 | 0x25);
+    ral::modify_reg!(periph, &rb, MY_ARRAY[val], [FIELD_A] => |val| val + reads);
+    assert_eq!(ral::read_reg!(periph, &rb, MY_ARRAY[val], FIELD_A), 0x28);
+
+    ral::modify_reg!(periph, &rb, MY_ARRAY[channel() as usize], FIELD_A: reads);
+    assert_eq!(rb.MY_ARRAY[Channel::Two as usize].read() & 0x7F, 3);
+    ral::write_reg!(periph, &rb, MY_ARRAY[first() as usize], FIELD_B: offset);
+    assert_eq!(rb.MY_ARRAY[idx].read(), 0b01 << 27);
+
+    ral::reset_reg!(periph, &rb, INST, MY_ARRAY[idx], FIELD_A);
+    assert_eq!(rb.MY_ARRAY[idx].read(), 0b01 << 27 | 42);
+
+    assert_eq!(
+        ral::try_read_reg!(periph, &rb, MY_ARRAY[reg], FIELD_A),
+        Ok(mask)
+    );
+    assert!(ral::try_write_reg!(periph, &rb, MY_ARRAY[idx], FIELD_A: value).is_ok());
+    assert!(ral::try_modify_reg!(periph, &rb, MY_ARRAY[idx], FIELD_A: value).is_ok());
+    assert_eq!(ral::read_reg!(periph, &rb, MY_ARRAY[idx], FIELD_A), value);
+
+    assert_eq!(
+        ral::poll_reg!(
+            periph,
+            &rb,
+            MY_ARRAY[idx],
+            FIELD_A == value,
+            max_iters = max
+        ),
+        Ok(1)
+    );
+    ral::wait_reg!(
+        periph,
+        &rb,
+        MY_ARRAY[idx],
+        FIELD_A == value,
+        max_iters = max
+    )
+    .unwrap();
+    assert_eq!(ral::extract_field!(periph, MY_ARRAY, FIELD_A, value), value);
+    assert_eq!(ral::build_value!(periph, MY_ARRAY, FIELD_A: value), value);
+}
+
+#[test]
+fn reg64_byte_placement() {
+    let rb = register_block();
+    let value: u64 = 0x0123_4567_89AB_CDEF;
+    ral::write_reg64!(periph, &rb, MY_ARRAY[0], MY_ARRAY[1], value);
+
+    // The low half is at the lower address, in the target's byte order.
+    let bytes: [u8; 8] = unsafe { std::ptr::read_volatile(&rb.MY_ARRAY as *const _ as *const _) };
+    assert_eq!(bytes[..4], 0x89AB_CDEFu32.to_ne_bytes());
+    assert_eq!(bytes[4..], 0x0123_4567u32.to_ne_bytes());
+    assert_eq!(rb.MY_ARRAY[2].read(), 0);
+
+    assert_eq!(
+        ral::read_reg64!(periph, &rb, MY_ARRAY[0], MY_ARRAY[1]),
+        value
+    );
+    assert_eq!(
+        ral::read_reg64!(periph, &rb, MY_ARRAY[1], MY_ARRAY[0]),
+        0x89AB_CDEF_0123_4567
+    );
 }
@@ -0,0 +1,137 @@
+//! Tests for the mock registers, driving a small peripheral driver on the host.
+
+#![cfg(feature = "mock")]
+#![allow(non_upper_case_globals, non_snake_case)] // Macro conventions.
+
+use ral_registers as ral;
+use ral_registers::mock::{Access, MockRegister};
+use ral_registers::ReadRegister;
+
+mod periph {
+    use ral_registers::mock::MockRegister;
+
+    #[derive(Default)]
+    pub struct RegisterBlock {
+        pub CR1: MockRegister<u32>,
+        pub SR: MockRegister<u32>,
+    }
+
+    pub mod CR1 {
+        pub mod EN {
+            pub const offset: u32 = 0;
+            pub const mask: u32 = 0b11 << offset;
+            pub mod R {}
+            pub mod W {}
+            pub mod RW {
+                pub const Enabled: u32 = 0b11;
+            }
+        }
+    }
+
+    pub mod SR {
+        pub mod READY {
+            pub const offset: u32 = 4;
+            pub const mask: u32 = 1 << offset;
+            pub mod R {}
+            pub mod W {}
+            pub mod RW {}
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum State {
+    Off,
+    Starting,
+    Ready,
+}
+
+/// A driver which enables the peripheral, then polls until it is ready.
+struct Driver<'a> {
+    rb: &'a periph::RegisterBlock,
+    state: State,
+}
+
+impl Driver<'_> {
+    fn poll(&mut self) -> State {
+        self.state = match self.state {
+            State::Off => {
+                ral::write_reg!(periph, self.rb, CR1, EN: Enabled);
+                State::Starting
+            }
+            State::Starting if ral::read_reg!(periph, self.rb, SR, READY == 1) => State::Ready,
+            state => state,
+        };
+        self.state
+    }
+}
+
+#[test]
+fn driver() {
+    let rb = periph::RegisterBlock::default();
+    let mut reads = 0;
+    rb.SR.on_read(move |value| {
+        reads += 1;
+        if reads == 3 {
+            value | (1 << 4)
+        } else {
+            value
+        }
+    });
+
+    let mut driver = Driver {
+        rb: &rb,
+        state: State::Off,
+    };
+    assert_eq!(driver.poll(), State::Starting);
+    assert_eq!(driver.poll(), State::Starting);
+    assert_eq!(driver.poll(), State::Starting);
+    assert_eq!(driver.poll(), State::Ready);
+    assert_eq!(driver.poll(), State::Ready);
+
+    assert_eq!(rb.CR1.take_log(), [Access::Write(0x3)]);
+    assert_eq!(
+        rb.SR.take_log(),
+        [Access::Read(0), Access::Read(0), Access::Read(1 << 4)]
+    );
+}
+
+#[test]
+fn modify() {
+    let rb = periph::RegisterBlock::default();
+    rb.CR1.set(0xF0);
+    ral::modify_reg!(periph, &rb, CR1, EN: 0b01);
+    assert_eq!(rb.CR1.get(), 0xF1);
+    assert_eq!(rb.CR1.log(), [Access::Read(0xF0), Access::Write(0xF1)]);
+    assert_eq!(rb.CR1.take_log().len(), 2);
+    assert!(rb.CR1.log().is_empty());
+}
+
+#[test]
+fn into_inner() {
+    let register = MockRegister::new(7u32);
+    register.on_read(|value| value + 1);
+    register.write(8);
+    assert_eq!(register.into_inner(), 8);
+}
+
+fn read_generic<R: ReadRegister<u32>>(register: &R) -> u32 {
+    register.read()
+}
+
+#[test]
+fn traits() {
+    let register = MockRegister::new(7u32);
+    assert_eq!(read_generic(&register), 7);
+    assert_eq!(register.take_log(), [Access::Read(7)]);
+}
+
+#[test]
+fn debug() {
+    let register = MockRegister::new(7u32);
+    register.write(8);
+    assert_eq!(
+        format!("{:?}", register),
+        "MockRegister { value: 8, accesses: 1 }"
+    );
+}
@@ -0,0 +1,144 @@
+//! Tests that `get_reg!` and `get_field!` have no panic path in an optimized build.
+//!
+//! The test compiles the crate and a few functions using the macros with `rustc -O`, and
+//! checks the assembly of each function. As a control, a function using `read_reg!` with a
+//! runtime index must reference a panic, so the check would notice one.
+
+use std::path::Path;
+use std::process::Command;
+
+const USES: &str = r#"
+#![no_std]
+#![allow(non_upper_case_globals, non_snake_case)]
+
+pub mod periph {
+    pub struct RegisterBlock {
+        pub MY_ARRAY: [ral_registers::RWRegister<u32>; 3],
+        pub MY_MATRIX: [[ral_registers::RWRegister<u32>; 2]; 4],
+    }
+
+    pub mod MY_ARRAY {
+        pub mod FIELD_A {
+            pub const offset: u32 = 4;
+            pub const mask: u32 = 0x7 << offset;
+            pub mod R {}
+            pub mod W {}
+            pub mod RW {}
+        }
+    }
+
+    pub mod MY_MATRIX {}
+}
+
+#[no_mangle]
+pub fn get_element(rb: &periph::RegisterBlock, index: usize) -> Option<u32> {
+    ral_registers::get_reg!(periph, rb, MY_ARRAY[index])
+}
+
+#[no_mangle]
+pub fn get_element_field(rb: &periph::RegisterBlock, index: usize) -> Option<u32> {
+    ral_registers::get_field!(periph, rb, MY_ARRAY[index], FIELD_A)
+}
+
+#[no_mangle]
+pub fn get_matrix_element(rb: &periph::RegisterBlock, row: usize, column: usize) -> Option<u32> {
+    ral_registers::get_reg!(periph, rb, MY_MATRIX[row][column])
+}
+
+#[no_mangle]
+pub fn read_element(rb: &periph::RegisterBlock, index: usize) -> u32 {
+    ral_registers::read_reg!(periph, rb, MY_ARRAY[index])
+}
+"#;
+
+fn rustc(args: &[&str]) {
+    let rustc = std::env::var("RUSTC").unwrap_or_else(|_| "rustc".into());
+    let output = Command::new(rustc).args(args).output().unwrap();
+    assert!(
+        output.status.success(),
+        "rustc failed:\n{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
+
+/// Returns the assembly of each function in `USES`, keyed by name.
+fn assembly(dir: &Path) -> Vec<(String, String)> {
+    let out = dir.to_str().unwrap();
+    let lib = concat!(env!("CARGO_MANIFEST_DIR"), "/src/lib.rs");
+    rustc(&[
+        "--edition=2018",
+        "--crate-type=rlib",
+        "--crate-name=ral_registers",
+        "-O",
+        "--out-dir",
+        out,
+        lib,
+    ]);
+    let uses = dir.join("uses.rs");
+    std::fs::write(&uses, USES).unwrap();
+    let extern_arg = format!("ral_registers={}/libral_registers.rlib", out);
+    rustc(&[
+        "--edition=2018",
+        "--crate-type=lib",
+        "-O",
+        "-C",
+        "debuginfo=0",
+        "--emit=asm",
+        "--extern",
+        &extern_arg,
+        "--out-dir",
+        out,
+        uses.to_str().unwrap(),
+    ]);
+    let asm = std::fs::read_to_string(dir.join("uses.s")).unwrap();
+
+    // Each function runs from its label to the next `.size` directive, or the next function.
+    let mut functions = Vec::new();
+    let mut current: Option<(String, String)> = None;
+    for line in asm.lines() {
+        let label = line.strip_suffix(':').filter(|label| {
+            ["get_", "read_"]
+                .iter()
+                .any(|prefix| label.trim_start_matches('_').starts_with(prefix))
+        });
+        if let Some(label) = label {
+            functions.extend(current.take());
+            current = Some((label.trim_start_matches('_').to_string(), String::new()));
+        } else if line.trim_start().starts_with(".size") || line.trim_start() == ".cfi_endproc" {
+            functions.extend(current.take());
+        } else if let Some((_, body)) = current.as_mut() {
+            body.push_str(line);
+            body.push('\n');
+        }
+    }
+    functions.extend(current);
+    functions
+}
+
+#[test]
+fn get_has_no_panic_path() {
+    let dir = Path::new(env!("CARGO_TARGET_TMPDIR")).join("no_panic");
+    std::fs::create_dir_all(&dir).unwrap();
+    let functions = assembly(&dir);
+    let body = |name: &str| {
+        functions
+            .iter()
+            .find(|(function, _)| function == name)
+            .map(|(_, body)| body.as_str())
+            .unwrap_or_else(|| panic!("no assembly for {}", name))
+    };
+
+    assert!(
+        body("read_element").contains("panic"),
+        "the control function should reference a panic:\n{}",
+        body("read_element")
+    );
+    for name in ["get_element", "get_element_field", "get_matrix_element"] {
+        assert!(
+            !body(name).contains("panic"),
+            "{} has a panic path:\n{}",
+            name,
+            body(name)
+        );
+    }
+}
@@ -8,6 +8,7 @@ mod periph {
     #[repr(C)]
     pub struct RegisterBlock {
         /// Multi-dimensional arrays.
+        #[allow(clippy::type_complexity)]
         pub DEEP_LEARNING: [[[[[[[[ral_registers::RWRegister<u32>; 1]; 2]; 3]; 4]; 5]; 6]; 7]; 8],
     }
 
@@ -53,6 +54,15 @@ fn write_deep_array() {
 
 #[test]
 fn modify_deep_array() {
+    let rb = register_block();
+    ral::modify_reg!(periph, &rb, DEEP_LEARNING[7][6][5][4][3][2][1][0], GRADIENT: 23);
+    assert_eq!(rb.DEEP_LEARNING[7][6][5][4][3][2][1][0].read(), 23 << 3);
+}
+
+/// Release builds mask field values to the field; debug builds panic instead.
+#[cfg(not(debug_assertions))]
+#[test]
+fn modify_deep_array_masked() {
     let rb = register_block();
     ral::modify_reg!(periph, &rb, DEEP_LEARNING[7][6][5][4][3][2][1][0], GRADIENT: 42);
     assert_eq!(
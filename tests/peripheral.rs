@@ -0,0 +1,49 @@
+//! Tests for mapping a register block over memory with `peripheral`.
+
+#![allow(non_snake_case)] // Register conventions.
+
+use ral_registers::{peripheral, RORegister, RWRegister};
+
+#[repr(C)]
+struct RegisterBlock {
+    CR: RWRegister<u32>,
+    SR: RORegister<u32>,
+}
+
+#[test]
+fn cast_aligned_memory() {
+    // The address comes from `Box::into_raw`, not a shared borrow, so the block may be written.
+    let base = Box::into_raw(Box::new([0x12u32, 0x34])) as usize;
+    // Safety: the memory is a valid RegisterBlock, and is never freed.
+    let block: &'static RegisterBlock = unsafe { peripheral(base) };
+    assert_eq!(block.CR.read(), 0x12);
+    assert_eq!(block.SR.read(), 0x34);
+    block.CR.write(0x56);
+    assert_eq!(block.CR.read(), 0x56);
+}
+
+#[test]
+fn instances_are_independent() {
+    let first = Box::into_raw(Box::new([0u32; 2])) as usize;
+    let second = Box::into_raw(Box::new([0u32; 2])) as usize;
+    // Safety: both allocations are valid RegisterBlocks, and are never freed.
+    let usart1: &'static RegisterBlock = unsafe { peripheral(first) };
+    let usart2: &'static RegisterBlock = unsafe { peripheral(second) };
+
+    usart1.CR.write(0x11);
+    assert_eq!(usart1.CR.read(), 0x11);
+    assert_eq!(usart2.CR.read(), 0);
+
+    usart2.CR.write(0x22);
+    assert_eq!(usart1.CR.read(), 0x11);
+    assert_eq!(usart2.CR.read(), 0x22);
+    assert_eq!((usart1.SR.read(), usart2.SR.read()), (0, 0));
+}
+
+#[cfg(debug_assertions)]
+#[should_panic(expected = "peripheral base address is not aligned for its register block")]
+#[test]
+fn cast_misaligned_memory() {
+    let base = Box::into_raw(Box::new([0u32; 3])) as usize + 2;
+    let _: &RegisterBlock = unsafe { peripheral(base) };
+}
@@ -0,0 +1,110 @@
+//! Property tests for the field arithmetic of the macros.
+//!
+//! The strategies generate register values, and field values that fit a
+//! field of a given width. The properties check that building a register
+//! value from fields and extracting them again round-trips, and that the
+//! register macros keep each field within its mask.
+
+#![allow(non_upper_case_globals, non_snake_case)] // Macro conventions.
+
+use proptest::prelude::*;
+use ral_registers as ral;
+
+mod periph {
+    #[repr(C)]
+    pub struct RegisterBlock {
+        pub REG: ral_registers::RWRegister<u32>,
+    }
+
+    /// 7-bit and 2-bit fields at low, middle, and high offsets.
+    pub mod REG {
+        macro_rules! field {
+            ($name:ident, $offset:expr, $width:expr) => {
+                pub mod $name {
+                    pub const offset: u32 = $offset;
+                    pub const mask: u32 = ((1 << $width) - 1) << offset;
+                    pub mod R {}
+                    pub mod W {}
+                    pub mod RW {}
+                }
+            };
+        }
+        field!(A7, 0, 7);
+        field!(B2, 7, 2);
+        field!(C7, 12, 7);
+        field!(D2, 19, 2);
+        field!(E7, 23, 7);
+        field!(F2, 30, 2);
+    }
+}
+
+fn register_block() -> periph::RegisterBlock {
+    periph::RegisterBlock {
+        REG: ral::RWRegister::new(0),
+    }
+}
+
+/// Values that fit a field of `width` bits.
+fn field_value(width: u32) -> impl Strategy<Value = u32> {
+    0..(1u32 << width)
+}
+
+/// A value for each field of `REG`, in declaration order.
+fn field_values() -> impl Strategy<Value = (u32, u32, u32, u32, u32, u32)> {
+    (
+        field_value(7),
+        field_value(2),
+        field_value(7),
+        field_value(2),
+        field_value(7),
+        field_value(2),
+    )
+}
+
+/// Any value of the whole register.
+fn register_value() -> impl Strategy<Value = u32> {
+    any::<u32>()
+}
+
+proptest! {
+    #[test]
+    fn build_then_extract(value in field_value(7), small in field_value(2)) {
+        let built = ral::build_value!(@checked periph, REG, C7: value);
+        prop_assert_eq!(built & !periph::REG::C7::mask, 0);
+        prop_assert_eq!(ral::extract_field!(periph, REG, C7, built), value);
+
+        let built = ral::build_value!(@checked periph, REG, F2: small, A7: value);
+        prop_assert_eq!(built & !(periph::REG::F2::mask | periph::REG::A7::mask), 0);
+        prop_assert_eq!(ral::extract_field!(periph, REG, [F2, A7], built), (small, value));
+    }
+
+    #[test]
+    fn extract_then_build(reg in register_value()) {
+        let (a, b, c, d, e, f) = ral::extract_field!(periph, REG, [A7, B2, C7, D2, E7, F2], reg);
+        let fields = periph::REG::A7::mask | periph::REG::B2::mask | periph::REG::C7::mask
+            | periph::REG::D2::mask | periph::REG::E7::mask | periph::REG::F2::mask;
+        let built = ral::build_value!(@checked periph, REG, A7: a, B2: b, C7: c, D2: d, E7: e, F2: f);
+        prop_assert_eq!(built, reg & fields);
+    }
+
+    #[test]
+    fn write_then_read((a, b, c, d, e, f) in field_values()) {
+        let rb = register_block();
+        ral::write_reg!(periph, &rb, REG, A7: a, B2: b, C7: c, D2: d, E7: e, F2: f);
+        prop_assert_eq!(
+            ral::read_reg!(periph, &rb, REG, A7, B2, C7, D2, E7, F2),
+            (a, b, c, d, e, f)
+        );
+    }
+
+    #[test]
+    fn modify_keeps_other_bits(reg in register_value(), value in field_value(7), small in field_value(2)) {
+        let rb = register_block();
+        rb.REG.write(reg);
+        let written = ral::modify_reg!(periph, &rb, REG, E7: value, D2: small);
+        let mask = periph::REG::E7::mask | periph::REG::D2::mask;
+        prop_assert_eq!(written & !mask, reg & !mask);
+        prop_assert_eq!(ral::read_reg!(periph, &rb, REG, E7, D2), (value, small));
+        prop_assert_eq!(rb.REG.read(), written);
+    }
+}
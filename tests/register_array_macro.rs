@@ -0,0 +1,82 @@
+//! Tests that register arrays declared with `register_array!` work
+//! with the read, write, modify, and reset macros.
+
+#![allow(non_upper_case_globals, non_snake_case)] // Macro conventions.
+
+use ral_registers as ral;
+
+mod periph {
+    ral_registers::register_array! {
+        MY_ARRAY: RWRegister<u32>[3],
+        fields { FIELD_A: 0..=6, FIELD_B: 27..=28, FIELD_C: 31..=31 }
+    }
+
+    ral_registers::register_array! {
+        MY_HALVES: RWRegister<u16>[2],
+        fields { FIELD_LOW: 0..=7, FIELD_HIGH: 8..=15 }
+    }
+
+    #[repr(C)]
+    pub struct RegisterBlock {
+        pub MY_ARRAY: MY_ARRAY::Array,
+        pub MY_HALVES: MY_HALVES::Array,
+    }
+
+    pub struct ResetValues {
+        pub MY_ARRAY: u32,
+    }
+
+    pub mod INST {
+        pub const reset: super::ResetValues = super::ResetValues {
+            MY_ARRAY: 0b01 << 27 | 42,
+        };
+    }
+}
+
+fn register_block() -> periph::RegisterBlock {
+    // Safety: bitpattern of zero is fine.
+    use std::mem::MaybeUninit;
+    unsafe { MaybeUninit::zeroed().assume_init() }
+}
+
+#[test]
+fn consts() {
+    assert_eq!(periph::MY_ARRAY::FIELD_A::offset, 0);
+    assert_eq!(periph::MY_ARRAY::FIELD_A::mask, 0x7F);
+    assert_eq!(periph::MY_ARRAY::FIELD_B::offset, 27);
+    assert_eq!(periph::MY_ARRAY::FIELD_B::mask, 0b11 << 27);
+    assert_eq!(periph::MY_ARRAY::FIELD_C::mask, 1 << 31);
+    let mask: u16 = periph::MY_HALVES::FIELD_HIGH::mask;
+    assert_eq!(mask, 0xFF00);
+    assert_eq!(std::mem::size_of::<periph::RegisterBlock>(), 3 * 4 + 2 * 2);
+
+    use ral::RegisterFields;
+    assert_eq!(periph::MY_ARRAY::Fields::NAME, "MY_ARRAY");
+    assert_eq!(periph::MY_ARRAY::Fields::FIELDS[1].name, "FIELD_B");
+}
+
+#[test]
+fn access() {
+    let rb = register_block();
+
+    ral::write_reg!(periph, &rb, MY_ARRAY[1], FIELD_A: 0x55, FIELD_B: 0b10);
+    assert_eq!(rb.MY_ARRAY[1].read(), 0b10 << 27 | 0x55);
+    assert_eq!(
+        ral::read_reg!(periph, &rb, MY_ARRAY[1], FIELD_A, FIELD_B),
+        (0x55, 0b10)
+    );
+
+    ral::modify_reg!(periph, &rb, MY_ARRAY[1], FIELD_C: 1);
+    assert_eq!(rb.MY_ARRAY[1].read(), 1 << 31 | 0b10 << 27 | 0x55);
+
+    ral::reset_reg!(periph, &rb, INST, MY_ARRAY[2]);
+    assert_eq!(rb.MY_ARRAY[2].read(), 0b01 << 27 | 42);
+    ral::reset_reg!(periph, &rb, INST, MY_ARRAY[1], FIELD_A);
+    assert_eq!(rb.MY_ARRAY[1].read(), 1 << 31 | 0b10 << 27 | 42);
+    assert_eq!(rb.MY_ARRAY[0].read(), 0);
+
+    ral::write_reg!(periph, &rb, MY_HALVES[1], FIELD_HIGH: 0xAB, FIELD_LOW: 0xCD);
+    assert_eq!(rb.MY_HALVES[1].read(), 0xABCD);
+    assert_eq!(ral::read_reg!(periph, &rb, MY_HALVES[1], FIELD_HIGH), 0xAB);
+    assert_eq!(rb.MY_HALVES[0].read(), 0);
+}
@@ -0,0 +1,294 @@
+//! Tests that read, write, modify, and reset macros work with
+//! arrays of 16-bit registers, where each element is two bytes
+//! from the last.
+//!
+//! Like the array tests in `macros.rs`, the macros are supplied with a
+//! reference to a register block, an "instance" type that derefs to a
+//! register block, and a pointer to a register block.
+
+#![allow(non_upper_case_globals, non_snake_case)] // Macro conventions.
+
+use ral_registers as ral;
+
+mod periph {
+    #[repr(C)]
+    pub struct RegisterBlock {
+        pub MY_ARRAY: [ral_registers::RWRegister<u16>; 4],
+        pub MY_AFTER: ral_registers::RWRegister<u16>,
+    }
+
+    pub mod MY_ARRAY {
+        pub mod FIELD_A {
+            pub const offset: u16 = 0;
+            pub const mask: u16 = 0x1FFF << offset;
+            pub mod R {}
+            pub mod W {}
+            pub mod RW {}
+        }
+        pub mod FIELD_B {
+            pub const offset: u16 = 13;
+            pub const mask: u16 = 0b111 << offset;
+            pub mod R {}
+            pub mod W {}
+            pub mod RW {
+                pub const Fast: u16 = 0b101;
+            }
+        }
+    }
+
+    /// A scalar reset value applies to every register of an array.
+    /// See `reset_values.rs` for per-element reset values.
+    pub struct ResetValues {
+        pub MY_ARRAY: u16,
+    }
+
+    pub mod INST {
+        pub const reset: super::ResetValues = super::ResetValues {
+            MY_ARRAY: 0b010 << 13 | 42,
+        };
+    }
+}
+
+fn register_block() -> periph::RegisterBlock {
+    // Safety: bitpattern of zero is fine.
+    use std::mem::MaybeUninit;
+    unsafe { MaybeUninit::zeroed().assume_init() }
+}
+
+struct Instance<'a> {
+    rb: &'a periph::RegisterBlock,
+}
+
+impl<'a> Instance<'a> {
+    fn new(rb: &'a periph::RegisterBlock) -> Self {
+        Self { rb }
+    }
+}
+
+impl std::ops::Deref for Instance<'_> {
+    type Target = periph::RegisterBlock;
+    fn deref(&self) -> &Self::Target {
+        self.rb
+    }
+}
+
+/// Checks that every element other than `index`, and the register after
+/// the array, still holds zero.
+macro_rules! assert_neighbours_untouched {
+    ($instance:expr, $index:expr) => {
+        for (i, register) in (*$instance).MY_ARRAY.iter().enumerate() {
+            if i != $index {
+                assert_eq!(register.read(), 0, "Element {} was modified", i);
+            }
+        }
+        assert_eq!(
+            (*$instance).MY_AFTER.read(),
+            0,
+            "Register after the array was modified"
+        );
+    };
+}
+
+macro_rules! read_reg_test_cases {
+    ($instance:expr, $index:expr) => {
+        (*$instance).MY_ARRAY[$index].write(u16::MAX);
+
+        assert_eq!(
+            ral::read_reg!(periph, $instance, MY_ARRAY[$index]),
+            u16::MAX,
+            "Direct read"
+        );
+
+        assert_eq!(
+            ral::read_reg!(periph, $instance, MY_ARRAY[$index], FIELD_A),
+            0x1FFF,
+            "Individual field read (A)"
+        );
+        assert_eq!(
+            ral::read_reg!(periph, $instance, MY_ARRAY[$index], FIELD_B),
+            0b111,
+            "Individual field read (B)"
+        );
+
+        let (a, b): (u16, u16) =
+            ral::read_reg!(periph, $instance, MY_ARRAY[$index], FIELD_A, FIELD_B);
+        assert_eq!((a, b), (0x1FFF, 0b111), "Tuple field reads");
+
+        assert!(
+            ral::read_reg!(periph, $instance, MY_ARRAY[$index], FIELD_B == 0b111),
+            "Boolean expressions"
+        );
+
+        (*$instance).MY_ARRAY[$index].write(0);
+        assert_neighbours_untouched!($instance, $index);
+    };
+}
+
+macro_rules! write_reg_test_cases {
+    ($instance:expr, $index:expr) => {
+        ral::write_reg!(periph, $instance, MY_ARRAY[$index], FIELD_B: Fast);
+        assert_eq!((*$instance).MY_ARRAY[$index].read(), 0b101 << 13, "1:1 write:field (B)");
+        assert_neighbours_untouched!($instance, $index);
+
+        let value: u16 = 0x1200;
+        ral::write_reg!(periph, $instance, MY_ARRAY[$index], FIELD_A: value | 0x34, FIELD_B: 1);
+        assert_eq!((*$instance).MY_ARRAY[$index].read(), 1 << 13 | 0x1234, "1:N write:field");
+
+        ral::write_reg!(periph, $instance, MY_ARRAY[$index], 0xAAAA);
+        assert_eq!((*$instance).MY_ARRAY[$index].read(), 0xAAAA, "Direct write");
+        assert_neighbours_untouched!($instance, $index);
+    };
+}
+
+macro_rules! modify_reg_test_cases {
+    ($instance:expr, $index:expr) => {
+        ral::modify_reg!(periph, $instance, MY_ARRAY[$index], FIELD_B: 0b111);
+        assert_eq!((*$instance).MY_ARRAY[$index].read(), 0b111 << 13, "RMW individual fields (B)");
+        ral::modify_reg!(periph, $instance, MY_ARRAY[$index], FIELD_A: 0x1FFF);
+        assert_eq!((*$instance).MY_ARRAY[$index].read(), u16::MAX, "RMW individual fields (A)");
+
+        ral::modify_reg!(periph, $instance, MY_ARRAY[$index], |reg| reg & !1);
+        assert_eq!((*$instance).MY_ARRAY[$index].read(), u16::MAX - 1, "RMW whole register");
+        assert_neighbours_untouched!($instance, $index);
+    };
+}
+
+macro_rules! reset_reg_test_cases {
+    ($instance:expr, $index:expr) => {
+        (*$instance).MY_ARRAY[$index].write(u16::MAX);
+        ral::reset_reg!(periph, $instance, INST, MY_ARRAY[$index]);
+        assert_eq!(
+            (*$instance).MY_ARRAY[$index].read(),
+            0b010 << 13 | 42,
+            "Entire register"
+        );
+
+        (*$instance).MY_ARRAY[$index].write(u16::MAX);
+        ral::reset_reg!(periph, $instance, INST, MY_ARRAY[$index], FIELD_B);
+        assert_eq!(
+            (*$instance).MY_ARRAY[$index].read(),
+            0x1FFF | 0b010 << 13,
+            "Field in register (B)"
+        );
+        assert_neighbours_untouched!($instance, $index);
+    };
+}
+
+#[test]
+fn layout() {
+    use std::mem::{offset_of, size_of};
+    assert_eq!(size_of::<[ral::RWRegister<u16>; 4]>(), 8);
+    assert_eq!(offset_of!(periph::RegisterBlock, MY_AFTER), 8);
+}
+
+#[test]
+fn read_array_ref() {
+    let rb = register_block();
+    read_reg_test_cases!(&rb, 1);
+}
+
+#[test]
+fn read_array_deref() {
+    let rb = register_block();
+    let inst = Instance::new(&rb);
+    read_reg_test_cases!(inst, 2);
+}
+
+#[test]
+fn read_array_ptr() {
+    let ptr: *const _ = &register_block();
+    unsafe {
+        read_reg_test_cases!(ptr, 3);
+    }
+}
+
+#[test]
+fn write_array_ref() {
+    let rb = register_block();
+    write_reg_test_cases!(&rb, 0);
+}
+
+#[test]
+fn write_array_deref() {
+    let rb = register_block();
+    let inst = Instance::new(&rb);
+    write_reg_test_cases!(inst, 1);
+}
+
+#[test]
+fn write_array_ptr() {
+    let ptr: *const _ = &register_block();
+    unsafe {
+        write_reg_test_cases!(ptr, 3);
+    }
+}
+
+#[test]
+fn modify_array_ref() {
+    let rb = register_block();
+    modify_reg_test_cases!(&rb, 0);
+}
+
+#[test]
+fn modify_array_deref() {
+    let rb = register_block();
+    let inst = Instance::new(&rb);
+    modify_reg_test_cases!(inst, 2);
+}
+
+#[test]
+fn modify_array_ptr() {
+    let ptr: *const _ = &register_block();
+    unsafe {
+        modify_reg_test_cases!(ptr, 3);
+    }
+}
+
+#[test]
+fn reset_array_ref() {
+    let rb = register_block();
+    reset_reg_test_cases!(&rb, 1);
+}
+
+#[test]
+fn reset_array_deref() {
+    let rb = register_block();
+    let inst = Instance::new(&rb);
+    reset_reg_test_cases!(inst, 0);
+}
+
+#[test]
+fn reset_array_ptr() {
+    let ptr: *const _ = &register_block();
+    unsafe {
+        reset_reg_test_cases!(ptr, 3);
+    }
+}
+
+#[test]
+fn reset_all_elements() {
+    let rb = register_block();
+    ral::reset_all!(periph, &rb, INST, [MY_ARRAY]);
+    for register in rb.MY_ARRAY.iter() {
+        assert_eq!(register.read(), 0b010 << 13 | 42);
+    }
+    assert_eq!(rb.MY_AFTER.read(), 0);
+}
+
+#[test]
+fn read_many_elements() {
+    let rb = register_block();
+    for (i, register) in rb.MY_ARRAY.iter().enumerate() {
+        register.write(0x1000 + i as u16);
+    }
+    let values: [u16; 3] = ral::read_many!(periph, &rb, MY_ARRAY, 1..4);
+    assert_eq!(values, [0x1001, 0x1002, 0x1003]);
+}
+
+#[should_panic(expected = "MY_ARRAY index 4 out of bounds (len 4)")]
+#[test]
+fn write_array_out_of_bounds() {
+    let rb = register_block();
+    let index = 4;
+    ral::write_reg!(periph, &rb, MY_ARRAY[index], FIELD_A: 1);
+}
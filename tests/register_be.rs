@@ -0,0 +1,103 @@
+//! Tests for the big-endian register types.
+
+#![allow(non_upper_case_globals, non_snake_case)] // Macro conventions.
+
+use ral_registers as ral;
+use ral_registers::{RORegisterBE, RWRegisterBE, WORegisterBE};
+
+mod periph {
+    #[repr(C)]
+    pub struct RegisterBlock {
+        pub MY_RW: ral_registers::RWRegisterBE<u32>,
+        pub MY_RO: ral_registers::RORegisterBE<u32>,
+        pub MY_WO: ral_registers::WORegisterBE<u32>,
+        pub MY_ARRAY: [ral_registers::RWRegisterBE<u16>; 2],
+    }
+
+    pub mod MY_RW {
+        pub mod FIELD_A {
+            pub const offset: u32 = 0;
+            pub const mask: u32 = 0xFF << offset;
+            pub mod R {}
+            pub mod W {}
+            pub mod RW {}
+        }
+        pub mod FIELD_B {
+            pub const offset: u32 = 24;
+            pub const mask: u32 = 0xFF << offset;
+            pub mod R {}
+            pub mod W {}
+            pub mod RW {}
+        }
+    }
+
+    pub mod MY_ARRAY {
+        pub mod FIELD_C {
+            pub const offset: u16 = 4;
+            pub const mask: u16 = 0xF << offset;
+            pub mod R {}
+            pub mod W {}
+            pub mod RW {}
+        }
+    }
+}
+
+fn register_block() -> periph::RegisterBlock {
+    periph::RegisterBlock {
+        MY_RW: RWRegisterBE::new(0),
+        MY_RO: RORegisterBE::new(0x0A0B_0C0D),
+        MY_WO: WORegisterBE::new(0),
+        MY_ARRAY: [RWRegisterBE::new(0), RWRegisterBE::new(0)],
+    }
+}
+
+/// The bytes of a register's storage, in address order.
+fn bytes<T>(ptr: *const T) -> [u8; 4] {
+    unsafe { *(ptr as *const [u8; 4]) }
+}
+
+#[test]
+fn storage() {
+    let rb = register_block();
+    rb.MY_RW.write(0x0102_0304);
+    assert_eq!(bytes(rb.MY_RW.as_ptr()), [1, 2, 3, 4]);
+    #[cfg(target_endian = "little")]
+    assert_eq!(unsafe { rb.MY_RW.as_ptr().read() }, 0x0403_0201);
+    assert_eq!(rb.MY_RW.read(), 0x0102_0304);
+
+    assert_eq!(bytes(rb.MY_RO.as_ptr()), [0xA, 0xB, 0xC, 0xD]);
+    assert_eq!(rb.MY_RO.read(), 0x0A0B_0C0D);
+
+    rb.MY_WO.write(0x0102_0304);
+    assert_eq!(bytes(rb.MY_WO.as_ptr()), [1, 2, 3, 4]);
+}
+
+#[test]
+fn macros() {
+    let rb = register_block();
+    ral::write_reg!(periph, &rb, MY_RW, FIELD_A: 0x12, FIELD_B: 0x34);
+    assert_eq!(bytes(rb.MY_RW.as_ptr()), [0x34, 0, 0, 0x12]);
+    assert_eq!(
+        ral::read_reg!(periph, &rb, MY_RW, FIELD_A, FIELD_B),
+        (0x12, 0x34)
+    );
+
+    ral::modify_reg!(periph, &rb, MY_RW, FIELD_B: 0x56);
+    assert_eq!(rb.MY_RW.read(), 0x5600_0012);
+
+    ral::write_reg!(periph, &rb, MY_ARRAY[1], FIELD_C: 0xA);
+    assert_eq!(
+        unsafe { *(rb.MY_ARRAY[1].as_ptr() as *const [u8; 2]) },
+        [0, 0xA0]
+    );
+    assert_eq!(ral::read_reg!(periph, &rb, MY_ARRAY[1], FIELD_C), 0xA);
+}
+
+#[test]
+fn debug() {
+    let rb = register_block();
+    rb.MY_RW.write(0x0102_0304);
+    assert_eq!(format!("{:?}", rb.MY_RW), "RWRegisterBE(0x01020304)");
+    assert_eq!(format!("{:?}", rb.MY_RO), "RORegisterBE(0x0a0b0c0d)");
+    assert_eq!(format!("{:?}", rb.MY_WO), "WORegisterBE(<write-only>)");
+}
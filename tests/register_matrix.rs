@@ -0,0 +1,183 @@
+//! Tests that read, write, modify, and reset macros work with
+//! two-dimensional register arrays.
+
+#![allow(non_upper_case_globals, non_snake_case)] // Macro conventions.
+
+use ral_registers as ral;
+
+mod periph {
+    #[repr(C)]
+    pub struct RegisterBlock {
+        pub MY_MATRIX: [[ral_registers::RWRegister<u32>; 3]; 2],
+    }
+
+    pub mod MY_MATRIX {
+        pub mod FIELD_A {
+            pub const offset: u32 = 0;
+            pub const mask: u32 = 0x7F << offset;
+            pub mod R {}
+            pub mod W {}
+            pub mod RW {}
+        }
+        pub mod FIELD_B {
+            pub const offset: u32 = 27;
+            pub const mask: u32 = 0b11 << offset;
+            pub mod R {}
+            pub mod W {}
+            pub mod RW {}
+        }
+    }
+
+    pub struct ResetValues {
+        pub MY_MATRIX: u32,
+    }
+
+    pub mod INST {
+        pub const reset: super::ResetValues = super::ResetValues { MY_MATRIX: 42 };
+    }
+}
+
+fn register_block() -> periph::RegisterBlock {
+    // Safety: bitpattern of zero is fine.
+    use std::mem::MaybeUninit;
+    unsafe { MaybeUninit::zeroed().assume_init() }
+}
+
+/// Returns the value of every register in the matrix.
+fn values(rb: &periph::RegisterBlock) -> [[u32; 3]; 2] {
+    let mut values = [[0; 3]; 2];
+    for (i, row) in rb.MY_MATRIX.iter().enumerate() {
+        for (j, reg) in row.iter().enumerate() {
+            values[i][j] = reg.read();
+        }
+    }
+    values
+}
+
+#[test]
+fn read_matrix() {
+    let rb = register_block();
+    rb.MY_MATRIX[1][2].write(u32::MAX);
+    assert_eq!(ral::read_reg!(periph, &rb, MY_MATRIX[1][2]), u32::MAX);
+    assert_eq!(ral::read_reg!(periph, &rb, MY_MATRIX[1][2], FIELD_A), 0x7F);
+    assert_eq!(
+        ral::read_reg!(periph, &rb, MY_MATRIX[1][2], FIELD_A, FIELD_B),
+        (0x7F, 0b11)
+    );
+    assert!(ral::read_reg!(
+        periph,
+        &rb,
+        MY_MATRIX[1][2],
+        FIELD_B == 0b11
+    ));
+    assert_eq!(ral::read_reg!(periph, &rb, MY_MATRIX[0][2]), 0);
+    assert_eq!(ral::read_reg!(periph, &rb, MY_MATRIX[1][1]), 0);
+}
+
+#[test]
+fn write_matrix() {
+    let rb = register_block();
+    let (i, j) = (1, 0);
+    ral::write_reg!(periph, &rb, MY_MATRIX[i][j], FIELD_A: 3, FIELD_B: 1);
+    ral::write_reg!(periph, &rb, MY_MATRIX[i - 1][j + 2], 7);
+    assert_eq!(values(&rb), [[0, 0, 7], [3 | (1 << 27), 0, 0]]);
+}
+
+#[test]
+fn modify_matrix() {
+    let rb = register_block();
+    rb.MY_MATRIX[0][1].write(1 << 8);
+    ral::modify_reg!(periph, &rb, MY_MATRIX[0][1], FIELD_A: 3);
+    ral::modify_reg!(periph, &rb, MY_MATRIX[1][1], |reg| reg | 5);
+    assert_eq!(values(&rb), [[0, (1 << 8) | 3, 0], [0, 5, 0]]);
+}
+
+#[test]
+fn reset_matrix() {
+    let rb = register_block();
+    rb.MY_MATRIX[1][2].write(u32::MAX);
+    ral::reset_reg!(periph, &rb, INST, MY_MATRIX[0][2]);
+    ral::reset_reg!(periph, &rb, INST, MY_MATRIX[1][2], FIELD_A);
+    assert_eq!(values(&rb), [[0, 0, 42], [0, 0, !0x7F | 42]]);
+}
+
+#[should_panic]
+#[test]
+fn read_matrix_out_of_bounds_outer() {
+    let rb = register_block();
+    let outer = 2;
+    let _ = ral::read_reg!(periph, &rb, MY_MATRIX[outer][0]);
+}
+
+#[should_panic]
+#[test]
+fn read_matrix_out_of_bounds_inner() {
+    let rb = register_block();
+    let _ = ral::read_reg!(periph, &rb, MY_MATRIX[0][3]);
+}
+
+#[should_panic]
+#[test]
+fn write_matrix_out_of_bounds() {
+    let rb = register_block();
+    ral::write_reg!(periph, &rb, MY_MATRIX[0][3], 7);
+}
+
+#[should_panic]
+#[test]
+fn modify_matrix_out_of_bounds() {
+    let rb = register_block();
+    let outer = 2;
+    ral::modify_reg!(periph, &rb, MY_MATRIX[outer][0], |_| 7);
+}
+
+#[should_panic]
+#[test]
+fn reset_matrix_out_of_bounds() {
+    let rb = register_block();
+    ral::reset_reg!(periph, &rb, INST, MY_MATRIX[0][3]);
+}
+
+#[test]
+fn try_matrix_out_of_bounds() {
+    let rb = register_block();
+    let err = ral::IndexError { index: 3, len: 3 };
+    assert_eq!(ral::try_read_reg!(periph, &rb, MY_MATRIX[0][3]), Err(err));
+    let err = ral::IndexError { index: 2, len: 2 };
+    assert_eq!(
+        ral::try_write_reg!(periph, &rb, MY_MATRIX[2][0], 7),
+        Err(err)
+    );
+}
+
+#[test]
+fn get_matrix_out_of_bounds() {
+    let rb = register_block();
+    assert_eq!(ral::get_reg!(periph, &rb, MY_MATRIX[0][3]), None);
+    assert_eq!(ral::get_reg!(periph, &rb, MY_MATRIX[2][0]), None);
+    assert_eq!(ral::get_reg!(periph, &rb, MY_MATRIX[1][2]), Some(0));
+}
+
+#[test]
+fn get_matrix_is_slice_get() {
+    let rb = register_block();
+    for i in 0..4 {
+        for j in 0..5 {
+            let expected = rb
+                .MY_MATRIX
+                .get(i)
+                .and_then(|row| row.get(j))
+                .map(|register| register.read());
+            assert_eq!(ral::get_reg!(periph, &rb, MY_MATRIX[i][j]), expected);
+        }
+    }
+}
+
+#[test]
+fn reset_all_matrix_rows() {
+    let rb = register_block();
+    ral::reset_all!(periph, &rb, INST, [MY_MATRIX[0], MY_MATRIX[1]]);
+    for row in rb.MY_MATRIX.iter() {
+        assert!(row.iter().all(|register| register.read() == 42));
+    }
+}
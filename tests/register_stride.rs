@@ -0,0 +1,119 @@
+//! Tests that the macros apply a register module's `index` mapping
+//! to register arrays with padding between elements.
+
+#![allow(non_upper_case_globals, non_snake_case)] // Macro conventions.
+
+use ral_registers as ral;
+
+mod periph {
+    #[repr(C)]
+    pub struct RegisterBlock {
+        /// Three registers, each followed by a reserved register.
+        pub MY_STRIDED: [ral_registers::RWRegister<u32>; 6],
+    }
+
+    pub mod MY_STRIDED {
+        pub const fn index(i: usize) -> usize {
+            i * 2
+        }
+
+        pub mod FIELD_A {
+            pub const offset: u32 = 0;
+            pub const mask: u32 = 0x7F << offset;
+            pub mod R {}
+            pub mod W {}
+            pub mod RW {}
+        }
+    }
+
+    pub struct ResetValues {
+        pub MY_STRIDED: u32,
+    }
+
+    pub mod INST {
+        pub const reset: super::ResetValues = super::ResetValues { MY_STRIDED: 42 };
+    }
+}
+
+fn register_block() -> periph::RegisterBlock {
+    // Safety: bitpattern of zero is fine.
+    use std::mem::MaybeUninit;
+    unsafe { MaybeUninit::zeroed().assume_init() }
+}
+
+/// Returns the value of every register in the storage, including reserved registers.
+fn values(rb: &periph::RegisterBlock) -> [u32; 6] {
+    let mut values = [0; 6];
+    for (value, reg) in values.iter_mut().zip(rb.MY_STRIDED.iter()) {
+        *value = reg.read();
+    }
+    values
+}
+
+#[test]
+fn read_strided() {
+    let rb = register_block();
+    rb.MY_STRIDED[2].write(0x2A);
+    assert_eq!(ral::read_reg!(periph, &rb, MY_STRIDED[1]), 0x2A);
+    assert_eq!(ral::read_reg!(periph, &rb, MY_STRIDED[1], FIELD_A), 0x2A);
+    assert!(ral::read_reg!(periph, &rb, MY_STRIDED[1], FIELD_A == 0x2A));
+    assert_eq!(ral::read_reg!(periph, &rb, MY_STRIDED[0]), 0);
+    assert_eq!(ral::read_reg!(periph, &rb, MY_STRIDED[2]), 0);
+}
+
+#[test]
+fn write_strided() {
+    let rb = register_block();
+    ral::write_reg!(periph, &rb, MY_STRIDED[0], 1);
+    ral::write_reg!(periph, &rb, MY_STRIDED[1], FIELD_A: 2);
+    ral::write_reg!(periph, &rb, MY_STRIDED[2], 3);
+    assert_eq!(values(&rb), [1, 0, 2, 0, 3, 0]);
+}
+
+#[test]
+fn modify_strided() {
+    let rb = register_block();
+    rb.MY_STRIDED[4].write(1 << 8);
+    ral::modify_reg!(periph, &rb, MY_STRIDED[2], FIELD_A: 3);
+    ral::modify_reg!(periph, &rb, MY_STRIDED[1], |reg| reg | 5);
+    assert_eq!(values(&rb), [0, 0, 5, 0, (1 << 8) | 3, 0]);
+}
+
+#[test]
+fn reset_strided() {
+    let rb = register_block();
+    ral::reset_reg!(periph, &rb, INST, MY_STRIDED[1]);
+    assert_eq!(values(&rb), [0, 0, 42, 0, 0, 0]);
+}
+
+#[test]
+fn try_strided() {
+    let rb = register_block();
+    assert_eq!(ral::try_write_reg!(periph, &rb, MY_STRIDED[2], 7), Ok(()));
+    assert_eq!(values(&rb), [0, 0, 0, 0, 7, 0]);
+    assert_eq!(
+        ral::try_read_reg!(periph, &rb, MY_STRIDED[3]),
+        Err(ral::IndexError { index: 6, len: 6 })
+    );
+}
+
+#[should_panic(expected = "MY_STRIDED index 6 out of bounds (len 6)")]
+#[test]
+fn read_strided_out_of_bounds() {
+    let rb = register_block();
+    let index = 3;
+    let _ = ral::read_reg!(periph, &rb, MY_STRIDED[index]);
+}
+
+#[test]
+fn reg_addr_strided() {
+    let rb = register_block();
+    assert_eq!(
+        ral::reg_addr!(periph, &rb, MY_STRIDED[1]) - ral::reg_addr!(periph, &rb, MY_STRIDED[0]),
+        8
+    );
+    assert_eq!(
+        ral::reg_addr!(periph, &rb, MY_STRIDED[2]),
+        rb.MY_STRIDED[4].as_ptr() as usize
+    );
+}
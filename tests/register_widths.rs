@@ -0,0 +1,366 @@
+//! Tests that read, write, modify, and reset macros work with
+//! registers of every width, including fields at the top of the register.
+//!
+//! `width_tests!` declares the same peripheral for each width, with a
+//! scalar register, an array of registers, and a register after the array.
+//! Like the array tests in `macros.rs`, the macros are supplied with a
+//! reference to a register block, an "instance" type that derefs to a
+//! register block, and a pointer to a register block. Each test case then
+//! checks that every other register still holds zero.
+
+#![allow(non_upper_case_globals, non_snake_case)] // Macro conventions.
+
+macro_rules! read_reg_test_cases {
+    ($ty:ident, $instance:expr, $register:ident $([$offset:expr])*) => {
+        (*$instance).$register $([$offset])*.write($ty::MAX);
+
+        assert_eq!(ral::read_reg!(periph, $instance, $register $([$offset])*), $ty::MAX, "Direct read");
+
+        assert_eq!(ral::read_reg!(periph, $instance, $register $([$offset])*, FIELD_A), A_MAX, "Individual field read (A)");
+        assert_eq!(ral::read_reg!(periph, $instance, $register $([$offset])*, FIELD_B), 0b11, "Individual field read (B)");
+        assert_eq!(ral::read_reg!(periph, $instance, $register $([$offset])*, FIELD_C), 1, "Individual field read (C)");
+
+        let (a, b, c): ($ty, $ty, $ty) = ral::read_reg!(periph, $instance, $register $([$offset])*, FIELD_A, FIELD_B, FIELD_C);
+        assert_eq!((a, b, c), (A_MAX, 0b11, 1), "Tuple field reads");
+
+        assert!(ral::read_reg!(periph, $instance, $register $([$offset])*, FIELD_C == 1), "Boolean expressions");
+        assert_others_zero(&*$instance, (*$instance).$register $([$offset])*.as_ptr());
+    };
+}
+
+macro_rules! write_reg_test_cases {
+    ($ty:ident, $instance:expr, $register:ident $([$offset:expr])*) => {
+        ral::write_reg!(periph, $instance, $register $([$offset])*, FIELD_C: 1);
+        assert_eq!((*$instance).$register $([$offset])*.read(), 1 << C_OFFSET, "1:1 write:field (C)");
+
+        let value: $ty = 2;
+        ral::write_reg!(periph, $instance, $register $([$offset])*, FIELD_A: value | 1, FIELD_B: Fast, FIELD_C: Enabled);
+        assert_eq!((*$instance).$register $([$offset])*.read(), 1 << C_OFFSET | 0b10 << B_OFFSET | 3, "1:N write:field");
+
+        ral::write_reg!(periph, $instance, $register $([$offset])*, $ty::MAX / 3);
+        assert_eq!((*$instance).$register $([$offset])*.read(), $ty::MAX / 3, "Direct write");
+        assert_others_zero(&*$instance, (*$instance).$register $([$offset])*.as_ptr());
+    };
+}
+
+macro_rules! modify_reg_test_cases {
+    ($ty:ident, $instance:expr, $register:ident $([$offset:expr])*) => {
+        ral::modify_reg!(periph, $instance, $register $([$offset])*, FIELD_C: 1);
+        assert_eq!((*$instance).$register $([$offset])*.read(), 1 << C_OFFSET, "RMW individual fields (C)");
+        ral::modify_reg!(periph, $instance, $register $([$offset])*, FIELD_B: 0b11, FIELD_A: A_MAX);
+        assert_eq!((*$instance).$register $([$offset])*.read(), $ty::MAX, "RMW individual fields (A, B)");
+        ral::modify_reg!(periph, $instance, $register $([$offset])*, FIELD_C: 0);
+        assert_eq!((*$instance).$register $([$offset])*.read(), $ty::MAX >> 1, "RMW individual fields (C)");
+
+        ral::modify_reg!(periph, $instance, $register $([$offset])*, |reg| reg << 1);
+        assert_eq!((*$instance).$register $([$offset])*.read(), $ty::MAX - 1, "RMW whole register");
+        assert_others_zero(&*$instance, (*$instance).$register $([$offset])*.as_ptr());
+    };
+}
+
+macro_rules! reset_reg_test_cases {
+    ($ty:ident, $instance:expr, $register:ident $([$offset:expr])*) => {
+        (*$instance).$register $([$offset])*.write($ty::MAX);
+        ral::reset_reg!(periph, $instance, INST, $register $([$offset])*);
+        assert_eq!((*$instance).$register $([$offset])*.read(), RESET, "Entire register");
+
+        (*$instance).$register $([$offset])*.write($ty::MAX);
+        ral::reset_reg!(periph, $instance, INST, $register $([$offset])*, FIELD_B);
+        assert_eq!(
+            (*$instance).$register $([$offset])*.read(),
+            $ty::MAX & !(0b11 << B_OFFSET) | (0b01 << B_OFFSET),
+            "Field in register (B)"
+        );
+        assert_others_zero(&*$instance, (*$instance).$register $([$offset])*.as_ptr());
+    };
+}
+
+/// Declares a module `$name` with a peripheral of `$ty` registers, and the tests.
+///
+/// `FIELD_A` is every bit below the top three, `FIELD_B` is the two bits
+/// above it, and `FIELD_C` is the most significant bit. A register wide
+/// enough may also declare `FIELD_D`, a 16-bit field at the given offset
+/// which overlaps `FIELD_A`.
+macro_rules! width_tests {
+    ($name:ident, $ty:ident $(, FIELD_D: $d_offset:expr)?) => {
+        mod $name {
+            use ral_registers as ral;
+
+            /// The largest value of `FIELD_A`.
+            const A_MAX: $ty = $ty::MAX >> 3;
+            const B_OFFSET: $ty = $ty::BITS as $ty - 3;
+            const C_OFFSET: $ty = $ty::BITS as $ty - 1;
+            const RESET: $ty = 1 << C_OFFSET | 0b01 << B_OFFSET | 5;
+
+            mod periph {
+                #[repr(C)]
+                pub struct RegisterBlock {
+                    pub MY_SCALAR: ral_registers::RWRegister<$ty>,
+                    pub MY_ARRAY: [ral_registers::RWRegister<$ty>; 4],
+                    pub MY_AFTER: ral_registers::RWRegister<$ty>,
+                }
+
+                pub mod MY_SCALAR {
+                    pub mod FIELD_A {
+                        pub const offset: $ty = 0;
+                        pub const mask: $ty = super::super::super::A_MAX << offset;
+                        pub mod R {}
+                        pub mod W {}
+                        pub mod RW {}
+                    }
+                    pub mod FIELD_B {
+                        pub const offset: $ty = super::super::super::B_OFFSET;
+                        pub const mask: $ty = 0b11 << offset;
+                        pub mod R {}
+                        pub mod W {}
+                        pub mod RW {
+                            pub const Fast: $ty = 0b10;
+                        }
+                    }
+                    pub mod FIELD_C {
+                        pub const offset: $ty = super::super::super::C_OFFSET;
+                        pub const mask: $ty = 1 << offset;
+                        pub mod R {}
+                        pub mod W {}
+                        pub mod RW {
+                            pub const Enabled: $ty = 1;
+                        }
+                    }
+                    $(
+                        pub mod FIELD_D {
+                            pub const offset: $ty = $d_offset;
+                            pub const mask: $ty = 0xFFFF << offset;
+                            pub mod R {}
+                            pub mod W {}
+                            pub mod RW {}
+                        }
+                    )?
+                }
+
+                pub mod MY_ARRAY {
+                    pub use super::MY_SCALAR::*;
+                }
+
+                pub mod MY_AFTER {}
+
+                /// A scalar reset value applies to every register of an array.
+                /// See `reset_values.rs` for per-element reset values.
+                pub struct ResetValues {
+                    pub MY_SCALAR: $ty,
+                    pub MY_ARRAY: $ty,
+                }
+
+                pub mod INST {
+                    pub const reset: super::ResetValues = super::ResetValues {
+                        MY_SCALAR: super::super::RESET,
+                        MY_ARRAY: super::super::RESET,
+                    };
+                }
+            }
+
+            fn register_block() -> periph::RegisterBlock {
+                // Safety: bitpattern of zero is fine.
+                use std::mem::MaybeUninit;
+                unsafe { MaybeUninit::zeroed().assume_init() }
+            }
+
+            struct Instance<'a> {
+                rb: &'a periph::RegisterBlock,
+            }
+
+            impl<'a> Instance<'a> {
+                fn new(rb: &'a periph::RegisterBlock) -> Self {
+                    Self { rb }
+                }
+            }
+
+            impl std::ops::Deref for Instance<'_> {
+                type Target = periph::RegisterBlock;
+                fn deref(&self) -> &Self::Target {
+                    self.rb
+                }
+            }
+
+            /// Checks that every register other than `target` still holds zero.
+            fn assert_others_zero(rb: &periph::RegisterBlock, target: *const $ty) {
+                let registers = std::iter::once(("MY_SCALAR", &rb.MY_SCALAR))
+                    .chain(rb.MY_ARRAY.iter().map(|register| ("MY_ARRAY", register)))
+                    .chain(std::iter::once(("MY_AFTER", &rb.MY_AFTER)));
+                for (name, register) in registers {
+                    if register.as_ptr() != target {
+                        assert_eq!(register.read(), 0, "{} was modified", name);
+                    }
+                }
+            }
+
+            #[test]
+            fn layout() {
+                use std::mem::{offset_of, size_of};
+                assert_eq!(size_of::<[ral::RWRegister<$ty>; 4]>(), 4 * size_of::<$ty>());
+                assert_eq!(offset_of!(periph::RegisterBlock, MY_AFTER), 5 * size_of::<$ty>());
+            }
+
+            #[test]
+            fn read_scalar() {
+                let rb = register_block();
+                read_reg_test_cases!($ty, &rb, MY_SCALAR);
+            }
+
+            #[test]
+            fn read_array_ref() {
+                let rb = register_block();
+                read_reg_test_cases!($ty, &rb, MY_ARRAY[1]);
+            }
+
+            #[test]
+            fn read_array_deref() {
+                let rb = register_block();
+                let inst = Instance::new(&rb);
+                read_reg_test_cases!($ty, inst, MY_ARRAY[2]);
+            }
+
+            #[test]
+            fn read_array_ptr() {
+                let ptr: *const _ = &register_block();
+                unsafe {
+                    read_reg_test_cases!($ty, ptr, MY_ARRAY[3]);
+                }
+            }
+
+            #[test]
+            fn write_scalar() {
+                let rb = register_block();
+                write_reg_test_cases!($ty, &rb, MY_SCALAR);
+            }
+
+            #[test]
+            fn write_array_ref() {
+                let rb = register_block();
+                write_reg_test_cases!($ty, &rb, MY_ARRAY[0]);
+            }
+
+            #[test]
+            fn write_array_deref() {
+                let rb = register_block();
+                let inst = Instance::new(&rb);
+                write_reg_test_cases!($ty, inst, MY_ARRAY[1]);
+            }
+
+            #[test]
+            fn write_array_ptr() {
+                let ptr: *const _ = &register_block();
+                unsafe {
+                    write_reg_test_cases!($ty, ptr, MY_ARRAY[3]);
+                }
+            }
+
+            #[test]
+            fn modify_scalar() {
+                let rb = register_block();
+                modify_reg_test_cases!($ty, &rb, MY_SCALAR);
+            }
+
+            #[test]
+            fn modify_array_ref() {
+                let rb = register_block();
+                modify_reg_test_cases!($ty, &rb, MY_ARRAY[0]);
+            }
+
+            #[test]
+            fn modify_array_deref() {
+                let rb = register_block();
+                let inst = Instance::new(&rb);
+                modify_reg_test_cases!($ty, inst, MY_ARRAY[2]);
+            }
+
+            #[test]
+            fn modify_array_ptr() {
+                let ptr: *const _ = &register_block();
+                unsafe {
+                    modify_reg_test_cases!($ty, ptr, MY_ARRAY[3]);
+                }
+            }
+
+            #[test]
+            fn reset_scalar() {
+                let rb = register_block();
+                reset_reg_test_cases!($ty, &rb, MY_SCALAR);
+            }
+
+            #[test]
+            fn reset_array_ref() {
+                let rb = register_block();
+                reset_reg_test_cases!($ty, &rb, MY_ARRAY[1]);
+            }
+
+            #[test]
+            fn reset_array_deref() {
+                let rb = register_block();
+                let inst = Instance::new(&rb);
+                reset_reg_test_cases!($ty, inst, MY_ARRAY[0]);
+            }
+
+            #[test]
+            fn reset_array_ptr() {
+                let ptr: *const _ = &register_block();
+                unsafe {
+                    reset_reg_test_cases!($ty, ptr, MY_ARRAY[3]);
+                }
+            }
+
+            #[test]
+            fn reset_all_elements() {
+                let rb = register_block();
+                ral::reset_all!(periph, &rb, INST, [MY_ARRAY]);
+                for register in rb.MY_ARRAY.iter() {
+                    assert_eq!(register.read(), RESET);
+                }
+                assert_eq!(rb.MY_SCALAR.read(), 0);
+                assert_eq!(rb.MY_AFTER.read(), 0);
+            }
+
+            #[test]
+            fn read_many_elements() {
+                let rb = register_block();
+                for (i, register) in rb.MY_ARRAY.iter().enumerate() {
+                    register.write(0x10 + i as $ty);
+                }
+                let values: [$ty; 3] = ral::read_many!(periph, &rb, MY_ARRAY, 1..4);
+                assert_eq!(values, [0x11, 0x12, 0x13]);
+            }
+
+            #[should_panic(expected = "MY_ARRAY index 4 out of bounds (len 4)")]
+            #[test]
+            fn write_array_out_of_bounds() {
+                let rb = register_block();
+                let index = 4;
+                ral::write_reg!(periph, &rb, MY_ARRAY[index], FIELD_A: 1);
+            }
+
+            $(
+                #[test]
+                fn middle_field() {
+                    const D_OFFSET: $ty = $d_offset;
+                    let rb = register_block();
+
+                    ral::write_reg!(periph, &rb, MY_ARRAY[1], FIELD_D: 0xABCD);
+                    assert_eq!(rb.MY_ARRAY[1].read(), 0xABCD << D_OFFSET, "Write");
+                    assert_eq!(ral::read_reg!(periph, &rb, MY_ARRAY[1], FIELD_D), 0xABCD, "Read");
+
+                    ral::modify_reg!(periph, &rb, MY_ARRAY[1], FIELD_C: 1, FIELD_D: 0x1234);
+                    assert_eq!(rb.MY_ARRAY[1].read(), 1 << C_OFFSET | 0x1234 << D_OFFSET, "Modify");
+
+                    rb.MY_ARRAY[1].write($ty::MAX);
+                    ral::reset_reg!(periph, &rb, INST, MY_ARRAY[1], FIELD_D);
+                    assert_eq!(rb.MY_ARRAY[1].read(), $ty::MAX & !(0xFFFF << D_OFFSET), "Reset");
+                    assert_others_zero(&rb, rb.MY_ARRAY[1].as_ptr());
+                }
+            )?
+        }
+    };
+}
+
+width_tests!(width_u8, u8);
+width_tests!(width_u16, u16);
+width_tests!(width_u32, u32);
+width_tests!(width_u64, u64, FIELD_D: 40);
@@ -0,0 +1,372 @@
+//! Tests for the methods on the register types.
+
+#![allow(non_snake_case)] // Register conventions.
+
+use ral_registers::{
+    AtomicRWRegister, RORegister, RORegisterBE, RWRegister, RWRegisterBE, ReadRegister,
+    UnsafeRORegister, UnsafeRWRegister, UnsafeWORegister, WORegister, WORegisterBE, WriteRegister,
+};
+
+#[repr(C)]
+struct RegisterBlock {
+    MY_RW: RWRegister<u32>,
+    MY_RO: RORegister<u32>,
+    MY_WO: WORegister<u32>,
+    MY_UNSAFE_RW: UnsafeRWRegister<u32>,
+    MY_UNSAFE_RO: UnsafeRORegister<u32>,
+    MY_UNSAFE_WO: UnsafeWORegister<u32>,
+    MY_ARRAY: [RWRegister<u32>; 3],
+}
+
+fn register_block() -> RegisterBlock {
+    // Safety: bitpattern of zero is fine.
+    use std::mem::MaybeUninit;
+    unsafe { MaybeUninit::zeroed().assume_init() }
+}
+
+#[test]
+fn as_ptr() {
+    let inst = register_block();
+    let base = &inst as *const RegisterBlock as usize;
+
+    assert_eq!(inst.MY_RW.as_ptr() as usize - base, 0);
+    assert_eq!(inst.MY_RO.as_ptr() as usize - base, 4);
+    assert_eq!(inst.MY_WO.as_ptr() as usize - base, 8);
+    assert_eq!(inst.MY_UNSAFE_RW.as_ptr() as usize - base, 12);
+    assert_eq!(inst.MY_UNSAFE_RO.as_ptr() as usize - base, 16);
+    assert_eq!(inst.MY_UNSAFE_WO.as_ptr() as usize - base, 20);
+
+    assert_eq!(
+        inst.MY_ARRAY[1].as_ptr() as usize - inst.MY_ARRAY[0].as_ptr() as usize,
+        4
+    );
+}
+
+#[test]
+fn as_mut_ptr() {
+    let inst = register_block();
+    assert_eq!(inst.MY_RW.as_mut_ptr() as *const u32, inst.MY_RW.as_ptr());
+
+    // Safety: the register block is owned by this test.
+    unsafe { inst.MY_RO.as_mut_ptr().write(42) };
+    assert_eq!(inst.MY_RO.read(), 42);
+
+    inst.MY_WO.write(7);
+    assert_eq!(unsafe { inst.MY_WO.as_ptr().read() }, 7);
+}
+
+#[test]
+fn into_inner() {
+    let inst = register_block();
+    inst.MY_RW.write(1);
+    unsafe { inst.MY_RO.as_mut_ptr().write(2) };
+    inst.MY_WO.write(3);
+    assert_eq!(inst.MY_RW.into_inner(), 1);
+    assert_eq!(inst.MY_RO.into_inner(), 2);
+    assert_eq!(inst.MY_WO.into_inner(), 3);
+    assert_eq!(inst.MY_UNSAFE_RW.into_inner(), 0);
+    assert_eq!(inst.MY_UNSAFE_RO.into_inner(), 0);
+    assert_eq!(inst.MY_UNSAFE_WO.into_inner(), 0);
+
+    assert_eq!(RWRegisterBE::new(0x0102_0304u32).into_inner(), 0x0102_0304);
+    assert_eq!(RORegisterBE::new(5u16).into_inner(), 5);
+    assert_eq!(WORegisterBE::new(6u8).into_inner(), 6);
+
+    let atomic = AtomicRWRegister::new(7u64);
+    atomic.write(8, core::sync::atomic::Ordering::SeqCst);
+    assert_eq!(atomic.into_inner(), 8);
+}
+
+#[test]
+fn from_ptr() {
+    let value = Box::new(0x1234_5678u32);
+    let ptr = Box::into_raw(value);
+
+    // Safety: the box is owned by this test, and only accessed through these views.
+    let ro = unsafe { RORegister::from_ptr(ptr) };
+    let rw = unsafe { RWRegister::from_mut_ptr(ptr) };
+    let wo = unsafe { WORegister::from_mut_ptr(ptr) };
+    assert_eq!(ro.read(), 0x1234_5678);
+    assert_eq!(ro.as_ptr(), ptr as *const u32);
+
+    rw.write(42);
+    assert_eq!(ro.read(), 42);
+    wo.write(7);
+    assert_eq!(rw.read(), 7);
+
+    // Safety: as above.
+    let unsafe_rw = unsafe { UnsafeRWRegister::from_ptr(ptr) };
+    let unsafe_ro = unsafe { UnsafeRORegister::from_ptr(ptr) };
+    let unsafe_wo = unsafe { UnsafeWORegister::from_mut_ptr(ptr) };
+    unsafe {
+        unsafe_wo.write(9);
+        assert_eq!(unsafe_ro.read(), 9);
+        assert_eq!(unsafe_rw.read(), 9);
+    }
+
+    drop(unsafe { Box::from_raw(ptr) });
+}
+
+#[test]
+fn new() {
+    let inst = RegisterBlock {
+        MY_RW: RWRegister::new(1),
+        MY_RO: RORegister::new(2),
+        MY_WO: WORegister::new(3),
+        MY_UNSAFE_RW: UnsafeRWRegister::new(4),
+        MY_UNSAFE_RO: UnsafeRORegister::new(5),
+        MY_UNSAFE_WO: UnsafeWORegister::new(6),
+        MY_ARRAY: [RWRegister::new(7), RWRegister::new(8), RWRegister::new(9)],
+    };
+
+    assert_eq!(inst.MY_RW.read(), 1);
+    assert_eq!(inst.MY_RO.read(), 2);
+    assert_eq!(unsafe { inst.MY_WO.as_ptr().read() }, 3);
+    assert_eq!(unsafe { inst.MY_UNSAFE_RW.read() }, 4);
+    assert_eq!(unsafe { inst.MY_UNSAFE_RO.read() }, 5);
+    assert_eq!(unsafe { inst.MY_UNSAFE_WO.as_ptr().read() }, 6);
+    assert_eq!(
+        [
+            inst.MY_ARRAY[0].read(),
+            inst.MY_ARRAY[1].read(),
+            inst.MY_ARRAY[2].read()
+        ],
+        [7, 8, 9]
+    );
+}
+
+#[test]
+fn new_in_const() {
+    const fn make() -> RWRegister<u32> {
+        RWRegister::new(0x2A)
+    }
+    let register = make();
+    assert_eq!(register.read(), 0x2A);
+}
+
+#[test]
+fn debug() {
+    let inst = RegisterBlock {
+        MY_RW: RWRegister::new(0x2A),
+        MY_RO: RORegister::new(0xDEAD_BEEF),
+        MY_WO: WORegister::new(3),
+        MY_UNSAFE_RW: UnsafeRWRegister::new(4),
+        MY_UNSAFE_RO: UnsafeRORegister::new(5),
+        MY_UNSAFE_WO: UnsafeWORegister::new(6),
+        MY_ARRAY: [RWRegister::new(7), RWRegister::new(8), RWRegister::new(9)],
+    };
+
+    assert_eq!(format!("{:?}", inst.MY_RW), "RWRegister(0x0000002a)");
+    assert_eq!(format!("{:?}", inst.MY_RO), "RORegister(0xdeadbeef)");
+    assert_eq!(format!("{:?}", inst.MY_WO), "WORegister(<write-only>)");
+    assert_eq!(
+        format!("{:?}", inst.MY_UNSAFE_RW),
+        "UnsafeRWRegister(<unsafe>)"
+    );
+    assert_eq!(
+        format!("{:?}", inst.MY_UNSAFE_RO),
+        "UnsafeRORegister(<unsafe>)"
+    );
+    assert_eq!(
+        format!("{:?}", inst.MY_UNSAFE_WO),
+        "UnsafeWORegister(<write-only>)"
+    );
+    assert_eq!(
+        format!("{:?}", inst.MY_ARRAY),
+        "[RWRegister(0x00000007), RWRegister(0x00000008), RWRegister(0x00000009)]"
+    );
+
+    assert_eq!(format!("{:?}", RWRegister::new(0x2Au8)), "RWRegister(0x2a)");
+    assert_eq!(
+        format!("{:?}", RORegister::new(0x2Au16)),
+        "RORegister(0x002a)"
+    );
+}
+
+#[test]
+fn compare_with_value() {
+    let mut inst = register_block();
+    inst.MY_RW = RWRegister::new(5);
+    inst.MY_RO = RORegister::new(0x2A);
+    inst.MY_ARRAY[1] = RWRegister::new(7);
+
+    assert!(inst.MY_RW == 5);
+    assert!(inst.MY_RW != 0);
+    assert!(inst.MY_RO == 0x2A);
+    assert!(inst.MY_ARRAY[1] == 7);
+    assert!(inst.MY_ARRAY[0] == 0);
+
+    inst.MY_RW.write(0);
+    assert!(inst.MY_RW == 0);
+    assert!(RWRegister::new(0xFFu8) == u8::MAX);
+}
+
+#[test]
+fn set_bits() {
+    let inst = register_block();
+    inst.MY_RW.write(0x0F00_0001);
+    inst.MY_RW.set_bits(0x8000_0011);
+    assert_eq!(inst.MY_RW.read(), 0x8F00_0011);
+}
+
+#[test]
+fn clear_bits() {
+    let inst = register_block();
+    inst.MY_RW.write(0x0F00_0011);
+    inst.MY_RW.clear_bits(0x0300_0001);
+    assert_eq!(inst.MY_RW.read(), 0x0C00_0010);
+}
+
+#[test]
+fn toggle_bits() {
+    let inst = register_block();
+    inst.MY_RW.write(0x0F00_0011);
+    inst.MY_RW.toggle_bits(0xF000_0001);
+    assert_eq!(inst.MY_RW.read(), 0xFF00_0010);
+    inst.MY_RW.toggle_bits(0xF000_0001);
+    assert_eq!(inst.MY_RW.read(), 0x0F00_0011);
+}
+
+#[test]
+fn replace() {
+    let inst = register_block();
+    inst.MY_RW.write(0x1234_5678);
+    assert_eq!(inst.MY_RW.replace(u32::MAX), 0x1234_5678);
+    assert_eq!(inst.MY_RW.read(), u32::MAX);
+    assert_eq!(inst.MY_RW.replace(0x1234_5678), u32::MAX);
+    assert_eq!(inst.MY_RW.read(), 0x1234_5678);
+}
+
+#[test]
+fn read_write() {
+    let inst = register_block();
+    inst.MY_RW.write(0x1234_5678);
+    assert_eq!(inst.MY_RW.read_write(0xAAAA_5555), 0x1234_5678);
+    assert_eq!(inst.MY_RW.read(), 0xAAAA_5555);
+
+    unsafe {
+        inst.MY_UNSAFE_RW.write(7);
+        assert_eq!(inst.MY_UNSAFE_RW.read_write(8), 7);
+        assert_eq!(inst.MY_UNSAFE_RW.read(), 8);
+    }
+}
+
+#[test]
+fn update() {
+    let inst = register_block();
+    inst.MY_ARRAY[1].write(0x10);
+    assert_eq!(inst.MY_ARRAY[1].update(|v| v | 0x1), 0x11);
+    assert_eq!(inst.MY_ARRAY[1].read(), 0x11);
+
+    unsafe {
+        inst.MY_UNSAFE_RW.write(0x10);
+        assert_eq!(inst.MY_UNSAFE_RW.update(|v| v << 4), 0x100);
+        assert_eq!(inst.MY_UNSAFE_RW.read(), 0x100);
+    }
+}
+
+#[test]
+fn modify_returning() {
+    let inst = register_block();
+    inst.MY_ARRAY[1].write(0x10);
+    assert!(!inst.MY_ARRAY[1].modify_returning(|v| (v | 0x1, v & 0x1 != 0)));
+    assert!(inst.MY_ARRAY[1].modify_returning(|v| (v | 0x1, v & 0x1 != 0)));
+    assert_eq!(inst.MY_ARRAY[1].read(), 0x11);
+
+    unsafe {
+        inst.MY_UNSAFE_RW.write(0x10);
+        assert_eq!(inst.MY_UNSAFE_RW.modify_returning(|v| (v << 4, v)), 0x10);
+        assert_eq!(inst.MY_UNSAFE_RW.read(), 0x100);
+    }
+}
+
+#[test]
+fn bit() {
+    let inst = register_block();
+    inst.MY_RW.write(1 | 1 << 31);
+    assert!(inst.MY_RW.bit(0));
+    assert!(!inst.MY_RW.bit(1));
+    assert!(inst.MY_RW.bit(31));
+
+    unsafe { inst.MY_RO.as_mut_ptr().write(1 << 31) };
+    assert!(!inst.MY_RO.bit(0));
+    assert!(inst.MY_RO.bit(31));
+}
+
+#[test]
+fn set_bit() {
+    let inst = register_block();
+    inst.MY_RW.write(0x10);
+    inst.MY_RW.set_bit(0, true);
+    assert_eq!(inst.MY_RW.read(), 0x11);
+    inst.MY_RW.set_bit(31, true);
+    assert_eq!(inst.MY_RW.read(), 0x8000_0011);
+    inst.MY_RW.set_bit(0, false);
+    inst.MY_RW.set_bit(31, false);
+    assert_eq!(inst.MY_RW.read(), 0x10);
+}
+
+#[test]
+#[should_panic(expected = "bit 32 is out of range for a register 32 bits wide")]
+fn bit_out_of_range() {
+    let inst = register_block();
+    inst.MY_RW.set_bit(32, true);
+}
+
+#[test]
+#[should_panic(expected = "bit 8 is out of range for a register 8 bits wide")]
+fn bit_out_of_range_u8() {
+    let register = RORegister::new(0u8);
+    register.bit(8);
+}
+
+fn read_generic<R: ReadRegister<u32>>(register: &R) -> u32 {
+    register.read()
+}
+
+fn write_generic<R: WriteRegister<u32>>(register: &R, val: u32) {
+    register.write(val)
+}
+
+#[test]
+fn traits() {
+    let inst = register_block();
+
+    write_generic(&inst.MY_RW, 0x12);
+    assert_eq!(read_generic(&inst.MY_RW), 0x12);
+
+    unsafe { inst.MY_RO.as_mut_ptr().write(0x34) };
+    assert_eq!(read_generic(&inst.MY_RO), 0x34);
+
+    write_generic(&inst.MY_WO, 0x56);
+    assert_eq!(unsafe { inst.MY_WO.as_ptr().read() }, 0x56);
+
+    let registers: [&dyn ReadRegister<u32>; 2] = [&inst.MY_RW, &inst.MY_RO];
+    let values: Vec<u32> = registers.iter().map(|r| r.read()).collect();
+    assert_eq!(values, [0x12, 0x34]);
+}
+
+#[test]
+fn layout() {
+    use core::mem::{align_of, size_of};
+
+    macro_rules! check {
+        ($($ty:ty),+) => {$(
+            assert_eq!(size_of::<RWRegister<$ty>>(), size_of::<$ty>());
+            assert_eq!(size_of::<RORegister<$ty>>(), size_of::<$ty>());
+            assert_eq!(size_of::<WORegister<$ty>>(), size_of::<$ty>());
+            assert_eq!(size_of::<UnsafeRWRegister<$ty>>(), size_of::<$ty>());
+            assert_eq!(size_of::<UnsafeRORegister<$ty>>(), size_of::<$ty>());
+            assert_eq!(size_of::<UnsafeWORegister<$ty>>(), size_of::<$ty>());
+            assert_eq!(align_of::<RWRegister<$ty>>(), align_of::<$ty>());
+            assert_eq!(align_of::<RORegister<$ty>>(), align_of::<$ty>());
+            assert_eq!(align_of::<WORegister<$ty>>(), align_of::<$ty>());
+            assert_eq!(align_of::<UnsafeRWRegister<$ty>>(), align_of::<$ty>());
+            assert_eq!(align_of::<UnsafeRORegister<$ty>>(), align_of::<$ty>());
+            assert_eq!(align_of::<UnsafeWORegister<$ty>>(), align_of::<$ty>());
+        )+};
+    }
+
+    check!(u8, u16, u32, u64);
+    assert_eq!(size_of::<RegisterBlock>(), 9 * size_of::<u32>());
+}
@@ -0,0 +1,197 @@
+//! Tests that reset macros work with reset values that are
+//! arrays, where each register of an array resets to the value
+//! at its own index.
+
+#![allow(non_upper_case_globals, non_snake_case)] // Macro conventions.
+
+use ral_registers as ral;
+
+mod periph {
+    #[repr(C)]
+    pub struct RegisterBlock {
+        pub MY_SCALAR: ral_registers::RWRegister<u32>,
+        pub MY_ARRAY: [ral_registers::RWRegister<u32>; 3],
+        pub MY_MATRIX: [[ral_registers::RWRegister<u32>; 2]; 2],
+    }
+
+    pub mod MY_SCALAR {}
+
+    pub mod MY_ARRAY {
+        pub mod FIELD_A {
+            pub const offset: u32 = 0;
+            pub const mask: u32 = 0xFF << offset;
+            pub mod R {}
+            pub mod W {}
+            pub mod RW {}
+        }
+        ral_registers::impl_register_fields!(MY_ARRAY, [FIELD_A]);
+    }
+
+    pub mod MY_MATRIX {}
+
+    pub struct ResetValues {
+        pub MY_SCALAR: u32,
+        pub MY_ARRAY: [u32; 3],
+        pub MY_MATRIX: [[u32; 2]; 2],
+    }
+
+    pub mod INST {
+        pub const reset: super::ResetValues = super::ResetValues {
+            MY_SCALAR: 7,
+            MY_ARRAY: [0x1111_1111, 0x2222_2222, 0x3333_3333],
+            MY_MATRIX: [[0x00, 0x01], [0x10, 0x11]],
+        };
+    }
+}
+
+fn register_block() -> periph::RegisterBlock {
+    // Safety: bitpattern of zero is fine.
+    use std::mem::MaybeUninit;
+    unsafe { MaybeUninit::zeroed().assume_init() }
+}
+
+#[test]
+fn reset_element() {
+    let rb = register_block();
+    let value = ral::reset_reg!(periph, &rb, INST, MY_ARRAY[1]);
+    assert_eq!(value, 0x2222_2222);
+    assert_eq!(rb.MY_ARRAY[0].read(), 0);
+    assert_eq!(rb.MY_ARRAY[1].read(), 0x2222_2222);
+    assert_eq!(rb.MY_ARRAY[2].read(), 0);
+
+    let index = 2;
+    ral::reset_reg!(periph, &rb, INST, MY_ARRAY[index]);
+    assert_eq!(rb.MY_ARRAY[2].read(), 0x3333_3333);
+
+    ral::reset_reg!(periph, &rb, INST, MY_SCALAR);
+    assert_eq!(rb.MY_SCALAR.read(), 7);
+}
+
+#[test]
+fn reset_element_field() {
+    let rb = register_block();
+    rb.MY_ARRAY[2].write(u32::MAX);
+    let value = ral::reset_reg!(periph, &rb, INST, MY_ARRAY[2], FIELD_A);
+    assert_eq!(value, 0xFFFF_FF33);
+    assert_eq!(rb.MY_ARRAY[2].read(), 0xFFFF_FF33);
+}
+
+#[test]
+fn reset_every_element() {
+    let rb = register_block();
+    let value = ral::reset_reg!(periph, &rb, INST, MY_ARRAY[..]);
+    assert_eq!(value, [0x1111_1111, 0x2222_2222, 0x3333_3333]);
+    for (register, reset) in rb.MY_ARRAY.iter().zip(value) {
+        assert_eq!(register.read(), reset);
+    }
+
+    for register in rb.MY_ARRAY.iter() {
+        register.write(u32::MAX);
+    }
+    ral::reset_reg!(periph, &rb, INST, MY_ARRAY[..], FIELD_A);
+    assert_eq!(rb.MY_ARRAY[0].read(), 0xFFFF_FF11);
+    assert_eq!(rb.MY_ARRAY[1].read(), 0xFFFF_FF22);
+    assert_eq!(rb.MY_ARRAY[2].read(), 0xFFFF_FF33);
+}
+
+#[test]
+fn reset_matrix() {
+    let rb = register_block();
+    ral::reset_reg!(periph, &rb, INST, MY_MATRIX[1][0]);
+    assert_eq!(rb.MY_MATRIX[1][0].read(), 0x10);
+    assert_eq!(rb.MY_MATRIX[0][0].read(), 0);
+
+    ral::reset_all!(periph, &rb, INST, [MY_MATRIX[0], MY_MATRIX[1]]);
+    assert_eq!(rb.MY_MATRIX[0][0].read(), 0x00);
+    assert_eq!(rb.MY_MATRIX[0][1].read(), 0x01);
+    assert_eq!(rb.MY_MATRIX[1][0].read(), 0x10);
+    assert_eq!(rb.MY_MATRIX[1][1].read(), 0x11);
+}
+
+#[test]
+fn reset_evaluates_index_once() {
+    let rb = register_block();
+    let calls = std::cell::Cell::new(0);
+    let index = |index: usize| {
+        calls.set(calls.get() + 1);
+        index
+    };
+
+    ral::reset_reg!(periph, &rb, INST, MY_ARRAY[index(1)]);
+    assert_eq!(calls.get(), 1);
+    ral::reset_reg!(periph, &rb, INST, MY_ARRAY[index(2)], FIELD_A);
+    assert_eq!(calls.get(), 2);
+    ral::reset_reg!(periph, &rb, INST, MY_MATRIX[index(1)][index(0)]);
+    assert_eq!(calls.get(), 4);
+    ral::reset_all!(periph, &rb, INST, [MY_SCALAR, MY_MATRIX[index(0)]]);
+    assert_eq!(calls.get(), 5);
+    assert_eq!(
+        [
+            rb.MY_ARRAY[0].read(),
+            rb.MY_ARRAY[1].read(),
+            rb.MY_ARRAY[2].read()
+        ],
+        [0, 0x2222_2222, 0x33]
+    );
+    assert_eq!(rb.MY_MATRIX[1][0].read(), 0x10);
+    assert_eq!(rb.MY_MATRIX[0][1].read(), 0x01);
+
+    // Each element is written with its own reset value.
+    let rb = register_block();
+    let mut indices = vec![0, 2].into_iter();
+    ral::reset_reg!(periph, &rb, INST, MY_ARRAY[indices.next().unwrap()]);
+    ral::reset_reg!(periph, &rb, INST, MY_ARRAY[indices.next().unwrap()]);
+    assert_eq!(rb.MY_ARRAY[0].read(), 0x1111_1111);
+    assert_eq!(rb.MY_ARRAY[1].read(), 0);
+    assert_eq!(rb.MY_ARRAY[2].read(), 0x3333_3333);
+}
+
+#[test]
+fn reset_all_and_assert() {
+    let rb = register_block();
+    ral::reset_all!(periph, &rb, INST, [MY_SCALAR, MY_ARRAY]);
+    ral::assert_reset!(
+        periph,
+        &rb,
+        INST,
+        [MY_SCALAR, MY_ARRAY[0], MY_ARRAY[1], MY_ARRAY[2]]
+    );
+}
+
+#[should_panic(expected = "MY_ARRAY[1] is 0x0, but its reset value is 0x22222222")]
+#[test]
+fn assert_reset_element() {
+    let rb = register_block();
+    ral::reset_reg!(periph, &rb, INST, MY_ARRAY[0]);
+    ral::assert_reset!(periph, &rb, INST, [MY_ARRAY[0], MY_ARRAY[1]]);
+}
+
+#[should_panic(expected = "MY_ARRAY[2] is 0x0, but its reset value is 0x33333333")]
+#[test]
+fn assert_reset_runtime_index() {
+    let rb = register_block();
+    ral::reset_reg!(periph, &rb, INST, MY_ARRAY[0]);
+    ral::reset_reg!(periph, &rb, INST, MY_ARRAY[1]);
+    for i in 0..3 {
+        ral::assert_reset!(periph, &rb, INST, [MY_ARRAY[i]]);
+    }
+}
+
+#[should_panic(expected = "MY_MATRIX[1][0] is 0x0, but its reset value is 0x10")]
+#[test]
+fn assert_reset_evaluates_index_once() {
+    let rb = register_block();
+    ral::reset_all!(periph, &rb, INST, [MY_MATRIX[0]]);
+    let mut rows = vec![0, 1].into_iter();
+    for _ in 0..2 {
+        ral::assert_reset!(periph, &rb, INST, [MY_MATRIX[rows.next().unwrap()][0]]);
+    }
+}
+
+#[should_panic(expected = "MY_ARRAY index 3 out of bounds (len 3)")]
+#[test]
+fn reset_element_out_of_bounds() {
+    let rb = register_block();
+    let index = 3;
+    ral::reset_reg!(periph, &rb, INST, MY_ARRAY[index]);
+}
@@ -0,0 +1,125 @@
+//! Tests for modifying a register through SET and CLR alias registers.
+
+#![allow(non_upper_case_globals, non_snake_case)] // Macro conventions.
+
+use ral_registers as ral;
+use std::cell::Cell;
+use std::rc::Rc;
+
+/// The state of a simulated register with SET and CLR aliases.
+#[derive(Default)]
+struct State {
+    value: Cell<u32>,
+    /// Every write to the SET and CLR registers, in order.
+    writes: Cell<Vec<(&'static str, u32)>>,
+}
+
+impl State {
+    fn log(&self, name: &'static str, value: u32) {
+        let mut writes = self.writes.take();
+        writes.push((name, value));
+        self.writes.set(writes);
+    }
+}
+
+/// Writing 1s sets bits in the register.
+pub struct SetRegister(Rc<State>);
+
+impl SetRegister {
+    pub fn write(&self, value: u32) {
+        self.0.log("SET", value);
+        self.0.value.set(self.0.value.get() | value);
+    }
+}
+
+/// Writing 1s clears bits in the register.
+pub struct ClrRegister(Rc<State>);
+
+impl ClrRegister {
+    pub fn write(&self, value: u32) {
+        self.0.log("CLR", value);
+        self.0.value.set(self.0.value.get() & !value);
+    }
+}
+
+/// `DIR` has no register in the block, so `modify_atomic!` can't touch it.
+mod periph {
+    pub struct RegisterBlock {
+        pub DIR_SET: super::SetRegister,
+        pub DIR_CLR: super::ClrRegister,
+    }
+
+    pub mod DIR {
+        pub mod PIN3 {
+            pub const offset: u32 = 3;
+            pub const mask: u32 = 1 << offset;
+            pub mod R {}
+            pub mod W {}
+            pub mod RW {}
+        }
+        pub mod PIN4 {
+            pub const offset: u32 = 4;
+            pub const mask: u32 = 1 << offset;
+            pub mod R {}
+            pub mod W {}
+            pub mod RW {}
+        }
+        pub mod MODE {
+            pub const offset: u32 = 8;
+            pub const mask: u32 = 0b11 << offset;
+            pub mod R {}
+            pub mod W {
+                pub const Fast: u32 = 0b10;
+            }
+            pub mod RW {}
+        }
+    }
+}
+
+fn register_block() -> (periph::RegisterBlock, Rc<State>) {
+    let state = Rc::new(State::default());
+    let rb = periph::RegisterBlock {
+        DIR_SET: SetRegister(state.clone()),
+        DIR_CLR: ClrRegister(state.clone()),
+    };
+    (rb, state)
+}
+
+#[test]
+fn set_and_clear() {
+    let (rb, state) = register_block();
+    state.value.set(0xF000_0010);
+
+    ral::modify_atomic!(periph, &rb, DIR, DIR_SET, DIR_CLR, PIN3: 1, PIN4: 0);
+    assert_eq!(state.value.get(), 0xF000_0008);
+    assert_eq!(state.writes.take(), [("SET", 1 << 3), ("CLR", 1 << 4)]);
+}
+
+#[test]
+fn set_only() {
+    let (rb, state) = register_block();
+    ral::modify_atomic!(periph, &rb, DIR, DIR_SET, DIR_CLR, PIN3: 1, PIN4: 1);
+    assert_eq!(state.value.get(), 0b11 << 3);
+    assert_eq!(state.writes.take(), [("SET", 0b11 << 3)]);
+}
+
+#[test]
+fn clear_only() {
+    let (rb, state) = register_block();
+    state.value.set(u32::MAX);
+    ral::modify_atomic!(periph, &rb, DIR, DIR_SET, DIR_CLR, PIN4: 0);
+    assert_eq!(state.value.get(), !(1 << 4));
+    assert_eq!(state.writes.take(), [("CLR", 1 << 4)]);
+}
+
+#[test]
+fn multi_bit_field() {
+    let (rb, state) = register_block();
+    state.value.set(0b01 << 8);
+    ral::modify_atomic!(periph, &rb, DIR, DIR_SET, DIR_CLR, MODE: Fast);
+    assert_eq!(state.value.get(), 0b10 << 8);
+    assert_eq!(
+        state.writes.take(),
+        [("SET", 0b10 << 8), ("CLR", 0b01 << 8)]
+    );
+}
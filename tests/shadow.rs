@@ -0,0 +1,59 @@
+//! Tests for shadowing a write-only register.
+
+#![allow(non_upper_case_globals, non_snake_case)] // Macro conventions.
+
+use ral_registers as ral;
+use ral_registers::{Shadow, WORegister};
+
+mod periph {
+    #[repr(C)]
+    pub struct RegisterBlock {
+        pub MY_WO: ral_registers::WORegister<u32>,
+    }
+
+    pub mod MY_WO {
+        pub mod FIELD_A {
+            pub const offset: u32 = 0;
+            pub const mask: u32 = 0x7F << offset;
+            pub mod R {}
+            pub mod W {}
+            pub mod RW {}
+        }
+        pub mod FIELD_B {
+            pub const offset: u32 = 27;
+            pub const mask: u32 = 0b11 << offset;
+            pub mod R {}
+            pub mod W {}
+            pub mod RW {
+                pub const Fast: u32 = 0b10;
+            }
+        }
+    }
+}
+
+#[test]
+fn modify_field() {
+    let rb = periph::RegisterBlock {
+        MY_WO: WORegister::new(0),
+    };
+    let shadow = Shadow::new(ral::reg_ref!(periph, &rb, MY_WO), 0x55);
+
+    let value = ral::modify_reg_ref!(periph, MY_WO, &shadow, FIELD_B: Fast);
+    assert_eq!(value, 0b10 << 27 | 0x55);
+    assert_eq!(shadow.read(), value);
+    assert_eq!(rb.MY_WO.into_inner(), value);
+}
+
+#[test]
+fn read_and_write() {
+    let register = WORegister::new(0u32);
+    let shadow = Shadow::new(&register, 0);
+
+    ral::write_reg_ref!(periph, MY_WO, &shadow, FIELD_A: 3, FIELD_B: 1);
+    assert_eq!(ral::read_reg_ref!(periph, MY_WO, &shadow, FIELD_A), 3);
+    assert!(ral::read_reg_ref!(periph, MY_WO, &shadow, FIELD_B == 1));
+    ral::modify_reg_ref!(periph, MY_WO, &shadow, FIELD_A: |a| a + 1);
+    assert_eq!(shadow.read(), 1 << 27 | 4);
+    assert!(core::ptr::eq(shadow.register(), &register));
+    assert_eq!(format!("{:?}", shadow), "Shadow(0x08000004)");
+}
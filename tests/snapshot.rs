@@ -0,0 +1,122 @@
+//! Tests for serializable register snapshots.
+
+#![cfg(feature = "serde")]
+#![allow(non_upper_case_globals, non_snake_case)] // Macro conventions.
+
+use ral_registers as ral;
+use ral_registers::snapshot::RawSnapshot;
+
+mod periph {
+    #[repr(C)]
+    pub struct RegisterBlock {
+        pub MY_SCALAR: ral_registers::RWRegister<u32>,
+        pub MY_ARRAY: [ral_registers::RWRegister<u32>; 3],
+    }
+
+    pub mod MY_SCALAR {
+        pub mod FIELD_A {
+            pub const offset: u32 = 0;
+            pub const mask: u32 = 0x7F << offset;
+            pub mod R {}
+            pub mod W {}
+            pub mod RW {}
+        }
+        pub mod FIELD_B {
+            pub const offset: u32 = 27;
+            pub const mask: u32 = 0b11 << offset;
+            pub mod R {}
+            pub mod W {}
+            pub mod RW {}
+        }
+    }
+
+    pub use MY_SCALAR as MY_ARRAY;
+}
+
+fn register_block() -> periph::RegisterBlock {
+    periph::RegisterBlock {
+        MY_SCALAR: ral::RWRegister::new(0),
+        MY_ARRAY: [
+            ral::RWRegister::new(0),
+            ral::RWRegister::new(0),
+            ral::RWRegister::new(0),
+        ],
+    }
+}
+
+#[test]
+fn snapshot() {
+    let rb = register_block();
+    rb.MY_ARRAY[1].write((0b11 << 27) | 0x100 | 0x7F);
+    let snapshot = ral::snapshot_reg!(periph, &rb, MY_ARRAY[1], FIELD_A, FIELD_B);
+    assert_eq!(*snapshot.value(), (0b11 << 27) | 0x100 | 0x7F);
+    assert_eq!(snapshot.fields(), [("FIELD_A", 0x7F), ("FIELD_B", 0b11)]);
+}
+
+#[test]
+fn json_round_trip() {
+    let rb = register_block();
+    rb.MY_SCALAR.write((0b10 << 27) | 0x100 | 0x55);
+    let snapshot = ral::snapshot_reg!(periph, &rb, MY_SCALAR, FIELD_A, FIELD_B);
+
+    let json = serde_json::to_string(&snapshot).unwrap();
+    assert_eq!(
+        json,
+        r#"{"value":268435797,"fields":{"FIELD_A":85,"FIELD_B":2}}"#
+    );
+
+    let raw: RawSnapshot<u32> = serde_json::from_str(&json).unwrap();
+    assert_eq!(raw.value, *snapshot.value());
+
+    rb.MY_SCALAR.write(0);
+    ral::write_reg!(periph, &rb, MY_SCALAR, raw.value);
+    assert_eq!(rb.MY_SCALAR.read(), (0b10 << 27) | 0x100 | 0x55);
+}
+
+#[test]
+fn snapshot_all() {
+    let rb = register_block();
+    rb.MY_SCALAR.write(7);
+    rb.MY_ARRAY[2].write(0x100);
+    let index = 2;
+    let snapshot = ral::snapshot_all!(periph, &rb, [MY_SCALAR, MY_ARRAY[0], MY_ARRAY[index]]);
+    assert_eq!(
+        snapshot.registers(),
+        [
+            ("MY_SCALAR", 7),
+            ("MY_ARRAY[0]", 0),
+            ("MY_ARRAY[index]", 0x100)
+        ]
+    );
+    assert_eq!(
+        serde_json::to_string(&snapshot).unwrap(),
+        r#"{"MY_SCALAR":7,"MY_ARRAY[0]":0,"MY_ARRAY[index]":256}"#
+    );
+}
+
+#[test]
+fn diff() {
+    let rb = register_block();
+    rb.MY_SCALAR.write(7);
+    let before = ral::snapshot_all!(periph, &rb, [MY_SCALAR, MY_ARRAY[0], MY_ARRAY[1]]);
+    ral::modify_reg!(periph, &rb, MY_ARRAY[1], FIELD_B: 0b10);
+    let after = ral::snapshot_all!(periph, &rb, [MY_SCALAR, MY_ARRAY[0], MY_ARRAY[1]]);
+
+    let diff = before.diff(&after);
+    assert_eq!(diff.len(), 1);
+    assert_eq!(diff.as_slice(), [("MY_ARRAY[1]", 0, 0b10 << 27)]);
+    assert!(before.diff(&before).is_empty());
+}
+
+#[test]
+fn diff_snapshot() {
+    let rb = register_block();
+    rb.MY_ARRAY[0].write(1);
+    let diff = ral::diff_snapshot!(periph, &rb, [MY_SCALAR, MY_ARRAY[0], MY_ARRAY[2]], {
+        ral::write_reg!(periph, &rb, MY_SCALAR, FIELD_A: 0x55);
+        ral::write_reg!(periph, &rb, MY_ARRAY[0], 1);
+        ral::write_reg!(periph, &rb, MY_ARRAY[1], 2);
+    });
+    let changes: Vec<_> = diff.iter().copied().collect();
+    assert_eq!(changes, [("MY_SCALAR", 0, 0x55)]);
+}
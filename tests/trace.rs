@@ -0,0 +1,62 @@
+//! Tests for the register access trace hook.
+
+#![cfg(feature = "trace")]
+#![allow(non_upper_case_globals, non_snake_case)] // Macro conventions.
+
+use ral_registers as ral;
+use std::sync::Mutex;
+
+mod periph {
+    #[repr(C)]
+    pub struct RegisterBlock {
+        pub MY_SCALAR: ral_registers::RWRegister<u32>,
+        pub MY_HALF: ral_registers::RWRegister<u16>,
+    }
+
+    pub mod MY_SCALAR {
+        pub mod FIELD_A {
+            pub const offset: u32 = 0;
+            pub const mask: u32 = 0x7F << offset;
+            pub mod R {}
+            pub mod W {}
+            pub mod RW {}
+        }
+    }
+}
+
+/// `(addr, width, value, is_write)` for each traced access.
+static ACCESSES: Mutex<Vec<(usize, u8, u64, bool)>> = Mutex::new(Vec::new());
+
+fn record(addr: usize, width: u8, value: u64, is_write: bool) {
+    ACCESSES
+        .lock()
+        .unwrap()
+        .push((addr, width, value, is_write));
+}
+
+/// The hook is global, so this is the only test in this file.
+#[test]
+fn trace() {
+    let rb = periph::RegisterBlock {
+        MY_SCALAR: ral::RWRegister::new(0xF0),
+        MY_HALF: ral::RWRegister::new(0x1234),
+    };
+    let scalar = rb.MY_SCALAR.as_ptr() as usize;
+    let half = rb.MY_HALF.as_ptr() as usize;
+
+    ral::trace::set_hook(record);
+    ral::modify_reg!(periph, &rb, MY_SCALAR, FIELD_A: 0x55);
+    ral::modify_reg!(periph, &rb, MY_HALF, |reg| reg + 1);
+    ral::trace::clear_hook();
+    rb.MY_SCALAR.write(0);
+
+    assert_eq!(
+        *ACCESSES.lock().unwrap(),
+        [
+            (scalar, 32, 0xF0, false),
+            (scalar, 32, 0xD5, true),
+            (half, 16, 0x1234, false),
+            (half, 16, 0x1235, true),
+        ]
+    );
+}
@@ -0,0 +1,13 @@
+//! Tests that misusing a register's access type, indexing a register array with an out of
+//! bounds literal, overlapping field masks, resetting an inconsistent field, mapping a register
+//! block at a misaligned constant address, or discarding a read with `unused_must_use` denied,
+//! is a compile error.
+//!
+//! Expected compiler output lives next to each case in `tests/ui`. After an intentional
+//! change to the diagnostics, regenerate it with `TRYBUILD=overwrite cargo test --test ui`.
+
+#[test]
+fn ui() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/ui/*.rs");
+}
@@ -0,0 +1,24 @@
+//! Two fields of one register whose masks share a bit.
+
+#![allow(non_upper_case_globals, non_snake_case)]
+
+mod periph {
+    pub mod MY_REG {
+        pub mod FIELD_A {
+            pub const offset: u32 = 0;
+            pub const mask: u32 = 0xF << offset;
+        }
+        pub mod FIELD_B {
+            pub const offset: u32 = 3;
+            pub const mask: u32 = 0b11 << offset;
+        }
+        pub mod FIELD_C {
+            pub const offset: u32 = 8;
+            pub const mask: u32 = 1 << offset;
+        }
+    }
+
+    ral_registers::assert_no_overlap!(self, MY_REG, [FIELD_A, FIELD_B, FIELD_C]);
+}
+
+fn main() {}
@@ -0,0 +1,28 @@
+//! Indexing a register array with an out of bounds literal.
+
+#![allow(non_upper_case_globals, non_snake_case, unused_imports)]
+
+use ral_registers as ral;
+
+mod periph {
+    #[repr(C)]
+    pub struct RegisterBlock {
+        pub MY_ARRAY: [ral_registers::RWRegister<u32>; 3],
+    }
+
+    pub mod MY_ARRAY {
+        pub mod FIELD {
+            pub const offset: u32 = 0;
+            pub const mask: u32 = 1 << offset;
+            pub mod R {}
+            pub mod W {}
+            pub mod RW {}
+        }
+    }
+}
+
+fn misuse(block: &periph::RegisterBlock) {
+    let _ = ral::read_reg!(periph, block, MY_ARRAY[5]);
+}
+
+fn main() {}
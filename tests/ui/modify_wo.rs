@@ -0,0 +1,33 @@
+//! Modifying a write-only register.
+
+#![allow(non_upper_case_globals, non_snake_case, unused_imports)]
+
+use ral_registers as ral;
+
+mod periph {
+    #[repr(C)]
+    pub struct RegisterBlock {
+        pub STATUS: ral_registers::RORegister<u32>,
+        pub COMMAND: ral_registers::WORegister<u32>,
+    }
+
+    pub mod STATUS {
+        pub mod FIELD {
+            pub const offset: u32 = 0;
+            pub const mask: u32 = 1 << offset;
+            pub mod R {}
+            pub mod W {}
+            pub mod RW {}
+        }
+    }
+
+    pub mod COMMAND {
+        pub use super::STATUS::*;
+    }
+}
+
+fn misuse(block: &periph::RegisterBlock) {
+    ral::modify_reg!(periph, block, COMMAND, FIELD: 1);
+}
+
+fn main() {}
@@ -0,0 +1,14 @@
+//! Mapping a register block at an address which is not aligned for it.
+
+#![allow(non_snake_case)]
+
+#[repr(C)]
+struct RegisterBlock {
+    CR: ral_registers::RWRegister<u32>,
+}
+
+fn misuse() -> &'static RegisterBlock {
+    unsafe { ral_registers::peripheral!(RegisterBlock, 0x4000_0002) }
+}
+
+fn main() {}
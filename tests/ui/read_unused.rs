@@ -0,0 +1,30 @@
+//! Discarding the value of a read.
+
+#![allow(non_upper_case_globals, non_snake_case)]
+#![deny(unused_must_use)]
+
+use ral_registers as ral;
+
+mod periph {
+    #[repr(C)]
+    pub struct RegisterBlock {
+        pub MY_SCALAR: ral_registers::RWRegister<u32>,
+    }
+
+    pub mod MY_SCALAR {
+        pub mod BUSY {
+            pub const offset: u32 = 0;
+            pub const mask: u32 = 1 << offset;
+            pub mod R {}
+            pub mod W {}
+            pub mod RW {}
+        }
+    }
+}
+
+fn misuse(block: &periph::RegisterBlock) {
+    block.MY_SCALAR.read();
+    ral::read_reg!(periph, block, MY_SCALAR, BUSY == 0);
+}
+
+fn main() {}
@@ -0,0 +1,35 @@
+//! Resetting a field whose mask has bits below its offset.
+
+#![allow(non_upper_case_globals, non_snake_case)]
+
+mod periph {
+    pub struct RegisterBlock {
+        pub MY_REG: ral_registers::RWRegister<u32>,
+    }
+
+    pub mod MY_REG {
+        pub mod FIELD_A {
+            // A generator bug: the mask was not shifted by the offset.
+            pub const offset: u32 = 4;
+            pub const mask: u32 = 0xFF;
+            pub mod R {}
+            pub mod W {}
+            pub mod RW {}
+        }
+    }
+
+    pub struct ResetValues {
+        pub MY_REG: u32,
+    }
+
+    pub mod INST {
+        pub const reset: super::ResetValues = super::ResetValues { MY_REG: 0x40 };
+    }
+}
+
+fn main() {
+    let rb = periph::RegisterBlock {
+        MY_REG: ral_registers::RWRegister::new(0),
+    };
+    ral_registers::reset_reg!(periph, &rb, INST, MY_REG, FIELD_A);
+}
@@ -0,0 +1,70 @@
+//! Tests that `write_reg!` makes exactly one volatile write, and that
+//! consecutive writes happen in program order, using the trace hook.
+
+#![cfg(feature = "trace")]
+#![allow(non_upper_case_globals, non_snake_case)] // Macro conventions.
+
+use ral_registers as ral;
+use std::sync::Mutex;
+
+mod periph {
+    #[repr(C)]
+    pub struct RegisterBlock {
+        pub MY_FIRST: ral_registers::RWRegister<u32>,
+        pub MY_SECOND: ral_registers::RWRegister<u32>,
+    }
+
+    pub mod MY_FIRST {
+        pub mod FIELD_A {
+            pub const offset: u32 = 0;
+            pub const mask: u32 = 0x7F << offset;
+            pub mod R {}
+            pub mod W {}
+            pub mod RW {}
+        }
+        pub mod FIELD_B {
+            pub const offset: u32 = 27;
+            pub const mask: u32 = 0b11 << offset;
+            pub mod R {}
+            pub mod W {}
+            pub mod RW {}
+        }
+    }
+
+    pub mod MY_SECOND {
+        pub use super::MY_FIRST::*;
+    }
+}
+
+/// `(addr, value, is_write)` for each traced access.
+static ACCESSES: Mutex<Vec<(usize, u64, bool)>> = Mutex::new(Vec::new());
+
+fn record(addr: usize, _: u8, value: u64, is_write: bool) {
+    ACCESSES.lock().unwrap().push((addr, value, is_write));
+}
+
+/// The hook is global, so this is the only test in this file.
+#[test]
+fn write_order() {
+    let rb = periph::RegisterBlock {
+        MY_FIRST: ral::RWRegister::new(0),
+        MY_SECOND: ral::RWRegister::new(0),
+    };
+    let first = rb.MY_FIRST.as_ptr() as usize;
+    let second = rb.MY_SECOND.as_ptr() as usize;
+
+    ral::trace::set_hook(record);
+    ral::write_reg!(periph, &rb, MY_FIRST, FIELD_A: 0x55, FIELD_B: 0b10);
+    ral::write_reg!(periph, &rb, MY_SECOND, FIELD_A: 1);
+    ral::write_reg!(periph, &rb, MY_FIRST, 7);
+    ral::trace::clear_hook();
+
+    assert_eq!(
+        *ACCESSES.lock().unwrap(),
+        [
+            (first, 0b10 << 27 | 0x55, true),
+            (second, 1, true),
+            (first, 7, true),
+        ]
+    );
+}
@@ -0,0 +1,94 @@
+//! Tests for the write-one-to-clear macros, with a register that simulates W1C hardware.
+
+#![allow(non_upper_case_globals, non_snake_case)] // Macro conventions.
+
+use ral_registers as ral;
+use std::cell::Cell;
+
+/// A status register whose flags are cleared by writing a 1.
+#[derive(Default)]
+pub struct W1CRegister {
+    flags: Cell<u32>,
+    accesses: Cell<(u32, u32)>,
+}
+
+impl W1CRegister {
+    pub fn read(&self) -> u32 {
+        let (reads, writes) = self.accesses.get();
+        self.accesses.set((reads + 1, writes));
+        self.flags.get()
+    }
+
+    pub fn write(&self, value: u32) {
+        let (reads, writes) = self.accesses.get();
+        self.accesses.set((reads, writes + 1));
+        self.flags.set(self.flags.get() & !value);
+    }
+
+    /// Sets flags, as the hardware would.
+    fn raise(&self, flags: u32) {
+        self.flags.set(self.flags.get() | flags);
+    }
+}
+
+mod periph {
+    #[derive(Default)]
+    pub struct RegisterBlock {
+        pub ISR: super::W1CRegister,
+        pub CHANNEL_ISR: [super::W1CRegister; 2],
+    }
+
+    pub mod ISR {
+        pub mod TC {
+            pub const offset: u32 = 0;
+            pub const mask: u32 = 1 << offset;
+            pub mod R {}
+            pub mod W {}
+            pub mod RW {}
+        }
+        pub mod ERR {
+            pub const offset: u32 = 4;
+            pub const mask: u32 = 0b111 << offset;
+            pub mod R {}
+            pub mod W {}
+            pub mod RW {}
+        }
+    }
+
+    pub mod CHANNEL_ISR {
+        pub use super::ISR::*;
+    }
+}
+
+#[test]
+fn read_clear() {
+    let rb = periph::RegisterBlock::default();
+    rb.ISR.raise(0b101 << 4 | 1);
+
+    let pending = ral::read_clear_reg!(periph, &rb, ISR);
+    assert_eq!(pending, 0b101 << 4 | 1);
+    assert_eq!(rb.ISR.flags.get(), 0);
+    assert_eq!(rb.ISR.accesses.get(), (1, 1));
+
+    assert_eq!(ral::read_clear_reg!(periph, &rb, ISR), 0);
+
+    rb.CHANNEL_ISR[1].raise(1);
+    assert_eq!(ral::read_clear_reg!(periph, &rb, CHANNEL_ISR[1]), 1);
+    assert_eq!(rb.CHANNEL_ISR[1].flags.get(), 0);
+}
+
+#[test]
+fn clear_flags() {
+    let rb = periph::RegisterBlock::default();
+    rb.ISR.raise(0b011 << 4 | 1);
+
+    ral::clear_flags!(periph, &rb, ISR, TC);
+    assert_eq!(rb.ISR.flags.get(), 0b011 << 4);
+    ral::clear_flags!(periph, &rb, ISR, ERR);
+    assert_eq!(rb.ISR.flags.get(), 0);
+    assert_eq!(rb.ISR.accesses.get(), (0, 2));
+
+    rb.CHANNEL_ISR[0].raise(0b111 << 4 | 1);
+    ral::clear_flags!(periph, &rb, CHANNEL_ISR[0], TC, ERR);
+    assert_eq!(rb.CHANNEL_ISR[0].flags.get(), 0);
+}